@@ -0,0 +1,458 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use serde::de::value::StrDeserializer;
+use serde::de::{
+    DeserializeOwned, DeserializeSeed, IntoDeserializer, MapAccess, SeqAccess, Visitor,
+};
+use serde::{forward_to_deserialize_any, Deserializer};
+use serde_json::Value;
+use sqlx::postgres::PgRow;
+use sqlx::{Column, Row};
+
+use crate::{SerColumn, SerializeOptions, DEFAULT_OPTIONS};
+
+/// 反序列化错误
+#[derive(Debug)]
+pub struct Error(String);
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl serde::de::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error(msg.to_string())
+    }
+}
+
+/// 将行数据反序列化成结构体
+/// > 列名对应字段名称，支持 `#[serde(rename)]`、`Option` 以及 JSONB 嵌套结构体
+/// ```rust,ignore
+/// #[derive(Deserialize)]
+/// struct Feature {
+///     id: String,
+///     #[serde(rename = "type_name")]
+///     kind: Option<String>,
+///     properties: Properties,
+/// }
+/// let feature: Feature = cts_pgrow::de::from_row(&row)?;
+/// ```
+pub fn from_row<T: DeserializeOwned>(row: &PgRow) -> Result<T, Error> {
+    T::deserialize(PgRowDeserializer::new(row))
+}
+
+/// 按序列化配置将行数据反序列化成结构体
+/// @param row 行数据
+/// @param options 序列化配置，自定义的列、类型格式在反序列化前生效
+pub fn from_row_with<T: DeserializeOwned>(
+    row: &PgRow,
+    options: &SerializeOptions,
+) -> Result<T, Error> {
+    T::deserialize(PgRowDeserializer::with_options(row, options))
+}
+
+/// 行数据反序列化器
+/// - 结构体、map 按列名读取
+/// - 元组、数组 按列顺序读取
+/// - 只有一列时可以直接反序列化成基础类型
+pub struct PgRowDeserializer<'a> {
+    row: &'a PgRow,
+    options: &'a SerializeOptions,
+}
+
+impl<'a> PgRowDeserializer<'a> {
+    pub fn new(row: &'a PgRow) -> Self {
+        Self {
+            row,
+            options: &DEFAULT_OPTIONS,
+        }
+    }
+
+    pub fn with_options(row: &'a PgRow, options: &'a SerializeOptions) -> Self {
+        Self { row, options }
+    }
+
+    /// 读取列值
+    fn value(&self, index: usize) -> Result<Value, Error> {
+        let column = &self.row.columns()[index];
+        let value = self
+            .row
+            .try_get_raw(index)
+            .map_err(|err| Error(err.to_string()))?;
+        serde_json::to_value(SerColumn {
+            value,
+            column: column.name(),
+            options: self.options,
+        })
+        .map_err(|err| Error(format!("column {}: {err}", column.name())))
+    }
+
+    /// 只有一列时，按列值反序列化
+    fn single(&self) -> Result<Value, Error> {
+        match self.row.columns().len() {
+            1 => self.value(0),
+            len => Err(Error(format!(
+                "expected a single column row, found {len} columns"
+            ))),
+        }
+    }
+}
+
+macro_rules! deserialize_single {
+    ($($method:ident)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                ColumnDeserializer(self.single()?).$method(visitor)
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for PgRowDeserializer<'_> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_map(visitor)
+    }
+
+    deserialize_single! {
+        deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+        deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+        deserialize_f32 deserialize_f64 deserialize_char deserialize_str deserialize_string
+        deserialize_bytes deserialize_byte_buf
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        // 行数据本身不为空
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_seq(RowSeqAccess { de: self, index: 0 })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_map(RowMapAccess { de: self, index: 0 })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_map(visitor)
+    }
+
+    forward_to_deserialize_any! {
+        i128 u128 unit unit_struct enum identifier ignored_any
+    }
+}
+
+/// 按列名读取
+struct RowMapAccess<'a> {
+    de: PgRowDeserializer<'a>,
+    index: usize,
+}
+
+impl<'de> MapAccess<'de> for RowMapAccess<'_> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        let columns = self.de.row.columns();
+        if self.index >= columns.len() {
+            return Ok(None);
+        }
+        let key: StrDeserializer<Error> = columns[self.index].name().into_deserializer();
+        seed.deserialize(key).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        let value = self.de.value(self.index)?;
+        self.index += 1;
+        seed.deserialize(ColumnDeserializer(value))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.de.row.columns().len() - self.index)
+    }
+}
+
+/// 按列顺序读取
+struct RowSeqAccess<'a> {
+    de: PgRowDeserializer<'a>,
+    index: usize,
+}
+
+impl<'de> SeqAccess<'de> for RowSeqAccess<'_> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        if self.index >= self.de.row.columns().len() {
+            return Ok(None);
+        }
+        let value = self.de.value(self.index)?;
+        self.index += 1;
+        seed.deserialize(ColumnDeserializer(value)).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.de.row.columns().len() - self.index)
+    }
+}
+
+/// 列值反序列化器
+/// > NUMERIC、MONEY 等类型序列化为字符串，数字类型的字段可以从数字字符串读取，
+/// > 整数字段可以读取小数部分为0的字符串，例如 NUMERIC(10,2) 的 `12.00`
+struct ColumnDeserializer(Value);
+
+macro_rules! deserialize_number {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                match self.0 {
+                    Value::String(data) => visitor.$visit(parse_number(&data)?),
+                    data => data.$method(visitor).map_err(|err| Error(err.to_string())),
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! deserialize_value {
+    ($($method:ident)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                self.0.$method(visitor).map_err(|err| Error(err.to_string()))
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for ColumnDeserializer {
+    type Error = Error;
+
+    deserialize_number! {
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+    }
+
+    deserialize_value! {
+        deserialize_any deserialize_bool deserialize_i128 deserialize_u128 deserialize_char
+        deserialize_str deserialize_string deserialize_bytes deserialize_byte_buf deserialize_unit
+        deserialize_seq deserialize_map deserialize_identifier deserialize_ignored_any
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.0 {
+            Value::Null => visitor.visit_none(),
+            data => visitor.visit_some(ColumnDeserializer(data)),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.0
+            .deserialize_unit_struct(name, visitor)
+            .map_err(|err| Error(err.to_string()))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.0
+            .deserialize_tuple(len, visitor)
+            .map_err(|err| Error(err.to_string()))
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.0
+            .deserialize_tuple_struct(name, len, visitor)
+            .map_err(|err| Error(err.to_string()))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.0
+            .deserialize_struct(name, fields, visitor)
+            .map_err(|err| Error(err.to_string()))
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.0
+            .deserialize_enum(name, variants, visitor)
+            .map_err(|err| Error(err.to_string()))
+    }
+}
+
+/// 解析数字字符串，小数部分全部为0时去掉后再解析，整数类型可以读取 `12.00`
+fn parse_number<T: FromStr>(data: &str) -> Result<T, Error> {
+    let data = data.trim();
+    if let Ok(value) = data.parse() {
+        return Ok(value);
+    }
+    match data.split_once('.') {
+        Some((integer, fraction)) if fraction.chars().all(|item| item == '0') => integer.parse(),
+        _ => data.parse(),
+    }
+    .map_err(|_| Error(format!("invalid number {data}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+    use sqlx::{Connection, PgConnection};
+
+    use super::*;
+
+    // 设置 DATABASE_URL 时才连接数据库测试，没有设置时跳过
+    async fn query(sql: &str) -> Option<PgRow> {
+        let url = std::env::var("DATABASE_URL").ok()?;
+        let mut conn = PgConnection::connect(&url).await.unwrap();
+        sqlx::query("create type pg_temp.mood as enum ('sad', 'ok')")
+            .execute(&mut conn)
+            .await
+            .unwrap();
+        Some(sqlx::query(sql).fetch_one(&mut conn).await.unwrap())
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    enum Mood {
+        Sad,
+        Ok,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Detail {
+        color: String,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Order {
+        id: i32,
+        amount: f64,
+        quantity: i64,
+        discount: Option<f64>,
+        name: String,
+        tags: Vec<String>,
+        paid: bool,
+        created_at: String,
+        mood: Mood,
+        detail: Detail,
+    }
+
+    #[tokio::test]
+    async fn test_from_row() {
+        let Some(row) = query(
+            "select 1 as id, 12.5::numeric as amount, 3.00::numeric(10, 2) as quantity, \
+             null::numeric as discount, 'a' as name, array['x', 'y'] as tags, true as paid, \
+             '2024-01-01T00:00:00Z'::timestamptz as created_at, 'ok'::pg_temp.mood as mood, \
+             '{\"color\": \"red\"}'::jsonb as detail",
+        )
+        .await
+        else {
+            return;
+        };
+        let order: Order = from_row(&row).unwrap();
+        assert_eq!(
+            order,
+            Order {
+                id: 1,
+                amount: 12.5,
+                quantity: 3,
+                discount: None,
+                name: "a".to_string(),
+                tags: vec!["x".to_string(), "y".to_string()],
+                paid: true,
+                created_at: "2024-01-01T00:00:00+00:00".to_string(),
+                mood: Mood::Ok,
+                detail: Detail {
+                    color: "red".to_string()
+                },
+            }
+        );
+        let (id, amount): (i64, Option<f64>) = from_row(&row).unwrap();
+        assert_eq!((id, amount), (1, Some(12.5)));
+
+        // 只有一列时直接读取列值
+        let row = query("select 1.25::numeric as amount").await.unwrap();
+        assert_eq!(from_row::<f64>(&row).unwrap(), 1.25);
+        let row = query("select 1.25::numeric as amount").await.unwrap();
+        assert!(from_row::<i64>(&row).is_err());
+    }
+}
@@ -0,0 +1,279 @@
+use std::fmt::Write;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use serde::{Serialize, Serializer};
+use sqlx::error::BoxDynError;
+use sqlx::postgres::types::{PgInterval, PgMoney};
+use sqlx::postgres::{PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
+use sqlx::{Decode, Type};
+
+/// NUMERIC 字符串，不依赖 decimal 特性，按原始精度输出
+pub struct Numeric(pub String);
+
+/// INET / CIDR 字符串，例如 `192.168.0.1/24`
+pub struct Inet(pub String);
+
+/// MACADDR / MACADDR8 字符串，例如 `08:00:2b:01:02:03`
+pub struct MacAddr(pub String);
+
+macro_rules! string_type {
+    ($ty:ident, $name:literal, $decode:ident) => {
+        impl Type<Postgres> for $ty {
+            fn type_info() -> PgTypeInfo {
+                PgTypeInfo::with_name($name)
+            }
+        }
+
+        impl<'r> Decode<'r, Postgres> for $ty {
+            fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+                match value.format() {
+                    PgValueFormat::Text => Ok($ty(value.as_str()?.to_string())),
+                    PgValueFormat::Binary => Ok($ty($decode(value.as_bytes()?)?)),
+                }
+            }
+        }
+
+        impl Serialize for $ty {
+            fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+                s.serialize_str(&self.0)
+            }
+        }
+    };
+}
+
+string_type!(Numeric, "numeric", numeric_to_string);
+string_type!(Inet, "inet", inet_to_string);
+string_type!(MacAddr, "macaddr", macaddr_to_string);
+
+/// 解析二进制 NUMERIC
+/// > ndigits(i16) weight(i16) sign(u16) dscale(u16) digits(i16 * ndigits)，每个 digit 为万进制
+pub fn numeric_to_string(buf: &[u8]) -> Result<String, BoxDynError> {
+    if buf.len() < 8 {
+        return Err("invalid NUMERIC value".into());
+    }
+    let ndigits = i16::from_be_bytes([buf[0], buf[1]]);
+    let weight = i16::from_be_bytes([buf[2], buf[3]]) as i32;
+    let sign = u16::from_be_bytes([buf[4], buf[5]]);
+    let dscale = u16::from_be_bytes([buf[6], buf[7]]) as usize;
+    match sign {
+        0xC000 => return Ok("NaN".to_string()),
+        0xD000 => return Ok("Infinity".to_string()),
+        0xF000 => return Ok("-Infinity".to_string()),
+        _ => {}
+    }
+    if buf.len() < 8 + ndigits.max(0) as usize * 2 {
+        return Err("invalid NUMERIC value".into());
+    }
+    let digits: Vec<i16> = buf[8..8 + ndigits.max(0) as usize * 2]
+        .chunks_exact(2)
+        .map(|c| i16::from_be_bytes([c[0], c[1]]))
+        .collect();
+    let digit = |index: i32| -> i16 {
+        if index < 0 {
+            0
+        } else {
+            digits.get(index as usize).copied().unwrap_or(0)
+        }
+    };
+    let mut result = String::new();
+    if sign == 0x4000 {
+        result.push('-');
+    }
+    // 整数部分
+    if weight < 0 {
+        result.push('0');
+    } else {
+        for index in 0..=weight {
+            if index == 0 {
+                write!(result, "{}", digit(index))?;
+            } else {
+                write!(result, "{:04}", digit(index))?;
+            }
+        }
+    }
+    // 小数部分
+    if dscale > 0 {
+        let mut fraction = String::new();
+        let mut index = weight + 1;
+        while fraction.len() < dscale {
+            write!(fraction, "{:04}", digit(index))?;
+            index += 1;
+        }
+        fraction.truncate(dscale);
+        result.push('.');
+        result.push_str(&fraction);
+    }
+    Ok(result)
+}
+
+/// 解析二进制 INET / CIDR
+/// > family(u8) bits(u8) is_cidr(u8) nb(u8) address
+pub fn inet_to_string(buf: &[u8]) -> Result<String, BoxDynError> {
+    if buf.len() < 4 {
+        return Err("invalid INET value".into());
+    }
+    let bits = buf[1];
+    let is_cidr = buf[2] == 1;
+    let address = &buf[4..];
+    let (ip, max_bits) = match (buf[0], address.len()) {
+        // AF_INET
+        (2, 4) => (
+            IpAddr::V4(Ipv4Addr::new(
+                address[0], address[1], address[2], address[3],
+            )),
+            32,
+        ),
+        // AF_INET6
+        (3, 16) => {
+            let mut octets = [0u8; 16];
+            octets.copy_from_slice(address);
+            (IpAddr::V6(Ipv6Addr::from(octets)), 128)
+        }
+        _ => return Err("invalid INET value".into()),
+    };
+    if is_cidr || bits != max_bits {
+        Ok(format!("{ip}/{bits}"))
+    } else {
+        Ok(ip.to_string())
+    }
+}
+
+/// 解析二进制 MACADDR / MACADDR8
+pub fn macaddr_to_string(buf: &[u8]) -> Result<String, BoxDynError> {
+    if buf.len() != 6 && buf.len() != 8 {
+        return Err("invalid MACADDR value".into());
+    }
+    let parts: Vec<String> = buf.iter().map(|b| format!("{b:02x}")).collect();
+    Ok(parts.join(":"))
+}
+
+/// 将 INTERVAL 转换成 ISO 8601 持续时间字符串，例如 `P1Y2M3DT4H5M6.5S`
+pub fn interval_to_string(interval: &PgInterval) -> String {
+    let years = interval.months / 12;
+    let months = interval.months % 12;
+    let days = interval.days;
+    let hours = interval.microseconds / 3_600_000_000;
+    let minutes = interval.microseconds % 3_600_000_000 / 60_000_000;
+    let micros = interval.microseconds % 60_000_000;
+
+    let mut result = String::from("P");
+    if years != 0 {
+        let _ = write!(result, "{years}Y");
+    }
+    if months != 0 {
+        let _ = write!(result, "{months}M");
+    }
+    if days != 0 {
+        let _ = write!(result, "{days}D");
+    }
+    if hours != 0 || minutes != 0 || micros != 0 {
+        result.push('T');
+        if hours != 0 {
+            let _ = write!(result, "{hours}H");
+        }
+        if minutes != 0 {
+            let _ = write!(result, "{minutes}M");
+        }
+        if micros != 0 {
+            let sign = if micros < 0 { "-" } else { "" };
+            let micros = micros.abs();
+            let mut seconds = format!("{sign}{}.{:06}", micros / 1_000_000, micros % 1_000_000);
+            // 去掉多余的0
+            let trimmed = seconds.trim_end_matches('0').trim_end_matches('.').len();
+            seconds.truncate(trimmed);
+            let _ = write!(result, "{seconds}S");
+        }
+    }
+    if result.len() == 1 {
+        result.push_str("T0S");
+    }
+    result
+}
+
+/// 将 MONEY 转换成两位小数的字符串
+/// > 二进制协议只返回以最小货币单位计的整数，小数位数由数据库的 lc_monetary 决定，
+/// > 这里固定按两位小数处理，lc_monetary 不是两位小数的货币(例如日元)时需要在sql中转换成 numeric
+pub fn money_to_string(money: &PgMoney) -> String {
+    let sign = if money.0 < 0 { "-" } else { "" };
+    let value = money.0.unsigned_abs();
+    format!("{sign}{}.{:02}", value / 100, value % 100)
+}
+
+/// 无法识别类型的字节转换成字符串，非 UTF-8 数据按 `\x` 十六进制输出
+pub fn bytes_to_string(buf: &[u8]) -> String {
+    match std::str::from_utf8(buf) {
+        Ok(data) => data.to_string(),
+        Err(_) => {
+            let mut result = String::from("\\x");
+            for b in buf {
+                let _ = write!(result, "{b:02x}");
+            }
+            result
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numeric(ndigits: i16, weight: i16, sign: u16, dscale: u16, digits: &[i16]) -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&ndigits.to_be_bytes());
+        buf.extend_from_slice(&weight.to_be_bytes());
+        buf.extend_from_slice(&sign.to_be_bytes());
+        buf.extend_from_slice(&dscale.to_be_bytes());
+        for d in digits {
+            buf.extend_from_slice(&d.to_be_bytes());
+        }
+        buf
+    }
+
+    #[test]
+    fn test_numeric() {
+        // 12345.678
+        let buf = numeric(3, 1, 0, 3, &[1, 2345, 6780]);
+        assert_eq!(numeric_to_string(&buf).unwrap(), "12345.678");
+        // -0.0012
+        let buf = numeric(1, -1, 0x4000, 4, &[12]);
+        assert_eq!(numeric_to_string(&buf).unwrap(), "-0.0012");
+        // 10000
+        let buf = numeric(1, 1, 0, 0, &[1]);
+        assert_eq!(numeric_to_string(&buf).unwrap(), "10000");
+        let buf = numeric(0, 0, 0xC000, 0, &[]);
+        assert_eq!(numeric_to_string(&buf).unwrap(), "NaN");
+    }
+
+    #[test]
+    fn test_inet() {
+        assert_eq!(
+            inet_to_string(&[2, 32, 0, 4, 192, 168, 0, 1]).unwrap(),
+            "192.168.0.1"
+        );
+        assert_eq!(
+            inet_to_string(&[2, 24, 1, 4, 10, 0, 0, 0]).unwrap(),
+            "10.0.0.0/24"
+        );
+        assert_eq!(
+            macaddr_to_string(&[8, 0, 0x2b, 1, 2, 3]).unwrap(),
+            "08:00:2b:01:02:03"
+        );
+    }
+
+    #[test]
+    fn test_interval() {
+        let interval = PgInterval {
+            months: 14,
+            days: 3,
+            microseconds: 4 * 3_600_000_000 + 5 * 60_000_000 + 6_500_000,
+        };
+        assert_eq!(interval_to_string(&interval), "P1Y2M3DT4H5M6.5S");
+        let interval = PgInterval {
+            months: 0,
+            days: 0,
+            microseconds: 0,
+        };
+        assert_eq!(interval_to_string(&interval), "PT0S");
+        assert_eq!(money_to_string(&PgMoney(-12345)), "-123.45");
+    }
+}
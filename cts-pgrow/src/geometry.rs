@@ -0,0 +1,184 @@
+use serde_json::{json, Value};
+use sqlx::error::BoxDynError;
+
+/// 解析 PostGIS (E)WKB 数据并转换成 GeoJSON geometry 对象
+/// > 支持 Point、LineString、Polygon、Multi* 以及 GeometryCollection，Z/M 维度只保留 Z
+pub fn wkb_to_geojson(buf: &[u8]) -> Result<Value, BoxDynError> {
+    let mut reader = WkbReader { buf, pos: 0 };
+    reader.read_geometry()
+}
+
+struct WkbReader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+/// 几何对象头信息
+struct Header {
+    little_endian: bool,
+    geometry_type: u32,
+    has_z: bool,
+    has_m: bool,
+}
+
+impl WkbReader<'_> {
+    fn take(&mut self, len: usize) -> Result<&[u8], BoxDynError> {
+        let end = self.pos + len;
+        if end > self.buf.len() {
+            return Err("invalid WKB value".into());
+        }
+        let data = &self.buf[self.pos..end];
+        self.pos = end;
+        Ok(data)
+    }
+
+    fn read_u32(&mut self, little_endian: bool) -> Result<u32, BoxDynError> {
+        let data: [u8; 4] = self.take(4)?.try_into()?;
+        Ok(if little_endian {
+            u32::from_le_bytes(data)
+        } else {
+            u32::from_be_bytes(data)
+        })
+    }
+
+    fn read_f64(&mut self, little_endian: bool) -> Result<f64, BoxDynError> {
+        let data: [u8; 8] = self.take(8)?.try_into()?;
+        Ok(if little_endian {
+            f64::from_le_bytes(data)
+        } else {
+            f64::from_be_bytes(data)
+        })
+    }
+
+    fn read_header(&mut self) -> Result<Header, BoxDynError> {
+        let little_endian = self.take(1)?[0] == 1;
+        let raw = self.read_u32(little_endian)?;
+        // EWKB 标记位
+        let mut has_z = raw & 0x8000_0000 != 0;
+        let mut has_m = raw & 0x4000_0000 != 0;
+        if raw & 0x2000_0000 != 0 {
+            // 跳过 SRID
+            self.read_u32(little_endian)?;
+        }
+        let mut geometry_type = raw & 0x0FFF_FFFF;
+        // ISO WKB 维度编码
+        match geometry_type / 1000 {
+            1 => has_z = true,
+            2 => has_m = true,
+            3 => {
+                has_z = true;
+                has_m = true;
+            }
+            _ => {}
+        }
+        geometry_type %= 1000;
+        Ok(Header {
+            little_endian,
+            geometry_type,
+            has_z,
+            has_m,
+        })
+    }
+
+    fn read_point(&mut self, header: &Header) -> Result<Vec<f64>, BoxDynError> {
+        let x = self.read_f64(header.little_endian)?;
+        let y = self.read_f64(header.little_endian)?;
+        let mut point = vec![x, y];
+        if header.has_z {
+            point.push(self.read_f64(header.little_endian)?);
+        }
+        if header.has_m {
+            self.read_f64(header.little_endian)?;
+        }
+        Ok(point)
+    }
+
+    fn read_points(&mut self, header: &Header) -> Result<Vec<Vec<f64>>, BoxDynError> {
+        let count = self.read_u32(header.little_endian)?;
+        let mut points = Vec::new();
+        for _ in 0..count {
+            points.push(self.read_point(header)?);
+        }
+        Ok(points)
+    }
+
+    fn read_rings(&mut self, header: &Header) -> Result<Vec<Vec<Vec<f64>>>, BoxDynError> {
+        let count = self.read_u32(header.little_endian)?;
+        let mut rings = Vec::new();
+        for _ in 0..count {
+            rings.push(self.read_points(header)?);
+        }
+        Ok(rings)
+    }
+
+    fn read_geometry(&mut self) -> Result<Value, BoxDynError> {
+        let header = self.read_header()?;
+        let geometry = match header.geometry_type {
+            1 => {
+                let point = self.read_point(&header)?;
+                // 空点使用 NaN 表示
+                if point.iter().all(|v| v.is_nan()) {
+                    json!({"type": "Point", "coordinates": []})
+                } else {
+                    json!({"type": "Point", "coordinates": point})
+                }
+            }
+            2 => json!({"type": "LineString", "coordinates": self.read_points(&header)?}),
+            3 => json!({"type": "Polygon", "coordinates": self.read_rings(&header)?}),
+            4..=7 => {
+                let count = self.read_u32(header.little_endian)?;
+                let mut children = Vec::new();
+                for _ in 0..count {
+                    children.push(self.read_geometry()?);
+                }
+                if header.geometry_type == 7 {
+                    json!({"type": "GeometryCollection", "geometries": children})
+                } else {
+                    let name = match header.geometry_type {
+                        4 => "MultiPoint",
+                        5 => "MultiLineString",
+                        _ => "MultiPolygon",
+                    };
+                    let coordinates: Vec<Value> = children
+                        .into_iter()
+                        .map(|mut child| child["coordinates"].take())
+                        .collect();
+                    json!({"type": name, "coordinates": coordinates})
+                }
+            }
+            other => return Err(format!("unsupported WKB geometry type {other}").into()),
+        };
+        Ok(geometry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_point() {
+        // SRID=4326;POINT(120 30)
+        let buf = hex::decode("0101000020E61000000000000000005E400000000000003E40").unwrap();
+        let value = wkb_to_geojson(&buf).unwrap();
+        assert_eq!(
+            value,
+            json!({"type": "Point", "coordinates": [120.0, 30.0]})
+        );
+    }
+
+    #[test]
+    fn test_multi_line() {
+        // MULTILINESTRING((0 0,1 1))
+        let buf = hex::decode(
+            "010500000001000000010200000002000000000000000000000000000000000000000000000000\
+             00F03F000000000000F03F",
+        )
+        .unwrap();
+        let value = wkb_to_geojson(&buf).unwrap();
+        assert_eq!(
+            value,
+            json!({"type": "MultiLineString", "coordinates": [[[0.0, 0.0], [1.0, 1.0]]]})
+        );
+    }
+}
@@ -1,9 +1,40 @@
+pub mod de;
+mod decode;
+mod geometry;
+pub mod options;
+
+use std::ops::Bound;
+use std::sync::{Arc, LazyLock};
+
 use serde_json::Value;
-use sqlx::postgres::{PgRow, PgValueRef, Postgres};
-use sqlx::{Column, Decode, Row, TypeInfo, ValueRef};
+use sqlx::postgres::types::{PgInterval, PgMoney, PgRange};
+use sqlx::postgres::{PgRow, PgTypeInfo, PgTypeKind, PgValueRef, Postgres};
+use sqlx::{Column, Decode, Row, Type, TypeInfo, ValueRef};
+
+use serde::ser::{Error, SerializeMap, SerializeSeq};
+use serde::{Deserialize, Serialize, Serializer};
+
+pub use crate::de::{from_row, from_row_with};
+use crate::decode::{bytes_to_string, interval_to_string, money_to_string, Inet, MacAddr, Numeric};
+pub use crate::geometry::wkb_to_geojson;
+pub use crate::options::SerializeOptions;
+
+/// 默认序列化配置
+pub(crate) static DEFAULT_OPTIONS: LazyLock<Arc<SerializeOptions>> =
+    LazyLock::new(|| Arc::new(SerializeOptions::default()));
 
-use serde::ser::{SerializeMap, SerializeSeq};
-use serde::{Serialize, Serializer};
+/// 无法识别的数据库类型处理方式
+/// - String 按字符串输出（默认）
+/// - Null 输出null
+/// - Error 返回序列化错误
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Fallback {
+    #[default]
+    String,
+    Null,
+    Error,
+}
 
 pub fn read_header(row: &PgRow) -> Vec<String> {
     let columns = row.columns();
@@ -14,20 +45,48 @@ pub fn read_header(row: &PgRow) -> Vec<String> {
     headers
 }
 
-pub fn read_row(row: &PgRow) -> Vec<Value> {
+pub fn read_row(row: &PgRow) -> Result<Vec<Value>, serde_json::Error> {
     let columns = row.columns();
     let mut result: Vec<Value> = Vec::with_capacity(columns.len());
     for c in columns {
-        let value = row.try_get_raw(c.ordinal()).unwrap();
-        let value = SerPgValueRef(value);
-        let value = serde_json::to_value(&value).unwrap();
+        let value = row
+            .try_get_raw(c.ordinal())
+            .map_err(serde_json::Error::custom)?;
+        let value = SerPgValueRef::from(value);
+        let value = serde_json::to_value(&value)?;
         result.push(value);
     }
-    result
+    Ok(result)
 }
 
 /// Can be used with serialize_with
 pub fn serialize_pg_value_ref<S>(value: &PgValueRef, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serialize_pg_value_ref_with(value, Fallback::default(), s)
+}
+
+/// 序列化数据库值，解析失败时返回序列化错误，timestamptz 输出UTC时间
+/// @param value 数据库值
+/// @param fallback 无法识别类型的处理方式
+pub fn serialize_pg_value_ref_with<S>(
+    value: &PgValueRef,
+    fallback: Fallback,
+    s: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serialize_value(value, &SerializeOptions::new().with_fallback(fallback), s)
+}
+
+/// 按类型序列化数据库值，使用配置中的无法识别类型处理方式以及时区
+fn serialize_value<S>(
+    value: &PgValueRef,
+    options: &SerializeOptions,
+    s: S,
+) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
 {
@@ -35,131 +94,432 @@ where
         return s.serialize_none();
     }
     let value = value.clone();
-    let info = value.type_info();
-    let name = info.name();
+    let info = value.type_info().into_owned();
+    let name = type_name(&info);
     match name {
         "BOOL" => {
-            let v: bool = Decode::<Postgres>::decode(value).unwrap();
+            let v: bool = decode(value)?;
             s.serialize_bool(v)
         }
         "INT2" => {
-            let v: i16 = Decode::<Postgres>::decode(value).unwrap();
+            let v: i16 = decode(value)?;
             s.serialize_i16(v)
         }
         "INT4" => {
-            let v: i32 = Decode::<Postgres>::decode(value).unwrap();
+            let v: i32 = decode(value)?;
             s.serialize_i32(v)
         }
         "INT8" => {
-            let v: i64 = Decode::<Postgres>::decode(value).unwrap();
+            let v: i64 = decode(value)?;
             s.serialize_i64(v)
         }
+        "OID" => {
+            let v: sqlx::postgres::types::Oid = decode(value)?;
+            s.serialize_u32(v.0)
+        }
         "FLOAT4" => {
-            let v: f32 = Decode::<Postgres>::decode(value).unwrap();
+            let v: f32 = decode(value)?;
             s.serialize_f32(v)
         }
         "FLOAT8" => {
-            let v: f64 = Decode::<Postgres>::decode(value).unwrap();
+            let v: f64 = decode(value)?;
             s.serialize_f64(v)
         }
         #[cfg(feature = "decimal")]
         "NUMERIC" => {
-            let v: sqlx::types::Decimal = Decode::<Postgres>::decode(value).unwrap();
+            let v: sqlx::types::Decimal = decode(value)?;
             s.serialize_str(&v.to_string())
         }
-        "CHAR" | "VARCHAR" | "TEXT" | "\"CHAR\"" => {
-            let v: String = Decode::<Postgres>::decode(value).unwrap();
+        #[cfg(not(feature = "decimal"))]
+        "NUMERIC" => {
+            let v: Numeric = decode(value)?;
+            v.serialize(s)
+        }
+        // 文本协议直接输出数据库格式化后的金额，二进制协议按两位小数输出
+        "MONEY" if matches!(value.format(), sqlx::postgres::PgValueFormat::Text) => {
+            let v: &str = decode(value)?;
+            s.serialize_str(v)
+        }
+        "MONEY" => {
+            let v: PgMoney = decode(value)?;
+            s.serialize_str(&money_to_string(&v))
+        }
+        "CHAR" | "VARCHAR" | "TEXT" | "\"CHAR\"" | "NAME" | "CITEXT" | "JSONPATH" | "XML" => {
+            let v: String = decode(value)?;
             s.serialize_str(&v)
         }
         "BYTEA" => {
-            let v: Vec<u8> = Decode::<Postgres>::decode(value).unwrap();
+            let v: Vec<u8> = decode(value)?;
             s.serialize_some(&v)
         }
         "JSON" | "JSONB" => {
-            let v: Value = Decode::<Postgres>::decode(value).unwrap();
+            let v: Value = decode(value)?;
             s.serialize_some(&v)
         }
+        "INET" | "CIDR" => {
+            let v: Inet = decode(value)?;
+            v.serialize(s)
+        }
+        "MACADDR" | "MACADDR8" => {
+            let v: MacAddr = decode(value)?;
+            v.serialize(s)
+        }
+        "INTERVAL" => match value.format() {
+            sqlx::postgres::PgValueFormat::Text => {
+                let v: String = decode(value)?;
+                s.serialize_str(&v)
+            }
+            sqlx::postgres::PgValueFormat::Binary => {
+                let v: PgInterval = decode(value)?;
+                s.serialize_str(&interval_to_string(&v))
+            }
+        },
         #[cfg(feature = "chrono")]
         "TIMESTAMP" => {
-            let v: chrono::NaiveDateTime = Decode::<Postgres>::decode(value).unwrap();
-            let v = v.format("%Y-%m-%dT%H:%M:%S.%f").to_string();
-            s.serialize_str(&v)
+            let v: chrono::NaiveDateTime = decode(value)?;
+            s.serialize_str(&format_timestamp(&v))
         }
         #[cfg(feature = "chrono")]
         "TIMESTAMPTZ" => {
-            let v: chrono::DateTime<chrono::Utc> = Decode::<Postgres>::decode(value).unwrap();
-            s.serialize_str(&v.to_rfc3339())
+            // 二进制协议不包含时区，统一按配置的时区输出，没有配置时输出UTC时间
+            let v: chrono::DateTime<chrono::Utc> = decode(value)?;
+            s.serialize_str(&format_timestamptz(&v, options))
         }
         #[cfg(feature = "chrono")]
         "DATE" => {
-            let v: chrono::NaiveDate = Decode::<Postgres>::decode(value).unwrap();
+            let v: chrono::NaiveDate = decode(value)?;
             s.serialize_str(&v.to_string())
         }
         #[cfg(feature = "chrono")]
         "TIME" => {
-            let v: chrono::NaiveTime = Decode::<Postgres>::decode(value).unwrap();
+            let v: chrono::NaiveTime = decode(value)?;
             s.serialize_str(&v.to_string())
         }
+        #[cfg(feature = "chrono")]
+        "TIMETZ" => {
+            let v: sqlx::postgres::types::PgTimeTz<chrono::NaiveTime, chrono::FixedOffset> =
+                decode(value)?;
+            s.serialize_str(&format!("{}{}", v.time, v.offset))
+        }
         #[cfg(feature = "uuid")]
         "UUID" => {
-            let v: sqlx::types::Uuid = Decode::<Postgres>::decode(value).unwrap();
+            let v: sqlx::types::Uuid = decode(value)?;
             let v = v.to_string();
             s.serialize_str(&v)
         }
-        "geometry" => {
-            let v: Vec<u8> = Decode::<Postgres>::decode(value).unwrap();
+        "geometry" | "geography" => {
+            let v: Vec<u8> = decode(value)?;
             s.serialize_some(&v)
         }
-        _ => {
-            let error_message = format!("Failed to deserialize postgres type {} as string", name);
-            let v: String = Decode::<Postgres>::decode(value).expect(&error_message);
-            s.serialize_str(&v)
+        // 数组类型
+        "BOOL[]" => serialize_array::<bool, _, _>(value, s, |v| v),
+        "INT2[]" => serialize_array::<i16, _, _>(value, s, |v| v),
+        "INT4[]" => serialize_array::<i32, _, _>(value, s, |v| v),
+        "INT8[]" => serialize_array::<i64, _, _>(value, s, |v| v),
+        "FLOAT4[]" => serialize_array::<f32, _, _>(value, s, |v| v),
+        "FLOAT8[]" => serialize_array::<f64, _, _>(value, s, |v| v),
+        "NUMERIC[]" => serialize_array::<Numeric, _, _>(value, s, |v| v),
+        "TEXT[]" | "VARCHAR[]" | "CHAR[]" | "NAME[]" | "\"CHAR\"[]" => {
+            serialize_array::<String, _, _>(value, s, |v| v)
+        }
+        "JSON[]" | "JSONB[]" => serialize_array::<Value, _, _>(value, s, |v| v),
+        "INET[]" | "CIDR[]" => serialize_array::<Inet, _, _>(value, s, |v| v),
+        "MACADDR[]" | "MACADDR8[]" => serialize_array::<MacAddr, _, _>(value, s, |v| v),
+        "INTERVAL[]" => serialize_array::<PgInterval, _, _>(value, s, |v| interval_to_string(&v)),
+        "MONEY[]" => serialize_array::<PgMoney, _, _>(value, s, |v| money_to_string(&v)),
+        #[cfg(feature = "chrono")]
+        "TIMESTAMP[]" => {
+            serialize_array::<chrono::NaiveDateTime, _, _>(value, s, |v| format_timestamp(&v))
+        }
+        #[cfg(feature = "chrono")]
+        "TIMESTAMPTZ[]" => serialize_array::<chrono::DateTime<chrono::Utc>, _, _>(value, s, |v| {
+            format_timestamptz(&v, options)
+        }),
+        #[cfg(feature = "chrono")]
+        "DATE[]" => serialize_array::<chrono::NaiveDate, _, _>(value, s, |v| v.to_string()),
+        #[cfg(feature = "chrono")]
+        "TIME[]" => serialize_array::<chrono::NaiveTime, _, _>(value, s, |v| v.to_string()),
+        #[cfg(feature = "uuid")]
+        "UUID[]" => serialize_array::<sqlx::types::Uuid, _, _>(value, s, |v| v.to_string()),
+        // 范围类型
+        "INT4RANGE" => serialize_range::<i32, _, _>(value, s, |v| v),
+        "INT8RANGE" => serialize_range::<i64, _, _>(value, s, |v| v),
+        "NUMRANGE" => serialize_range::<Numeric, _, _>(value, s, |v| v),
+        #[cfg(feature = "chrono")]
+        "TSRANGE" => {
+            serialize_range::<chrono::NaiveDateTime, _, _>(value, s, |v| format_timestamp(&v))
+        }
+        #[cfg(feature = "chrono")]
+        "TSTZRANGE" => serialize_range::<chrono::DateTime<chrono::Utc>, _, _>(value, s, |v| {
+            format_timestamptz(&v, options)
+        }),
+        #[cfg(feature = "chrono")]
+        "DATERANGE" => serialize_range::<chrono::NaiveDate, _, _>(value, s, |v| v.to_string()),
+        _ => match info.kind() {
+            // 自定义枚举按字符串输出
+            PgTypeKind::Enum(_) => {
+                let v: String = decode(value)?;
+                s.serialize_str(&v)
+            }
+            // 枚举数组
+            PgTypeKind::Array(element) if matches!(element.kind(), PgTypeKind::Enum(_)) => {
+                serialize_array::<String, _, _>(value, s, |v| v)
+            }
+            _ => serialize_fallback(value, name, options.fallback, s),
+        },
+    }
+}
+
+/// 获取类型名称，domain类型使用基础类型名称
+fn type_name(info: &PgTypeInfo) -> &str {
+    match info.kind() {
+        PgTypeKind::Domain(base) => type_name(base),
+        _ => info.name(),
+    }
+}
+
+fn decode<'r, T, E>(value: PgValueRef<'r>) -> Result<T, E>
+where
+    T: Decode<'r, Postgres>,
+    E: Error,
+{
+    // 内置类型的类型信息复制不需要分配内存，类型名称只在出错时使用
+    let info = value.type_info().into_owned();
+    T::decode(value).map_err(|err| {
+        E::custom(format!(
+            "failed to decode postgres type {}: {err}",
+            info.name()
+        ))
+    })
+}
+
+#[cfg(feature = "chrono")]
+fn format_timestamp(v: &chrono::NaiveDateTime) -> String {
+    v.format("%Y-%m-%dT%H:%M:%S.%f").to_string()
+}
+
+#[cfg(feature = "chrono")]
+fn format_timestamptz(v: &chrono::DateTime<chrono::Utc>, options: &SerializeOptions) -> String {
+    match options.timezone {
+        None => v.to_rfc3339(),
+        Some(timezone) => v.with_timezone(&timezone).to_rfc3339(),
+    }
+}
+
+/// 序列化数组类型，数组元素可以为null
+fn serialize_array<'r, T, U, S>(
+    value: PgValueRef<'r>,
+    s: S,
+    func: impl Fn(T) -> U,
+) -> Result<S::Ok, S::Error>
+where
+    T: for<'a> Decode<'a, Postgres> + Type<Postgres>,
+    U: Serialize,
+    S: Serializer,
+{
+    let v: Vec<Option<T>> = decode(value)?;
+    let v: Vec<Option<U>> = v.into_iter().map(|item| item.map(&func)).collect();
+    s.serialize_some(&v)
+}
+
+/// 序列化范围类型
+/// ```json
+/// {"lower": 1, "upper": 10, "lowerInclusive": true, "upperInclusive": false}
+/// ```
+fn serialize_range<'r, T, U, S>(
+    value: PgValueRef<'r>,
+    s: S,
+    func: impl Fn(T) -> U,
+) -> Result<S::Ok, S::Error>
+where
+    T: for<'a> Decode<'a, Postgres> + Type<Postgres>,
+    U: Serialize,
+    S: Serializer,
+{
+    let v: PgRange<T> = decode(value)?;
+    let (lower, lower_inclusive) = split_bound(v.start.map(&func));
+    let (upper, upper_inclusive) = split_bound(v.end.map(&func));
+    let mut map = s.serialize_map(Some(4))?;
+    map.serialize_entry("lower", &lower)?;
+    map.serialize_entry("upper", &upper)?;
+    map.serialize_entry("lowerInclusive", &lower_inclusive)?;
+    map.serialize_entry("upperInclusive", &upper_inclusive)?;
+    map.end()
+}
+
+fn split_bound<T>(bound: Bound<T>) -> (Option<T>, bool) {
+    match bound {
+        Bound::Included(v) => (Some(v), true),
+        Bound::Excluded(v) => (Some(v), false),
+        Bound::Unbounded => (None, false),
+    }
+}
+
+/// 无法识别类型的处理
+fn serialize_fallback<S>(
+    value: PgValueRef,
+    name: &str,
+    fallback: Fallback,
+    s: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match fallback {
+        Fallback::String => {
+            let bytes = value.as_bytes().map_err(S::Error::custom)?;
+            s.serialize_str(&bytes_to_string(bytes))
+        }
+        Fallback::Null => s.serialize_none(),
+        Fallback::Error => Err(S::Error::custom(format!(
+            "unsupported postgres type {name}"
+        ))),
+    }
+}
+
+/// 按配置序列化数据库值，先查找自定义序列化函数，没有时使用默认序列化
+/// @param value 数据库值
+/// @param column 列名称
+/// @param options 序列化配置
+pub fn serialize_pg_value_ref_with_options<S>(
+    value: &PgValueRef,
+    column: Option<&str>,
+    options: &SerializeOptions,
+    s: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if !value.is_null() && !options.is_empty() {
+        let info = value.type_info();
+        if let Some(func) = options.find(column, &[info.name(), type_name(&info)]) {
+            let v = func(value, options).map_err(|err| {
+                S::Error::custom(format!(
+                    "failed to serialize postgres type {}: {err}",
+                    info.name()
+                ))
+            })?;
+            return v.serialize(s);
         }
     }
+    serialize_value(value, options, s)
 }
 
 /// Can be used with serialize_with
 pub fn serialize_pgrow_as_vec<S>(x: &PgRow, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serialize_pgrow_as_vec_with(x, &DEFAULT_OPTIONS, s)
+}
+
+/// 按配置将行数据序列化成数组
+pub fn serialize_pgrow_as_vec_with<S>(
+    x: &PgRow,
+    options: &SerializeOptions,
+    s: S,
+) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
 {
     let cols = x.columns();
     let mut seq = s.serialize_seq(Some(cols.len()))?;
-    for c in cols {
-        let c: PgValueRef = x.try_get_raw(c.ordinal()).unwrap();
-        let c = SerPgValueRef(c);
-        seq.serialize_element(&c)?;
+    for col in cols {
+        let c: PgValueRef = x.try_get_raw(col.ordinal()).map_err(S::Error::custom)?;
+        seq.serialize_element(&SerColumn {
+            value: c,
+            column: col.name(),
+            options,
+        })?;
     }
     seq.end()
 }
 
 /// Can be used with serialize_with
 pub fn serialize_pgrow_as_map<S>(x: &PgRow, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serialize_pgrow_as_map_with(x, &DEFAULT_OPTIONS, s)
+}
+
+/// 按配置将行数据序列化成对象
+pub fn serialize_pgrow_as_map_with<S>(
+    x: &PgRow,
+    options: &SerializeOptions,
+    s: S,
+) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
 {
     let cols = x.columns();
     let mut map = s.serialize_map(Some(cols.len()))?;
     for col in cols {
-        let c: PgValueRef = x.try_get_raw(col.ordinal()).unwrap();
-        let c = SerPgValueRef(c);
-        map.serialize_entry(col.name(), &c)?;
+        let c: PgValueRef = x.try_get_raw(col.ordinal()).map_err(S::Error::custom)?;
+        map.serialize_entry(
+            col.name(),
+            &SerColumn {
+                value: c,
+                column: col.name(),
+                options,
+            },
+        )?;
     }
     map.end()
 }
 
+/// 带列名的数据库值
+pub(crate) struct SerColumn<'a, 'r> {
+    pub(crate) value: PgValueRef<'r>,
+    pub(crate) column: &'a str,
+    pub(crate) options: &'a SerializeOptions,
+}
+
+impl Serialize for SerColumn<'_, '_> {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        serialize_pg_value_ref_with_options(&self.value, Some(self.column), self.options, s)
+    }
+}
+
 /// SerVecPgRow::from(pg_row) will make your row serialize as a vector.
-#[derive(Serialize)]
-pub struct SerVecPgRow(#[serde(serialize_with = "serialize_pgrow_as_vec")] PgRow);
+pub struct SerVecPgRow {
+    row: PgRow,
+    options: Arc<SerializeOptions>,
+}
 
 /// SerMapPgRow::from(pg_row) will make your row serialize as a map.
 /// If you have multiple columns with the same name, the last one will win.
-#[derive(Serialize)]
-pub struct SerMapPgRow(#[serde(serialize_with = "serialize_pgrow_as_map")] PgRow);
+pub struct SerMapPgRow {
+    row: PgRow,
+    options: Arc<SerializeOptions>,
+}
+
+impl SerMapPgRow {
+    /// 设置无法识别类型的处理方式
+    pub fn with_fallback(mut self, fallback: Fallback) -> Self {
+        Arc::make_mut(&mut self.options).fallback = fallback;
+        self
+    }
+
+    /// 设置序列化配置
+    pub fn with_options(mut self, options: Arc<SerializeOptions>) -> Self {
+        self.options = options;
+        self
+    }
+}
+
+impl Serialize for SerMapPgRow {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        serialize_pgrow_as_map_with(&self.row, &self.options, s)
+    }
+}
 
 impl From<PgRow> for SerMapPgRow {
     fn from(row: PgRow) -> Self {
-        SerMapPgRow(row)
+        SerMapPgRow {
+            row,
+            options: DEFAULT_OPTIONS.clone(),
+        }
     }
 }
 
@@ -167,60 +527,239 @@ impl std::ops::Deref for SerMapPgRow {
     type Target = PgRow;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.row
     }
 }
 
 impl std::ops::DerefMut for SerMapPgRow {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+        &mut self.row
     }
 }
 
 impl From<SerMapPgRow> for PgRow {
     fn from(value: SerMapPgRow) -> Self {
-        value.0
+        value.row
     }
 }
 
 /// SerPgValueRef::from(pg_value_ref) will make your value serialize as its closest serde type.
-#[derive(Serialize)]
-pub struct SerPgValueRef<'r>(#[serde(serialize_with = "serialize_pg_value_ref")] PgValueRef<'r>);
+pub struct SerPgValueRef<'r> {
+    value: PgValueRef<'r>,
+    options: Arc<SerializeOptions>,
+}
+
+impl SerPgValueRef<'_> {
+    /// 设置无法识别类型的处理方式
+    pub fn with_fallback(mut self, fallback: Fallback) -> Self {
+        Arc::make_mut(&mut self.options).fallback = fallback;
+        self
+    }
+
+    /// 设置序列化配置，只有类型序列化函数以及时区生效
+    pub fn with_options(mut self, options: Arc<SerializeOptions>) -> Self {
+        self.options = options;
+        self
+    }
+}
+
+impl Serialize for SerPgValueRef<'_> {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        serialize_pg_value_ref_with_options(&self.value, None, &self.options, s)
+    }
+}
+
+impl<'r> From<PgValueRef<'r>> for SerPgValueRef<'r> {
+    fn from(value: PgValueRef<'r>) -> Self {
+        SerPgValueRef {
+            value,
+            options: DEFAULT_OPTIONS.clone(),
+        }
+    }
+}
+
+impl SerVecPgRow {
+    /// 设置无法识别类型的处理方式
+    pub fn with_fallback(mut self, fallback: Fallback) -> Self {
+        Arc::make_mut(&mut self.options).fallback = fallback;
+        self
+    }
+
+    /// 设置序列化配置
+    pub fn with_options(mut self, options: Arc<SerializeOptions>) -> Self {
+        self.options = options;
+        self
+    }
+}
+
+impl Serialize for SerVecPgRow {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        serialize_pgrow_as_vec_with(&self.row, &self.options, s)
+    }
+}
 
 impl From<PgRow> for SerVecPgRow {
     fn from(row: PgRow) -> Self {
-        SerVecPgRow(row)
+        SerVecPgRow {
+            row,
+            options: DEFAULT_OPTIONS.clone(),
+        }
     }
 }
-
 impl std::ops::Deref for SerVecPgRow {
     type Target = PgRow;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.row
     }
 }
 
 impl std::ops::DerefMut for SerVecPgRow {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+        &mut self.row
     }
 }
 
 impl From<SerVecPgRow> for PgRow {
     fn from(value: SerVecPgRow) -> Self {
-        value.0
+        value.row
     }
 }
 
-impl From<SerMapPgRow> for Value {
-    fn from(value: SerMapPgRow) -> Self {
-        serde_json::to_value(value).unwrap()
+impl TryFrom<SerMapPgRow> for Value {
+    type Error = serde_json::Error;
+
+    fn try_from(value: SerMapPgRow) -> Result<Self, Self::Error> {
+        serde_json::to_value(value)
     }
 }
 
-impl From<SerVecPgRow> for Value {
-    fn from(value: SerVecPgRow) -> Self {
-        serde_json::to_value(value).unwrap()
+impl TryFrom<SerVecPgRow> for Value {
+    type Error = serde_json::Error;
+
+    fn try_from(value: SerVecPgRow) -> Result<Self, Self::Error> {
+        serde_json::to_value(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use sqlx::{Connection, PgConnection};
+
+    use super::*;
+
+    // 设置 DATABASE_URL 时才连接数据库测试，没有设置时跳过
+    async fn query(sql: &str) -> Option<PgRow> {
+        let url = std::env::var("DATABASE_URL").ok()?;
+        let mut conn = PgConnection::connect(&url).await.unwrap();
+        // 自定义类型只在当前连接中存在
+        sqlx::query("create type pg_temp.mood as enum ('sad', 'ok')")
+            .execute(&mut conn)
+            .await
+            .unwrap();
+        Some(sqlx::query(sql).fetch_one(&mut conn).await.unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_array_range() {
+        let Some(row) = query(
+            "select array[1, null, 3]::int4[] as ints, array['a', 'b']::text[] as texts, \
+             array[1.50]::numeric[] as numbers, int4range(1, 10) as ints_range, \
+             numrange(1.5, null) as number_range, '[2024-01-01,2024-02-01)'::daterange as dates",
+        )
+        .await
+        else {
+            return;
+        };
+        assert_eq!(
+            Value::try_from(SerMapPgRow::from(row)).unwrap(),
+            json!({
+                "ints": [1, null, 3],
+                "texts": ["a", "b"],
+                "numbers": ["1.50"],
+                "ints_range": {"lower": 1, "upper": 10, "lowerInclusive": true, "upperInclusive": false},
+                "number_range": {"lower": "1.5", "upper": null, "lowerInclusive": true, "upperInclusive": false},
+                "dates": {"lower": "2024-01-01", "upper": "2024-02-01", "lowerInclusive": true, "upperInclusive": false}
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_enum_fallback() {
+        let Some(row) = query(
+            "select 'ok'::pg_temp.mood as mood, array['sad', null]::pg_temp.mood[] as moods, \
+             '(1,2)'::point as location",
+        )
+        .await
+        else {
+            return;
+        };
+        let value = Value::try_from(SerVecPgRow::from(row)).unwrap();
+        assert_eq!(
+            value,
+            json!(["ok", ["sad", null], "\\x3ff00000000000004000000000000000"])
+        );
+
+        let row = query("select '(1,2)'::point as location").await.unwrap();
+        let row = SerMapPgRow::from(row).with_fallback(Fallback::Null);
+        assert_eq!(Value::try_from(row).unwrap(), json!({"location": null}));
+        let row = query("select '(1,2)'::point as location").await.unwrap();
+        let err = Value::try_from(SerMapPgRow::from(row).with_fallback(Fallback::Error));
+        assert!(err.unwrap_err().to_string().contains("POINT"));
+    }
+
+    #[tokio::test]
+    async fn test_money() {
+        let Ok(url) = std::env::var("DATABASE_URL") else {
+            return;
+        };
+        let mut conn = PgConnection::connect(&url).await.unwrap();
+        sqlx::query("set lc_monetary = 'C'")
+            .execute(&mut conn)
+            .await
+            .unwrap();
+        let sql = "select 12.5::money as amount";
+        // 二进制协议按两位小数输出
+        let row = sqlx::query(sql).fetch_one(&mut conn).await.unwrap();
+        assert_eq!(
+            Value::try_from(SerMapPgRow::from(row)).unwrap(),
+            json!({"amount": "12.50"})
+        );
+        // 简单查询协议返回文本，直接输出数据库格式化后的金额
+        let row = sqlx::raw_sql(sql).fetch_one(&mut conn).await.unwrap();
+        assert_eq!(
+            Value::try_from(SerMapPgRow::from(row)).unwrap(),
+            json!({"amount": "$12.50"})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_timestamptz() {
+        let sql = "select '2024-01-01T08:00:00+08:00'::timestamptz as time, \
+                   array['2024-01-01T08:00:00+08:00']::timestamptz[] as times";
+        let Some(row) = query(sql).await else {
+            return;
+        };
+        // 没有设置时区时输出UTC时间
+        assert_eq!(
+            Value::try_from(SerMapPgRow::from(row)).unwrap(),
+            json!({"time": "2024-01-01T00:00:00+00:00", "times": ["2024-01-01T00:00:00+00:00"]})
+        );
+        let row = query(sql).await.unwrap();
+        let timezone = chrono::FixedOffset::east_opt(8 * 3600).unwrap();
+        let options = Arc::new(SerializeOptions::new().with_timezone(timezone));
+        assert_eq!(
+            Value::try_from(SerMapPgRow::from(row).with_options(options)).unwrap(),
+            json!({"time": "2024-01-01T08:00:00+08:00", "times": ["2024-01-01T08:00:00+08:00"]})
+        );
+
+        // 自定义格式同样按时区输出
+        let row = query(sql).await.unwrap();
+        let options = SerializeOptions::new()
+            .with_timezone(timezone)
+            .with_column("time", options::timestamp_format("%Y-%m-%d %H:%M:%S%:z"));
+        let value = Value::try_from(SerMapPgRow::from(row).with_options(Arc::new(options)));
+        assert_eq!(value.unwrap()["time"], json!("2024-01-01 08:00:00+08:00"));
     }
 }
@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+use base64::Engine;
+use serde_json::Value;
+use sqlx::error::BoxDynError;
+use sqlx::postgres::{PgValueFormat, PgValueRef};
+
+use crate::geometry::wkb_to_geojson;
+use crate::Fallback;
+
+/// 自定义序列化函数，输入数据库值(非null)以及序列化配置，返回json值
+pub type ValueSerializer =
+    Arc<dyn Fn(&PgValueRef, &SerializeOptions) -> Result<Value, BoxDynError> + Send + Sync>;
+
+/// 序列化配置
+/// > 按列名或者类型名称注册自定义序列化函数，列名优先于类型名称，都没有匹配时使用默认序列化
+/// ```rust
+/// use cts_pgrow::options::{self, SerializeOptions};
+///
+/// let options = SerializeOptions::new()
+///     .with_type("geometry", options::geometry_geojson())
+///     .with_type("bytea", options::bytes_base64())
+///     .with_column("photo", options::bytes_hex());
+/// ```
+#[derive(Clone, Default)]
+pub struct SerializeOptions {
+    pub fallback: Fallback,
+    /// timestamptz 输出的时区偏移，没有设置时输出UTC时间
+    #[cfg(feature = "chrono")]
+    pub timezone: Option<chrono::FixedOffset>,
+    types: HashMap<String, ValueSerializer>,
+    columns: HashMap<String, ValueSerializer>,
+}
+
+impl SerializeOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置无法识别类型的处理方式
+    pub fn with_fallback(mut self, fallback: Fallback) -> Self {
+        self.fallback = fallback;
+        self
+    }
+
+    /// 设置 timestamptz 输出的时区偏移
+    /// > 二进制协议返回的 timestamptz 不包含会话时区，需要按配置的时区转换
+    ///
+    /// @param timezone 时区偏移，例如 `+08:00`
+    #[cfg(feature = "chrono")]
+    pub fn with_timezone(mut self, timezone: chrono::FixedOffset) -> Self {
+        self.timezone = Some(timezone);
+        self
+    }
+
+    /// 注册类型序列化函数
+    /// @param name 数据库类型名称，不区分大小写，例如 timestamptz、geometry
+    /// @param func 序列化函数
+    pub fn with_type(mut self, name: &str, func: ValueSerializer) -> Self {
+        self.types.insert(name.to_lowercase(), func);
+        self
+    }
+
+    /// 注册列序列化函数
+    /// @param name 列名称
+    /// @param func 序列化函数
+    pub fn with_column(mut self, name: &str, func: ValueSerializer) -> Self {
+        self.columns.insert(name.to_string(), func);
+        self
+    }
+
+    /// 查找序列化函数，列名优先
+    pub fn find(&self, column: Option<&str>, type_names: &[&str]) -> Option<&ValueSerializer> {
+        if let Some(func) = column.and_then(|name| self.columns.get(name)) {
+            return Some(func);
+        }
+        type_names
+            .iter()
+            .find_map(|name| self.types.get(&name.to_lowercase()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty() && self.columns.is_empty()
+    }
+}
+
+impl Debug for SerializeOptions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut debug = f.debug_struct("SerializeOptions");
+        debug.field("fallback", &self.fallback);
+        #[cfg(feature = "chrono")]
+        debug.field("timezone", &self.timezone);
+        debug
+            .field("types", &self.types.keys().collect::<Vec<_>>())
+            .field("columns", &self.columns.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// 获取原始字节，文本协议下 bytea 为 `\x` 开头的十六进制，geometry 为十六进制
+fn raw_bytes(value: &PgValueRef) -> Result<Vec<u8>, BoxDynError> {
+    match value.format() {
+        PgValueFormat::Binary => Ok(value.as_bytes()?.to_vec()),
+        PgValueFormat::Text => {
+            let text = value.as_str()?;
+            let text = text.strip_prefix("\\x").unwrap_or(text);
+            Ok(hex::decode(text)?)
+        }
+    }
+}
+
+/// 字节数据输出为base64字符串
+pub fn bytes_base64() -> ValueSerializer {
+    Arc::new(|value, _| {
+        let bytes = raw_bytes(value)?;
+        Ok(Value::String(
+            base64::engine::general_purpose::STANDARD.encode(bytes),
+        ))
+    })
+}
+
+/// 字节数据输出为小写十六进制字符串
+pub fn bytes_hex() -> ValueSerializer {
+    Arc::new(|value, _| Ok(Value::String(hex::encode(raw_bytes(value)?))))
+}
+
+/// 空间数据输出为十六进制 EWKB 字符串，与 PostGIS 文本输出一致
+pub fn geometry_hex() -> ValueSerializer {
+    Arc::new(|value, _| Ok(Value::String(hex::encode_upper(raw_bytes(value)?))))
+}
+
+/// 空间数据输出为 GeoJSON geometry 对象
+pub fn geometry_geojson() -> ValueSerializer {
+    Arc::new(|value, _| wkb_to_geojson(&raw_bytes(value)?))
+}
+
+/// 日期时间输出为毫秒时间戳，支持 timestamp、timestamptz、date
+#[cfg(feature = "chrono")]
+pub fn timestamp_millis() -> ValueSerializer {
+    use sqlx::{Decode, Postgres, TypeInfo, ValueRef};
+    Arc::new(|value, _| {
+        let millis = match value.type_info().name() {
+            "TIMESTAMPTZ" => {
+                let v: chrono::DateTime<chrono::Utc> = Decode::<Postgres>::decode(value.clone())?;
+                v.timestamp_millis()
+            }
+            "DATE" => {
+                let v: chrono::NaiveDate = Decode::<Postgres>::decode(value.clone())?;
+                v.and_time(chrono::NaiveTime::MIN)
+                    .and_utc()
+                    .timestamp_millis()
+            }
+            _ => {
+                let v: chrono::NaiveDateTime = Decode::<Postgres>::decode(value.clone())?;
+                v.and_utc().timestamp_millis()
+            }
+        };
+        Ok(Value::from(millis))
+    })
+}
+
+/// 日期时间按指定格式输出，支持 timestamp、timestamptz
+/// > timestamptz 按配置的时区转换后输出，没有设置时区时输出UTC时间
+///
+/// @param pattern chrono 格式字符串，例如 `%Y-%m-%d %H:%M:%S`
+#[cfg(feature = "chrono")]
+pub fn timestamp_format(pattern: &str) -> ValueSerializer {
+    use sqlx::{Decode, Postgres, TypeInfo, ValueRef};
+    let pattern = pattern.to_string();
+    Arc::new(move |value, options| {
+        let text = match value.type_info().name() {
+            "TIMESTAMPTZ" => {
+                let v: chrono::DateTime<chrono::Utc> = Decode::<Postgres>::decode(value.clone())?;
+                match options.timezone {
+                    None => v.format(&pattern).to_string(),
+                    Some(timezone) => v.with_timezone(&timezone).format(&pattern).to_string(),
+                }
+            }
+            _ => {
+                let v: chrono::NaiveDateTime = Decode::<Postgres>::decode(value.clone())?;
+                v.format(&pattern).to_string()
+            }
+        };
+        Ok(Value::String(text))
+    })
+}
@@ -0,0 +1,499 @@
+pub mod parse;
+pub mod render;
+
+use serde::{Deserialize, Serialize};
+
+/// # 表达式语法树
+/// > 过滤参数先解析成语法树，再渲染成sql，服务端可以检查、改写、合并过滤条件
+/// ```rust
+/// use cts_sql_expression::ast::field;
+///
+/// let predicate = field("status")
+///     .eq("active")
+///     .and(field("level").between(1, 3))
+///     .or(field("name").is_null());
+/// assert_eq!(
+///     predicate.to_sql(),
+///     "((status = 'active') and (level between 1 and 3)) or (name is null)"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Literal {
+    Null,
+    Bool(bool),
+    Integer(i64),
+    Double(f64),
+    String(String),
+}
+
+/// 字段名称
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Field(pub String);
+
+/// 值表达式
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Expr {
+    Field(Field),
+    Literal(Literal),
+}
+
+/// 比较操作符
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+/// 条件表达式
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Predicate {
+    Compare(Expr, CompareOp, Expr),
+    In {
+        field: Field,
+        values: Vec<Literal>,
+        negated: bool,
+    },
+    Between {
+        field: Field,
+        low: Literal,
+        high: Literal,
+        negated: bool,
+    },
+    Like {
+        field: Field,
+        pattern: String,
+    },
+    IsNull {
+        field: Field,
+        negated: bool,
+    },
+    And(Vec<Predicate>),
+    Or(Vec<Predicate>),
+    Not(Box<Predicate>),
+    /// 空间相交，geometry 为 WKT 格式
+    Intersects {
+        field: Field,
+        geometry: String,
+        srid: i32,
+    },
+    /// 时间早于，time 为 ISO 8601 格式
+    Before {
+        field: Field,
+        time: String,
+    },
+    /// 时间晚于
+    After {
+        field: Field,
+        time: String,
+    },
+    /// 时间范围，包含开始时间，不包含结束时间
+    During {
+        field: Field,
+        start: String,
+        end: String,
+    },
+    /// 最近一段时间，unit 为 second、minute、hour、day、week、month、year
+    Last {
+        field: Field,
+        amount: i64,
+        unit: String,
+    },
+    /// 服务端sql片段，不会从请求参数中解析出来
+    Raw(String),
+}
+
+/// 创建字段
+pub fn field(name: &str) -> Field {
+    Field(name.to_string())
+}
+
+/// 字段名称按 . 拆分后每段只能包含字母、数字以及下划线，可以带表别名
+pub(crate) fn is_field_name(name: &str) -> bool {
+    name.split('.')
+        .all(|item| !item.is_empty() && item.chars().all(|ch| ch.is_alphanumeric() || ch == '_'))
+}
+
+impl CompareOp {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CompareOp::Eq => "=",
+            CompareOp::Ne => "!=",
+            CompareOp::Gt => ">",
+            CompareOp::Ge => ">=",
+            CompareOp::Lt => "<",
+            CompareOp::Le => "<=",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        let op = match name {
+            "=" => CompareOp::Eq,
+            "!=" | "<>" => CompareOp::Ne,
+            ">" => CompareOp::Gt,
+            ">=" => CompareOp::Ge,
+            "<" => CompareOp::Lt,
+            "<=" => CompareOp::Le,
+            _ => return None,
+        };
+        Some(op)
+    }
+}
+
+impl Field {
+    pub fn name(&self) -> &str {
+        &self.0
+    }
+
+    fn compare(self, op: CompareOp, value: impl Into<Literal>) -> Predicate {
+        Predicate::Compare(Expr::Field(self), op, Expr::Literal(value.into()))
+    }
+
+    pub fn eq(self, value: impl Into<Literal>) -> Predicate {
+        self.compare(CompareOp::Eq, value)
+    }
+
+    pub fn ne(self, value: impl Into<Literal>) -> Predicate {
+        self.compare(CompareOp::Ne, value)
+    }
+
+    pub fn gt(self, value: impl Into<Literal>) -> Predicate {
+        self.compare(CompareOp::Gt, value)
+    }
+
+    pub fn ge(self, value: impl Into<Literal>) -> Predicate {
+        self.compare(CompareOp::Ge, value)
+    }
+
+    pub fn lt(self, value: impl Into<Literal>) -> Predicate {
+        self.compare(CompareOp::Lt, value)
+    }
+
+    pub fn le(self, value: impl Into<Literal>) -> Predicate {
+        self.compare(CompareOp::Le, value)
+    }
+
+    pub fn is_in<T: Into<Literal>>(self, values: impl IntoIterator<Item = T>) -> Predicate {
+        Predicate::In {
+            field: self,
+            values: values.into_iter().map(Into::into).collect(),
+            negated: false,
+        }
+    }
+
+    pub fn not_in<T: Into<Literal>>(self, values: impl IntoIterator<Item = T>) -> Predicate {
+        Predicate::In {
+            field: self,
+            values: values.into_iter().map(Into::into).collect(),
+            negated: true,
+        }
+    }
+
+    pub fn between(self, low: impl Into<Literal>, high: impl Into<Literal>) -> Predicate {
+        Predicate::Between {
+            field: self,
+            low: low.into(),
+            high: high.into(),
+            negated: false,
+        }
+    }
+
+    pub fn not_between(self, low: impl Into<Literal>, high: impl Into<Literal>) -> Predicate {
+        Predicate::Between {
+            field: self,
+            low: low.into(),
+            high: high.into(),
+            negated: true,
+        }
+    }
+
+    pub fn like(self, pattern: &str) -> Predicate {
+        Predicate::Like {
+            field: self,
+            pattern: pattern.to_string(),
+        }
+    }
+
+    pub fn is_null(self) -> Predicate {
+        Predicate::IsNull {
+            field: self,
+            negated: false,
+        }
+    }
+
+    pub fn is_not_null(self) -> Predicate {
+        Predicate::IsNull {
+            field: self,
+            negated: true,
+        }
+    }
+
+    /// 与 WKT 空间对象相交
+    /// @param wkt 空间对象，例如 POLYGON((...))
+    /// @param srid 空间对象坐标系
+    pub fn intersects(self, wkt: &str, srid: i32) -> Predicate {
+        Predicate::Intersects {
+            field: self,
+            geometry: wkt.to_string(),
+            srid,
+        }
+    }
+
+    /// 早于 ISO 8601 时间
+    pub fn before(self, time: &str) -> Predicate {
+        Predicate::Before {
+            field: self,
+            time: time.to_string(),
+        }
+    }
+
+    /// 晚于 ISO 8601 时间
+    pub fn after(self, time: &str) -> Predicate {
+        Predicate::After {
+            field: self,
+            time: time.to_string(),
+        }
+    }
+
+    /// 在时间范围内，包含开始时间，不包含结束时间
+    pub fn during(self, start: &str, end: &str) -> Predicate {
+        Predicate::During {
+            field: self,
+            start: start.to_string(),
+            end: end.to_string(),
+        }
+    }
+
+    /// 最近一段时间
+    /// @param amount 时间长度
+    /// @param unit 时间单位，例如 day
+    pub fn last(self, amount: i64, unit: &str) -> Predicate {
+        Predicate::Last {
+            field: self,
+            amount,
+            unit: unit.to_string(),
+        }
+    }
+}
+
+impl Predicate {
+    /// 并且，合并同类型的条件
+    pub fn and(self, other: Predicate) -> Predicate {
+        match self {
+            Predicate::And(mut list) => {
+                list.push(other);
+                Predicate::And(list)
+            }
+            data => Predicate::And(vec![data, other]),
+        }
+    }
+
+    /// 或者，合并同类型的条件
+    pub fn or(self, other: Predicate) -> Predicate {
+        match self {
+            Predicate::Or(mut list) => {
+                list.push(other);
+                Predicate::Or(list)
+            }
+            data => Predicate::Or(vec![data, other]),
+        }
+    }
+
+    /// 取反
+    pub fn negate(self) -> Predicate {
+        Predicate::Not(Box::new(self))
+    }
+
+    /// 合并多个条件，没有条件时返回空
+    pub fn and_all(list: impl IntoIterator<Item = Predicate>) -> Option<Predicate> {
+        list.into_iter().reduce(|result, item| result.and(item))
+    }
+
+    /// 简化条件：展开嵌套的 and/or，去掉只有一个条件的 and/or 以及双重取反
+    pub fn simplify(self) -> Predicate {
+        match self {
+            Predicate::And(list) => flatten(list, true),
+            Predicate::Or(list) => flatten(list, false),
+            Predicate::Not(inner) => match inner.simplify() {
+                Predicate::Not(data) => *data,
+                data => Predicate::Not(Box::new(data)),
+            },
+            data => data,
+        }
+    }
+
+    /// 查询条件中使用的字段
+    pub fn fields(&self) -> Vec<&Field> {
+        let mut result = Vec::new();
+        self.collect_fields(&mut result);
+        result
+    }
+
+    /// 没有表名的字段加上表名，`Raw` 条件原样保留
+    /// @param table 表名
+    pub fn qualify(mut self, table: &str) -> Predicate {
+        self.qualify_fields(table);
+        self
+    }
+
+    fn qualify_fields(&mut self, table: &str) {
+        let qualify = |field: &mut Field| {
+            if !field.0.contains('.') {
+                field.0 = format!("{table}.{}", field.0);
+            }
+        };
+        match self {
+            Predicate::Compare(left, _, right) => {
+                for expr in [left, right] {
+                    if let Expr::Field(data) = expr {
+                        qualify(data);
+                    }
+                }
+            }
+            Predicate::In { field, .. }
+            | Predicate::Between { field, .. }
+            | Predicate::Like { field, .. }
+            | Predicate::IsNull { field, .. }
+            | Predicate::Intersects { field, .. }
+            | Predicate::Before { field, .. }
+            | Predicate::After { field, .. }
+            | Predicate::During { field, .. }
+            | Predicate::Last { field, .. } => qualify(field),
+            Predicate::And(list) | Predicate::Or(list) => {
+                list.iter_mut().for_each(|item| item.qualify_fields(table))
+            }
+            Predicate::Not(inner) => inner.qualify_fields(table),
+            Predicate::Raw(_) => {}
+        }
+    }
+
+    fn collect_fields<'a>(&'a self, result: &mut Vec<&'a Field>) {
+        match self {
+            Predicate::Compare(left, _, right) => {
+                for expr in [left, right] {
+                    if let Expr::Field(data) = expr {
+                        result.push(data);
+                    }
+                }
+            }
+            Predicate::In { field, .. }
+            | Predicate::Between { field, .. }
+            | Predicate::Like { field, .. }
+            | Predicate::IsNull { field, .. }
+            | Predicate::Intersects { field, .. }
+            | Predicate::Before { field, .. }
+            | Predicate::After { field, .. }
+            | Predicate::During { field, .. }
+            | Predicate::Last { field, .. } => result.push(field),
+            Predicate::And(list) | Predicate::Or(list) => {
+                list.iter().for_each(|item| item.collect_fields(result))
+            }
+            Predicate::Not(inner) => inner.collect_fields(result),
+            Predicate::Raw(_) => {}
+        }
+    }
+}
+
+fn flatten(list: Vec<Predicate>, is_and: bool) -> Predicate {
+    let mut result = Vec::new();
+    for item in list.into_iter().map(Predicate::simplify) {
+        match (item, is_and) {
+            (Predicate::And(sub), true) | (Predicate::Or(sub), false) => result.extend(sub),
+            (data, _) => result.push(data),
+        }
+    }
+    if result.len() == 1 {
+        return result.remove(0);
+    }
+    if is_and {
+        Predicate::And(result)
+    } else {
+        Predicate::Or(result)
+    }
+}
+
+impl From<&str> for Literal {
+    fn from(value: &str) -> Self {
+        Literal::String(value.to_string())
+    }
+}
+
+impl From<String> for Literal {
+    fn from(value: String) -> Self {
+        Literal::String(value)
+    }
+}
+
+impl From<i64> for Literal {
+    fn from(value: i64) -> Self {
+        Literal::Integer(value)
+    }
+}
+
+impl From<i32> for Literal {
+    fn from(value: i32) -> Self {
+        Literal::Integer(value as i64)
+    }
+}
+
+impl From<f64> for Literal {
+    fn from(value: f64) -> Self {
+        Literal::Double(value)
+    }
+}
+
+impl From<bool> for Literal {
+    fn from(value: bool) -> Self {
+        Literal::Bool(value)
+    }
+}
+
+impl<T: Into<Literal>> From<Option<T>> for Literal {
+    fn from(value: Option<T>) -> Self {
+        match value {
+            None => Literal::Null,
+            Some(data) => data.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simplify() {
+        let predicate = Predicate::And(vec![
+            field("a").eq(1),
+            Predicate::And(vec![field("b").eq(2), field("c").eq(3)]),
+        ])
+        .and(field("d").is_null().negate().negate());
+        let simplified = predicate.simplify();
+        assert_eq!(
+            simplified,
+            Predicate::And(vec![
+                field("a").eq(1),
+                field("b").eq(2),
+                field("c").eq(3),
+                field("d").is_null(),
+            ])
+        );
+        let names: Vec<&str> = simplified.fields().iter().map(|item| item.name()).collect();
+        assert_eq!(names, vec!["a", "b", "c", "d"]);
+    }
+
+    #[test]
+    fn test_qualify() {
+        let predicate = field("org_id")
+            .eq("org-1")
+            .and(field("road.name").is_null())
+            .qualify("device");
+        assert_eq!(
+            predicate.to_sql(),
+            "(device.org_id = 'org-1') and (road.name is null)"
+        );
+    }
+}
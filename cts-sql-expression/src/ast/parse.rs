@@ -0,0 +1,402 @@
+use crate::ast::{is_field_name, CompareOp, Expr, Field, Literal, Predicate};
+use crate::error::CtsError;
+use crate::error::CtsError::FilterError;
+use crate::expression::parse::filter::check_len;
+use crate::expression::parse::filter::spatial::{handler_wkt, DEFAULT_SRID};
+use crate::expression::parse::filter::temporal::{handler_time, handler_unit};
+use crate::expression::parse::handler_name;
+use crate::expression::{CtsValue, Single};
+
+/// # 过滤参数与语法树转换
+/// > FilterParse 以及参数校验都通过语法树解析过滤参数，错误路径相对于过滤参数
+/// ```txt
+/// [express,field,value]
+/// ["or",["=",field,value],["=",field2,value]]
+/// ["in",field,[value1,value2]]
+/// ["between",field,value1,value2]
+/// ["is null",field]
+/// ["intersects",field,"POINT(120 30)",4326]
+/// ["during",field,"2024-01-01","2024-02-01"]
+/// ["last",field,7,"day"]
+/// ```
+impl Predicate {
+    /// 将过滤参数解析成语法树
+    pub fn from_filter(data: &[CtsValue]) -> Result<Predicate, CtsError> {
+        if data.len() < 2 {
+            return Err(FilterError("过滤参数长度不够，至少2位。".to_string()));
+        }
+        let ope = handler_name(&data[0])?.to_lowercase();
+        let predicate = match ope.as_str() {
+            ">" | "<" | ">=" | "<=" | "=" | "!=" => {
+                check_len(data, 3)?;
+                let op =
+                    CompareOp::from_name(&ope).ok_or(FilterError("过滤参数错误".to_string()))?;
+                let field = handler_field(data)?;
+                let value = handler_literal(&data[2]).map_err(|err| err.with_path("[2]"))?;
+                Predicate::Compare(Expr::Field(field), op, Expr::Literal(value))
+            }
+            "or" | "and" => {
+                let list = handler_children(data)?;
+                if ope == "or" {
+                    Predicate::Or(list)
+                } else {
+                    Predicate::And(list)
+                }
+            }
+            "not" => {
+                let mut list = handler_children(data)?;
+                let inner = if list.len() == 1 {
+                    list.remove(0)
+                } else {
+                    Predicate::And(list)
+                };
+                inner.negate()
+            }
+            "in" | "not in" => {
+                check_len(data, 3)?;
+                let field = handler_field(data)?;
+                let values = match &data[2] {
+                    CtsValue::Array(list) => list
+                        .iter()
+                        .enumerate()
+                        .map(|(index, item)| {
+                            match item {
+                                // 不支持嵌套数组
+                                CtsValue::Array(_) => Err(FilterError("IN参数错误".to_string())),
+                                item => handler_not_empty(item),
+                            }
+                            .map_err(|err| err.with_path(&format!("[{index}]")))
+                        })
+                        .collect::<Result<Vec<_>, _>>(),
+                    value => handler_not_empty(value).map(|value| vec![value]),
+                }
+                .map_err(|err| err.with_path("[2]"))?;
+                Predicate::In {
+                    field,
+                    values,
+                    negated: ope == "not in",
+                }
+            }
+            "between" | "not between" => {
+                check_len(data, 4)?;
+                let field = handler_field(data)?;
+                let low = handler_between(&data[2]).map_err(|err| err.with_path("[2]"))?;
+                let high = handler_between(&data[3]).map_err(|err| err.with_path("[3]"))?;
+                Predicate::Between {
+                    field,
+                    low,
+                    high,
+                    negated: ope == "not between",
+                }
+            }
+            "like" => {
+                check_len(data, 3)?;
+                let field = handler_field(data)?;
+                let pattern = match &data[2] {
+                    CtsValue::Single(Single::String(pattern)) if pattern.is_empty() => {
+                        Err(FilterError("数据不能为空".to_string()))
+                    }
+                    CtsValue::Single(Single::String(pattern)) => Ok(pattern.to_string()),
+                    _ => Err(FilterError("LIKE参数错误".to_string())),
+                }
+                .map_err(|err| err.with_path("[2]"))?;
+                Predicate::Like { field, pattern }
+            }
+            "is null" | "is not null" => {
+                check_len(data, 2)?;
+                Predicate::IsNull {
+                    field: handler_field(data)?,
+                    negated: ope == "is not null",
+                }
+            }
+            "intersects" => {
+                // 坐标系可以省略
+                if data.len() < 3 {
+                    return Err(FilterError("参数错误，参数长度不够".to_string()));
+                }
+                if data.len() > 4 {
+                    return Err(FilterError("参数错误，参数过多".to_string()).with_path("[4]"));
+                }
+                let field = handler_field(data)?;
+                let geometry = handler_wkt(&data[2]).map_err(|err| err.with_path("[2]"))?;
+                let srid = match data.get(3) {
+                    None => Some(DEFAULT_SRID),
+                    Some(CtsValue::Single(Single::Integer(srid))) if *srid > 0 => Some(*srid),
+                    Some(_) => None,
+                }
+                .and_then(|srid| i32::try_from(srid).ok())
+                .ok_or(FilterError("坐标系参数错误".to_string()).with_path("[3]"))?;
+                Predicate::Intersects {
+                    field,
+                    geometry,
+                    srid,
+                }
+            }
+            "before" | "after" => {
+                check_len(data, 3)?;
+                let field = handler_field(data)?;
+                let time = handler_time(&data[2]).map_err(|err| err.with_path("[2]"))?;
+                if ope == "before" {
+                    Predicate::Before { field, time }
+                } else {
+                    Predicate::After { field, time }
+                }
+            }
+            "during" => {
+                check_len(data, 4)?;
+                Predicate::During {
+                    field: handler_field(data)?,
+                    start: handler_time(&data[2]).map_err(|err| err.with_path("[2]"))?,
+                    end: handler_time(&data[3]).map_err(|err| err.with_path("[3]"))?,
+                }
+            }
+            "last" => {
+                check_len(data, 4)?;
+                let field = handler_field(data)?;
+                let amount = match &data[2] {
+                    CtsValue::Single(Single::Integer(amount)) if *amount > 0 => *amount,
+                    _ => {
+                        return Err(FilterError("时间长度必须为正整数".to_string()).with_path("[2]"))
+                    }
+                };
+                let unit = handler_unit(&data[3]).map_err(|err| err.with_path("[3]"))?;
+                Predicate::Last {
+                    field,
+                    amount,
+                    unit: unit.to_string(),
+                }
+            }
+            _ => return Err(FilterError(format!("不支持的过滤操作符{ope}")).with_path("[0]")),
+        };
+        Ok(predicate)
+    }
+
+    /// 将语法树转换成过滤参数，sql片段以及字段之间比较无法转换
+    pub fn to_filter(&self) -> Result<Vec<CtsValue>, CtsError> {
+        let name = |value: &str| CtsValue::Single(Single::String(value.to_string()));
+        let filter = match self {
+            Predicate::Compare(Expr::Field(field), op, Expr::Literal(value)) => {
+                vec![name(op.as_str()), name(field.name()), literal_value(value)?]
+            }
+            Predicate::Compare(..) => {
+                return Err(FilterError("不支持转换字段之间的比较".to_string()))
+            }
+            Predicate::In {
+                field,
+                values,
+                negated,
+            } => {
+                let values = values
+                    .iter()
+                    .map(literal_value)
+                    .collect::<Result<Vec<_>, _>>()?;
+                let op = if *negated { "not in" } else { "in" };
+                vec![name(op), name(field.name()), CtsValue::Array(values)]
+            }
+            Predicate::Between {
+                field,
+                low,
+                high,
+                negated,
+            } => {
+                let op = if *negated { "not between" } else { "between" };
+                vec![
+                    name(op),
+                    name(field.name()),
+                    literal_value(low)?,
+                    literal_value(high)?,
+                ]
+            }
+            Predicate::Like { field, pattern } => {
+                vec![name("like"), name(field.name()), name(pattern)]
+            }
+            Predicate::IsNull { field, negated } => {
+                let op = if *negated { "is not null" } else { "is null" };
+                vec![name(op), name(field.name())]
+            }
+            Predicate::And(list) | Predicate::Or(list) => {
+                let op = if matches!(self, Predicate::And(_)) {
+                    "and"
+                } else {
+                    "or"
+                };
+                let mut result = vec![name(op)];
+                for item in list.iter() {
+                    result.push(CtsValue::Array(item.to_filter()?));
+                }
+                result
+            }
+            Predicate::Not(inner) => vec![name("not"), CtsValue::Array(inner.to_filter()?)],
+            Predicate::Intersects {
+                field,
+                geometry,
+                srid,
+            } => vec![
+                name("intersects"),
+                name(field.name()),
+                name(geometry),
+                CtsValue::Single(Single::Integer(i64::from(*srid))),
+            ],
+            Predicate::Before { field, time } => {
+                vec![name("before"), name(field.name()), name(time)]
+            }
+            Predicate::After { field, time } => vec![name("after"), name(field.name()), name(time)],
+            Predicate::During { field, start, end } => {
+                vec![name("during"), name(field.name()), name(start), name(end)]
+            }
+            Predicate::Last {
+                field,
+                amount,
+                unit,
+            } => vec![
+                name("last"),
+                name(field.name()),
+                CtsValue::Single(Single::Integer(*amount)),
+                name(unit),
+            ],
+            Predicate::Raw(_) => return Err(FilterError("不支持转换sql片段".to_string())),
+        };
+        Ok(filter)
+    }
+}
+
+// 字段名称，参数个数已经检查过，名称直接写入sql，只能包含字母、数字、下划线以及表别名
+fn handler_field(data: &[CtsValue]) -> Result<Field, CtsError> {
+    handler_name(&data[1])
+        .and_then(|name| match is_field_name(&name) {
+            true => Ok(Field(name)),
+            false => Err(FilterError(format!("字段名称错误{name}"))),
+        })
+        .map_err(|err| err.with_path("[1]"))
+}
+
+fn handler_children(data: &[CtsValue]) -> Result<Vec<Predicate>, CtsError> {
+    data.iter()
+        .enumerate()
+        .skip(1)
+        .map(|(index, item)| {
+            match item {
+                CtsValue::Array(list) => Predicate::from_filter(list),
+                CtsValue::Single(_) => Err(FilterError("参数错误".to_string())),
+            }
+            .map_err(|err| err.with_path(&format!("[{index}]")))
+        })
+        .collect()
+}
+
+fn handler_literal(data: &CtsValue) -> Result<Literal, CtsError> {
+    match data {
+        CtsValue::Single(value) => Ok(match value {
+            Single::String(data) => Literal::String(data.clone()),
+            Single::Integer(data) => Literal::Integer(*data),
+            Single::Double(data) => Literal::Double(*data),
+            Single::Bool(data) => Literal::Bool(*data),
+        }),
+        CtsValue::Array(_) => Err(FilterError("参数错误".to_string())),
+    }
+}
+
+fn handler_not_empty(data: &CtsValue) -> Result<Literal, CtsError> {
+    match handler_literal(data)? {
+        Literal::String(data) if data.is_empty() => Err(FilterError("数据不能为空".to_string())),
+        data => Ok(data),
+    }
+}
+
+fn handler_between(data: &CtsValue) -> Result<Literal, CtsError> {
+    match handler_literal(data)? {
+        Literal::Bool(_) => Err(FilterError("BETWEEN参数错误".to_string())),
+        data => Ok(data),
+    }
+}
+
+fn literal_value(data: &Literal) -> Result<CtsValue, CtsError> {
+    let value = match data {
+        Literal::Null => return Err(FilterError("不支持转换null值".to_string())),
+        Literal::Bool(data) => Single::Bool(*data),
+        Literal::Integer(data) => Single::Integer(*data),
+        Literal::Double(data) => Single::Double(*data),
+        Literal::String(data) => Single::String(data.clone()),
+    };
+    Ok(CtsValue::Single(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::{field, Predicate};
+    use crate::expression::CtsValue;
+
+    #[test]
+    fn test_from_filter() {
+        let filter: Vec<CtsValue> = serde_json::from_str(
+            r#"["or", ["=", "name", "a"], ["and", ["in", "type", [1, 2]], ["is not null", "code"]]]"#,
+        )
+        .unwrap();
+        let predicate = Predicate::from_filter(&filter).unwrap();
+        assert_eq!(
+            predicate,
+            field("name")
+                .eq("a")
+                .or(field("type").is_in([1, 2]).and(field("code").is_not_null()))
+        );
+        // 转换回过滤参数
+        let filter = predicate.to_filter().unwrap();
+        assert_eq!(Predicate::from_filter(&filter).unwrap(), predicate);
+        // 缺少参数
+        let filter: Vec<CtsValue> = serde_json::from_str(r#"["between", "level", 1]"#).unwrap();
+        assert!(Predicate::from_filter(&filter).is_err());
+    }
+
+    #[test]
+    fn test_temporal() {
+        let filter: Vec<CtsValue> = serde_json::from_str(
+            r#"["and", ["before", "a", "2024-01-01"], ["during", "b", "2024-01-01", "2024-02-01 08:00:00"], ["last", "c", 7, "days"]]"#,
+        )
+        .unwrap();
+        let predicate = Predicate::from_filter(&filter).unwrap();
+        assert_eq!(
+            predicate,
+            field("a")
+                .before("2024-01-01")
+                .and(field("b").during("2024-01-01", "2024-02-01 08:00:00"))
+                .and(field("c").last(7, "day"))
+        );
+        assert_eq!(
+            Predicate::from_filter(&predicate.to_filter().unwrap()).unwrap(),
+            predicate
+        );
+        assert_eq!(
+            predicate.to_sql(),
+            crate::expression::parse::filter::filter_parse(&filter)
+                .unwrap()
+                .unwrap()
+        );
+
+        let filter: Vec<CtsValue> =
+            serde_json::from_str(r#"["after", "a", "2024-01-01' or '1'='1"]"#).unwrap();
+        assert_eq!(
+            Predicate::from_filter(&filter).unwrap_err().path(),
+            Some("[2]")
+        );
+    }
+
+    #[test]
+    fn test_from_filter_len() {
+        // 多余的参数与 FilterParse 一样返回错误
+        for (filter, path) in [
+            (r#"["=", "a", 1, 2]"#, "[3]"),
+            (r#"["is null", "a", "x"]"#, "[2]"),
+            (r#"["in", "a", [1], [2]]"#, "[3]"),
+            (r#"["like", "a", "%b", "c"]"#, "[3]"),
+            (r#"["between", "a", 1, 2, 3]"#, "[4]"),
+            (
+                r#"["and", ["=", "a", 1], ["in", "b", [1, [2]]]]"#,
+                "[2][2][1]",
+            ),
+        ] {
+            let filter: Vec<CtsValue> = serde_json::from_str(filter).unwrap();
+            let err = Predicate::from_filter(&filter).unwrap_err();
+            assert_eq!(err.path(), Some(path));
+        }
+    }
+}
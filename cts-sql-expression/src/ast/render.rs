@@ -0,0 +1,333 @@
+use std::fmt::{Display, Formatter};
+
+use crate::ast::{is_field_name, Expr, Field, Literal, Predicate};
+use crate::dialect::{Dialect, SpatialFunction};
+use crate::error::CtsError;
+use crate::error::CtsError::FilterError;
+use crate::expression::parse::filter::temporal::{IsoDateTime, UNITS};
+
+/// # sql渲染
+/// > 字符串值转义单引号，空的 in 条件渲染成恒假，空的 not in 条件渲染成恒真
+impl Predicate {
+    pub fn to_sql(&self) -> String {
+        self.to_string()
+    }
+
+    /// # 按方言渲染sql
+    /// > 字段名称使用方言引用，只能包含字母、数字以及下划线，可以带表别名；
+    /// > 字符串、时间常量以及时间计算由方言生成，`Raw` 条件原样输出
+    ///
+    /// @param dialect 数据库方言
+    pub fn to_dialect_sql(&self, dialect: &dyn Dialect) -> Result<String, CtsError> {
+        let ident = |field: &Field| dialect_ident(dialect, field);
+        let literal = |data: &Literal| match data {
+            Literal::String(data) => dialect.string_literal(data),
+            data => data.to_string(),
+        };
+        let expr = |data: &Expr| match data {
+            Expr::Field(data) => ident(data),
+            Expr::Literal(data) => Ok(literal(data)),
+        };
+        let time = |data: &str| match IsoDateTime::parse(data) {
+            Some(time) => Ok(dialect.datetime_literal(&time)),
+            None => Err(FilterError(format!("时间格式错误{data}"))),
+        };
+        let sql = match self {
+            Predicate::Compare(left, op, right) => {
+                let left = expr(left)?;
+                match (op.as_str(), right) {
+                    ("=", Expr::Literal(Literal::Null)) => format!("{left} is null"),
+                    ("!=", Expr::Literal(Literal::Null)) => format!("{left} is not null"),
+                    (op, right) => format!("{left} {op} {}", expr(right)?),
+                }
+            }
+            Predicate::In {
+                field,
+                values,
+                negated,
+            } => {
+                if values.is_empty() {
+                    return Ok(if *negated { "1 = 1" } else { "1 != 1" }.to_string());
+                }
+                let values: Vec<String> = values.iter().map(literal).collect();
+                let op = if *negated { "not in" } else { "in" };
+                format!("{} {op} ({})", ident(field)?, values.join(","))
+            }
+            Predicate::Between {
+                field,
+                low,
+                high,
+                negated,
+            } => {
+                let op = if *negated { "not between" } else { "between" };
+                format!(
+                    "{} {op} {} and {}",
+                    ident(field)?,
+                    literal(low),
+                    literal(high)
+                )
+            }
+            Predicate::Like { field, pattern } => {
+                format!("{} like {}", ident(field)?, dialect.string_literal(pattern))
+            }
+            Predicate::IsNull { field, negated } => {
+                let op = if *negated { "is not null" } else { "is null" };
+                format!("{} {op}", ident(field)?)
+            }
+            Predicate::And(list) => dialect_list(dialect, list, "and", "1 = 1")?,
+            Predicate::Or(list) => dialect_list(dialect, list, "or", "1 != 1")?,
+            Predicate::Not(inner) => format!("not ({})", inner.to_dialect_sql(dialect)?),
+            Predicate::Intersects {
+                field,
+                geometry,
+                srid,
+            } => format!(
+                "{}({}, {}({}, {srid}))",
+                dialect.spatial_function(SpatialFunction::Intersects),
+                ident(field)?,
+                dialect.spatial_function(SpatialFunction::GeomFromText),
+                dialect.string_literal(geometry)
+            ),
+            Predicate::Before { field, time: value } => format!(
+                "{} < {}",
+                dialect.datetime_field(&ident(field)?),
+                time(value)?
+            ),
+            Predicate::After { field, time: value } => format!(
+                "{} > {}",
+                dialect.datetime_field(&ident(field)?),
+                time(value)?
+            ),
+            Predicate::During { field, start, end } => {
+                let field = dialect.datetime_field(&ident(field)?);
+                format!("({field} >= {} and {field} < {})", time(start)?, time(end)?)
+            }
+            Predicate::Last {
+                field,
+                amount,
+                unit,
+            } => {
+                if !UNITS.contains(&unit.as_str()) {
+                    return Err(FilterError(format!("不支持的时间单位{unit}")));
+                }
+                format!(
+                    "{} >= {}",
+                    dialect.datetime_field(&ident(field)?),
+                    dialect.now_minus(*amount, unit)
+                )
+            }
+            Predicate::Raw(sql) => sql.clone(),
+        };
+        Ok(sql)
+    }
+}
+
+impl Display for Literal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Literal::Null => write!(f, "NULL"),
+            Literal::Bool(data) => write!(f, "{data}"),
+            Literal::Integer(data) => write!(f, "{data}"),
+            Literal::Double(data) => write!(f, "{data}"),
+            Literal::String(data) => write!(f, "'{}'", data.replace('\'', "''")),
+        }
+    }
+}
+
+impl Display for Field {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Display for Expr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expr::Field(data) => write!(f, "{data}"),
+            Expr::Literal(data) => write!(f, "{data}"),
+        }
+    }
+}
+
+impl Display for Predicate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Predicate::Compare(left, op, right) => {
+                // 与null比较转换成 is null
+                match (op.as_str(), right) {
+                    ("=", Expr::Literal(Literal::Null)) => write!(f, "{left} is null"),
+                    ("!=", Expr::Literal(Literal::Null)) => write!(f, "{left} is not null"),
+                    (op, right) => write!(f, "{left} {op} {right}"),
+                }
+            }
+            Predicate::In {
+                field,
+                values,
+                negated,
+            } => {
+                if values.is_empty() {
+                    return write!(f, "{}", if *negated { "1 = 1" } else { "1 != 1" });
+                }
+                let values: Vec<String> = values.iter().map(|item| item.to_string()).collect();
+                let op = if *negated { "not in" } else { "in" };
+                write!(f, "{field} {op} ({})", values.join(","))
+            }
+            Predicate::Between {
+                field,
+                low,
+                high,
+                negated,
+            } => {
+                let op = if *negated { "not between" } else { "between" };
+                write!(f, "{field} {op} {low} and {high}")
+            }
+            Predicate::Like { field, pattern } => {
+                write!(f, "{field} like {}", Literal::String(pattern.clone()))
+            }
+            Predicate::IsNull { field, negated } => {
+                let op = if *negated { "is not null" } else { "is null" };
+                write!(f, "{field} {op}")
+            }
+            Predicate::And(list) => write_list(f, list, "and", "1 = 1"),
+            Predicate::Or(list) => write_list(f, list, "or", "1 != 1"),
+            Predicate::Not(inner) => write!(f, "not ({inner})"),
+            Predicate::Intersects {
+                field,
+                geometry,
+                srid,
+            } => {
+                let geometry = Literal::String(geometry.clone());
+                write!(
+                    f,
+                    "st_intersects({field}, st_geomfromtext({geometry}, {srid}))"
+                )
+            }
+            Predicate::Before { field, time } => write!(f, "{field} < {}", time_literal(time)),
+            Predicate::After { field, time } => write!(f, "{field} > {}", time_literal(time)),
+            Predicate::During { field, start, end } => write!(
+                f,
+                "({field} >= {} and {field} < {})",
+                time_literal(start),
+                time_literal(end)
+            ),
+            Predicate::Last {
+                field,
+                amount,
+                unit,
+            } => {
+                let interval = Literal::String(format!("{amount} {unit}"));
+                write!(f, "{field} >= now() - interval {interval}")
+            }
+            Predicate::Raw(sql) => write!(f, "{sql}"),
+        }
+    }
+}
+
+// ISO 8601 时间按类型转换，其他格式作为普通字符串
+fn time_literal(data: &str) -> String {
+    match IsoDateTime::parse(data) {
+        Some(time) => time.to_sql(),
+        None => Literal::String(data.to_string()).to_string(),
+    }
+}
+
+// 字段名称按 . 拆分后分别引用
+fn dialect_ident(dialect: &dyn Dialect, field: &Field) -> Result<String, CtsError> {
+    if !is_field_name(&field.0) {
+        return Err(FilterError(format!("字段名称错误{}", field.0)));
+    }
+    let names: Vec<String> = field
+        .0
+        .split('.')
+        .map(|name| dialect.quote_ident(name))
+        .collect();
+    Ok(names.join("."))
+}
+
+fn dialect_list(
+    dialect: &dyn Dialect,
+    list: &[Predicate],
+    op: &str,
+    empty: &str,
+) -> Result<String, CtsError> {
+    if list.is_empty() {
+        return Ok(empty.to_string());
+    }
+    let items = list
+        .iter()
+        .map(|item| Ok(format!("({})", item.to_dialect_sql(dialect)?)))
+        .collect::<Result<Vec<String>, CtsError>>()?;
+    Ok(items.join(&format!(" {op} ")))
+}
+
+fn write_list(
+    f: &mut Formatter<'_>,
+    list: &[Predicate],
+    op: &str,
+    empty: &str,
+) -> std::fmt::Result {
+    if list.is_empty() {
+        return write!(f, "{empty}");
+    }
+    let items: Vec<String> = list.iter().map(|item| format!("({item})")).collect();
+    write!(f, "{}", items.join(&format!(" {op} ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::field;
+    use crate::dialect::DialectKind;
+
+    #[test]
+    fn test_render() {
+        let predicate = field("name")
+            .like("%o'k%")
+            .and(field("id").is_in(Vec::<i64>::new()))
+            .and(field("type").not_in(["a", "b"]));
+        assert_eq!(
+            predicate.to_sql(),
+            "(name like '%o''k%') and (1 != 1) and (type not in ('a','b'))"
+        );
+
+        let predicate = field("created_at")
+            .during("2024-01-01", "2024-02-01T08:00:00+08:00")
+            .or(field("updated_at").last(7, "day"));
+        assert_eq!(
+            predicate.to_sql(),
+            "((created_at >= '2024-01-01'::date and created_at < '2024-02-01T08:00:00+08:00'::timestamptz)) \
+             or (updated_at >= now() - interval '7 day')"
+        );
+    }
+    #[test]
+    fn test_dialect_render() {
+        let predicate = field("r.name")
+            .like("%o\\'k%")
+            .and(field("created_at").after("2024-01-01T08:00:00+08:00"))
+            .and(field("updated_at").last(1, "week"));
+        assert_eq!(
+            predicate
+                .to_dialect_sql(DialectKind::MySql.dialect().as_ref())
+                .unwrap(),
+            "(`r`.`name` like '%o\\\\''k%') and (`created_at` > TIMESTAMP '2024-01-01 00:00:00') \
+             and (`updated_at` >= now() - INTERVAL 1 WEEK)"
+        );
+        assert_eq!(
+            predicate
+                .to_dialect_sql(DialectKind::Sqlite.dialect().as_ref())
+                .unwrap(),
+            "(\"r\".\"name\" like '%o\\''k%') \
+             and (datetime(\"created_at\") > datetime('2024-01-01T08:00:00+08:00')) \
+             and (datetime(\"updated_at\") >= datetime('now', '-7 day'))"
+        );
+
+        let dialect = DialectKind::Sqlite.dialect();
+        assert!(field("lower(name)")
+            .eq("a")
+            .to_dialect_sql(dialect.as_ref())
+            .is_err());
+        assert!(field("t")
+            .before("yesterday")
+            .to_dialect_sql(dialect.as_ref())
+            .is_err());
+    }
+}
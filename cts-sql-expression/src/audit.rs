@@ -0,0 +1,310 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{FromRow, Pool, Postgres};
+
+use crate::error::CtsError;
+use crate::expression::ID;
+
+/// # 审计配置
+/// > 开启后保存、修改、删除构造器在写入数据的同一条语句中写入审计记录，
+/// > 数据写入失败时审计记录也不会写入
+/// ```json
+/// {"table": "cts_audit_log", "schema": "audit"}
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditConfig {
+    /// 审计表名称
+    #[serde(default = "default_table")]
+    pub table: String,
+    /// 审计表schema，默认与数据表相同
+    pub schema: Option<String>,
+}
+
+fn default_table() -> String {
+    "cts_audit_log".to_string()
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self {
+            table: default_table(),
+            schema: None,
+        }
+    }
+}
+
+/// 操作类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditOperation {
+    Insert,
+    Update,
+    Delete,
+}
+
+impl AuditOperation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditOperation::Insert => "insert",
+            AuditOperation::Update => "update",
+            AuditOperation::Delete => "delete",
+        }
+    }
+}
+
+/// 审计记录
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditRecord {
+    pub id: i64,
+    pub table_schema: String,
+    pub table_name: String,
+    pub record_id: String,
+    pub operation: String,
+    pub actor: Option<String>,
+    /// 修改前的数据，新增时为空
+    pub before: Option<Value>,
+    /// 修改后的数据，删除时为空
+    pub after: Option<Value>,
+    /// ISO 8601 格式的操作时间
+    pub created_at: String,
+}
+
+/// # 审计写入
+/// > 通过 `ExpressionConfig::auditor` 获取，没有开启审计时为空
+/// ```rust
+/// use cts_sql_expression::audit::AuditConfig;
+/// use cts_sql_expression::config::ExpressionConfig;
+///
+/// let config = ExpressionConfig::new(None).with_audit(AuditConfig::default());
+/// let auditor = config.auditor(Some("admin".to_string())).unwrap();
+/// assert_eq!(auditor.table(), "public.cts_audit_log");
+/// ```
+/// @param schema 审计表schema
+/// @param table 审计表名称
+/// @param actor 操作人
+#[derive(Debug, Clone)]
+pub struct Auditor {
+    schema: String,
+    table: String,
+    actor: Option<String>,
+}
+
+impl Auditor {
+    pub fn new(schema: String, table: String, actor: Option<String>) -> Self {
+        Self {
+            schema,
+            table,
+            actor,
+        }
+    }
+
+    /// 修改操作人
+    /// @param actor 操作人
+    pub fn with_actor(mut self, actor: Option<String>) -> Self {
+        self.actor = actor;
+        self
+    }
+
+    /// 审计表全名
+    pub fn table(&self) -> String {
+        format!("{}.{}", self.schema, self.table)
+    }
+
+    /// 建表语句，按数据表以及数据id查询历史
+    pub fn create_table_sql(&self) -> Vec<String> {
+        vec![
+            format!(
+                "CREATE TABLE IF NOT EXISTS {} (id bigserial PRIMARY KEY, table_schema text NOT NULL, \
+                 table_name text NOT NULL, record_id text NOT NULL, operation text NOT NULL, actor text, \
+                 before jsonb, after jsonb, created_at timestamptz NOT NULL DEFAULT now())",
+                self.table()
+            ),
+            format!(
+                "CREATE INDEX IF NOT EXISTS {}_record_idx ON {} (table_schema, table_name, record_id)",
+                self.table,
+                self.table()
+            ),
+        ]
+    }
+
+    /// 创建审计表
+    /// @param pool 数据库连接池
+    pub async fn create_table(&self, pool: &Pool<Postgres>) -> Result<(), CtsError> {
+        for sql in self.create_table_sql() {
+            sqlx::query(&sql).execute(pool).await?;
+        }
+        Ok(())
+    }
+
+    /// # 包装写入语句
+    /// > 写入语句放在 cte 中返回变化的数据，外层语句写入审计表，两者在同一事务中执行，
+    /// > 修改时 before 读取语句执行前的快照，返回的影响行数等于数据变化的行数
+    /// ```sql
+    /// WITH changed AS (UPDATE public.road SET ... RETURNING *),
+    /// before AS (SELECT * FROM public.road WHERE ID = '1')
+    /// INSERT INTO public.cts_audit_log (...) SELECT ... FROM changed LEFT JOIN before ON ...
+    /// ```
+    /// @param sql 写入语句，不能包含 returning
+    /// @param operation 操作类型
+    /// @param schema 数据表schema
+    /// @param table 数据表名称
+    /// @param id 修改时的数据id，用于读取修改前的数据
+    pub fn wrap(
+        &self,
+        sql: &str,
+        operation: AuditOperation,
+        schema: &str,
+        table: &str,
+        id: Option<&str>,
+    ) -> String {
+        let (before, after, join) = match (operation, id) {
+            (AuditOperation::Insert, _) => ("NULL", "to_jsonb(changed)", String::new()),
+            (AuditOperation::Delete, _) => ("to_jsonb(changed)", "NULL", String::new()),
+            (AuditOperation::Update, None) => ("NULL", "to_jsonb(changed)", String::new()),
+            (AuditOperation::Update, Some(id)) => (
+                "to_jsonb(before)",
+                "to_jsonb(changed)",
+                format!(
+                    ", before AS (SELECT * FROM {schema}.{table} WHERE {ID} = {})",
+                    quote(id)
+                ),
+            ),
+        };
+        let from = match join.is_empty() {
+            true => "changed".to_string(),
+            false => format!("changed LEFT JOIN before ON before.{ID} = changed.{ID}"),
+        };
+        format!(
+            "WITH changed AS ({sql} RETURNING *){join} INSERT INTO {} \
+             (table_schema, table_name, record_id, operation, actor, before, after) \
+             SELECT {}, {}, changed.{ID}::text, '{}', {}, {before}, {after} FROM {from}",
+            self.table(),
+            quote(schema),
+            quote(table),
+            operation.as_str(),
+            self.actor_sql(),
+        )
+    }
+
+    /// # 包装 upsert 语句
+    /// > 按 xmax 区分插入以及修改，修改时 before 读取语句执行前的快照，
+    /// > 外层语句与 upsert 一样返回数据id以及是否插入，冲突时没有修改的数据不会记录
+    /// ```sql
+    /// WITH changed AS (INSERT INTO public.road ... ON CONFLICT ... RETURNING *, (xmax = 0) AS cts_inserted),
+    /// before AS (SELECT * FROM public.road WHERE ID IN (SELECT ID FROM changed)),
+    /// audit AS (INSERT INTO public.cts_audit_log (...) SELECT ... FROM changed LEFT JOIN before ON ...)
+    /// SELECT ID::text, cts_inserted AS inserted FROM changed
+    /// ```
+    /// @param sql upsert 语句，不能包含 returning
+    /// @param schema 数据表schema
+    /// @param table 数据表名称
+    pub fn wrap_upsert(&self, sql: &str, schema: &str, table: &str) -> String {
+        format!(
+            "WITH changed AS ({sql} RETURNING *, (xmax = 0) AS cts_inserted), \
+             before AS (SELECT * FROM {schema}.{table} WHERE {ID} IN (SELECT {ID} FROM changed)), \
+             audit AS (INSERT INTO {} \
+             (table_schema, table_name, record_id, operation, actor, before, after) \
+             SELECT {}, {}, changed.{ID}::text, CASE WHEN changed.cts_inserted THEN '{}' ELSE '{}' END, \
+             {}, to_jsonb(before), to_jsonb(changed) - 'cts_inserted' \
+             FROM changed LEFT JOIN before ON before.{ID} = changed.{ID}) \
+             SELECT {ID}::text, cts_inserted AS inserted FROM changed",
+            self.table(),
+            quote(schema),
+            quote(table),
+            AuditOperation::Insert.as_str(),
+            AuditOperation::Update.as_str(),
+            self.actor_sql(),
+        )
+    }
+
+    // 操作人常量，没有操作人时为 NULL
+    fn actor_sql(&self) -> String {
+        match &self.actor {
+            None => "NULL".to_string(),
+            Some(actor) => quote(actor),
+        }
+    }
+
+    /// 查询数据的变更历史，按操作时间排序
+    /// @param pool 数据库连接池
+    /// @param schema 数据表schema
+    /// @param table 数据表名称
+    /// @param id 数据id
+    pub async fn history(
+        &self,
+        pool: &Pool<Postgres>,
+        schema: &str,
+        table: &str,
+        id: &str,
+    ) -> Result<Vec<AuditRecord>, CtsError> {
+        let sql = format!(
+            "SELECT id, table_schema, table_name, record_id, operation, actor, before, after, \
+             to_json(created_at) #>> '{{}}' AS created_at FROM {} \
+             WHERE table_schema = $1 AND table_name = $2 AND record_id = $3 ORDER BY created_at, id",
+            self.table()
+        );
+        let list = sqlx::query_as::<_, AuditRecord>(&sql)
+            .bind(schema)
+            .bind(table)
+            .bind(id)
+            .fetch_all(pool)
+            .await?;
+        Ok(list)
+    }
+}
+
+// 字符串常量，转义单引号
+fn quote(data: &str) -> String {
+    format!("'{}'", data.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrap() {
+        let auditor = Auditor::new(
+            "public".to_string(),
+            "cts_audit_log".to_string(),
+            Some("O'Neil".to_string()),
+        );
+        let sql = auditor.wrap(
+            "UPDATE public.road SET name = 'a' WHERE id = '1'",
+            AuditOperation::Update,
+            "public",
+            "road",
+            Some("1"),
+        );
+        assert_eq!(
+            sql,
+            "WITH changed AS (UPDATE public.road SET name = 'a' WHERE id = '1' RETURNING *), \
+             before AS (SELECT * FROM public.road WHERE ID = '1') \
+             INSERT INTO public.cts_audit_log \
+             (table_schema, table_name, record_id, operation, actor, before, after) \
+             SELECT 'public', 'road', changed.ID::text, 'update', 'O''Neil', to_jsonb(before), \
+             to_jsonb(changed) FROM changed LEFT JOIN before ON before.ID = changed.ID"
+        );
+
+        let auditor = Auditor::new("audit".to_string(), "log".to_string(), None);
+        let sql = auditor.wrap(
+            "DELETE FROM public.road WHERE id = $1",
+            AuditOperation::Delete,
+            "public",
+            "road",
+            None,
+        );
+        assert!(sql.starts_with("WITH changed AS (DELETE FROM public.road WHERE id = $1 RETURNING *) INSERT INTO audit.log"));
+        assert!(sql.ends_with("'delete', NULL, to_jsonb(changed), NULL FROM changed"));
+
+        let sql = auditor.wrap_upsert(
+            "INSERT INTO public.road (ID, name) VALUES ('1', 'a') ON CONFLICT (ID) DO UPDATE SET name = EXCLUDED.name",
+            "public",
+            "road",
+        );
+        assert!(sql.starts_with("WITH changed AS (INSERT INTO public.road (ID, name) VALUES ('1', 'a') ON CONFLICT (ID) DO UPDATE SET name = EXCLUDED.name RETURNING *, (xmax = 0) AS cts_inserted)"));
+        assert!(sql.ends_with("SELECT ID::text, cts_inserted AS inserted FROM changed"));
+    }
+}
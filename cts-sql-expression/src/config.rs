@@ -1,9 +1,100 @@
+use std::sync::Arc;
+
+use cts_pgrow::SerializeOptions;
 use serde::{Deserialize, Serialize};
 
+use crate::ast::Predicate;
+use crate::audit::{AuditConfig, Auditor};
+use crate::dialect::DialectKind;
+use crate::error::CtsError;
+use crate::error::CtsError::ParamError;
+use crate::layer::VirtualLayer;
+use crate::limits::QueryLimits;
+use crate::metadata::cache::MetadataCache;
+use crate::policy::{FieldPolicies, FieldPolicy};
+use crate::tenant::{TenantContext, TenantResolver};
+use crate::timezone::TimeZone;
+
 #[derive(Debug,Serialize,Deserialize,Clone)]
 pub struct ExpressionConfig {
     pub schema: Option<String>,
     pub query_mode: QueryMode,
+    /// 结果序列化配置，按类型或者列名自定义输出格式
+    #[serde(skip)]
+    pub serialize_options: Option<Arc<SerializeOptions>>,
+    /// 表结构缓存，没有设置时使用全局共享缓存
+    #[serde(skip)]
+    pub metadata_cache: Option<Arc<MetadataCache>>,
+    /// 关联表配置，请求参数只能使用配置过的关联
+    #[serde(default)]
+    pub joins: Vec<JoinConfig>,
+    /// 虚拟图层，查询表名与图层名称相同时使用图层
+    #[serde(default)]
+    pub layers: Vec<VirtualLayer>,
+    /// 强制过滤条件，与请求过滤条件 and 合并，作用于查询、统计、按id修改以及删除
+    #[serde(default)]
+    pub predicates: Vec<Predicate>,
+    /// 字段权限
+    #[serde(default)]
+    pub field_policies: Vec<FieldPolicy>,
+    /// 租户识别，识别出的schema替换默认schema
+    #[serde(skip)]
+    pub tenant_resolver: Option<Arc<dyn TenantResolver>>,
+    /// 租户schema白名单
+    #[serde(default)]
+    pub tenant_schemas: Vec<String>,
+    /// 查询以及保存、修改、删除时在事务中设置 search_path 为当前schema，虚拟图层模板中可以省略schema
+    #[serde(default)]
+    pub search_path: bool,
+    /// 数据库方言，DialectSqlBuilder 使用
+    #[serde(default)]
+    pub dialect: DialectKind,
+    /// 查询限制，分页大小、最大返回行数以及语句超时时间
+    #[serde(default)]
+    pub limits: QueryLimits,
+    /// 时区，用于审计字段以及 date_trunc 分组，没有设置时审计字段使用服务器时区，
+    /// date_trunc 使用数据库会话时区
+    #[serde(default)]
+    pub timezone: Option<TimeZone>,
+    /// 审计配置，没有设置时不记录变更历史
+    #[serde(default)]
+    pub audit: Option<AuditConfig>,
+}
+
+/// # 关联表配置
+/// > 主表 source 通过 local_key 关联 table 的 foreign_key
+/// - one 一对一，关联数据作为对象返回，没有关联数据时为null，等同于left join
+/// - many 一对多，关联数据聚合成数组返回
+/// ```json
+/// {"name": "attachments", "source": "road", "table": "road_file",
+///  "localKey": "id", "foreignKey": "road_id", "kind": "many"}
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct JoinConfig {
+    /// 关联名称，请求参数以及返回结果中使用
+    pub name: String,
+    /// 主表名称
+    pub source: String,
+    /// 关联表名称
+    pub table: String,
+    /// 关联表schema，默认与主表相同
+    pub schema: Option<String>,
+    /// 主表关联字段
+    pub local_key: String,
+    /// 关联表关联字段
+    pub foreign_key: String,
+    /// 关联方式
+    pub kind: JoinKind,
+    /// 允许返回的关联表字段，默认全部非空间字段
+    pub fields: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum JoinKind {
+    One,
+    Many,
 }
 
 #[derive(Debug,Serialize,Deserialize,Clone)]
@@ -14,11 +105,193 @@ pub enum QueryMode {
 
 impl ExpressionConfig {
     pub fn new_normal(schema: Option<String>) -> Self {
-        Self { schema, query_mode: QueryMode::Normal }
+        Self::with_mode(schema, QueryMode::Normal)
     }
 
     pub fn new(schema: Option<String>) -> Self {
-        Self { schema, query_mode: QueryMode::Spatial }
+        Self::with_mode(schema, QueryMode::Spatial)
+    }
+
+    fn with_mode(schema: Option<String>, query_mode: QueryMode) -> Self {
+        Self {
+            schema,
+            query_mode,
+            serialize_options: None,
+            metadata_cache: None,
+            joins: Vec::new(),
+            layers: Vec::new(),
+            predicates: Vec::new(),
+            field_policies: Vec::new(),
+            tenant_resolver: None,
+            tenant_schemas: Vec::new(),
+            search_path: false,
+            dialect: DialectKind::Postgres,
+            limits: QueryLimits::default(),
+            timezone: None,
+            audit: None,
+        }
+    }
+
+    /// 添加关联表配置
+    pub fn with_join(mut self, join: JoinConfig) -> Self {
+        self.joins.push(join);
+        self
+    }
+
+    /// 添加强制过滤条件，例如只能查询当前机构的数据
+    /// ```rust
+    /// use cts_sql_expression::ast::field;
+    /// use cts_sql_expression::config::ExpressionConfig;
+    ///
+    /// let config = ExpressionConfig::new(None)
+    ///     .with_predicate(field("org_id").eq("org-1"))
+    ///     .with_predicate(field("geom").intersects("POLYGON((0 0,0 1,1 1,1 0,0 0))", 4326));
+    /// ```
+    pub fn with_predicate(mut self, predicate: Predicate) -> Self {
+        self.predicates.push(predicate);
+        self
+    }
+
+    /// 强制过滤条件sql，没有条件时返回空
+    pub fn predicate_sql(&self) -> Option<String> {
+        Predicate::and_all(self.predicates.iter().cloned()).map(|item| item.to_sql())
+    }
+
+    /// 添加字段权限
+    pub fn with_field_policy(mut self, policy: FieldPolicy) -> Self {
+        self.field_policies.push(policy);
+        self
+    }
+
+    /// 获取表的字段权限
+    pub fn field_policies(&self, table: &str) -> FieldPolicies {
+        FieldPolicies::for_table(&self.field_policies, table)
+    }
+
+    /// 设置租户识别
+    pub fn with_tenant_resolver(mut self, resolver: Arc<dyn TenantResolver>) -> Self {
+        self.tenant_resolver = Some(resolver);
+        self
+    }
+
+    /// 设置租户schema白名单
+    pub fn with_tenant_schemas(mut self, schemas: Vec<String>) -> Self {
+        self.tenant_schemas = schemas;
+        self
+    }
+
+    /// 查询以及写入时设置 search_path
+    pub fn with_search_path(mut self, search_path: bool) -> Self {
+        self.search_path = search_path;
+        self
+    }
+
+    /// 设置数据库方言
+    pub fn with_dialect(mut self, dialect: DialectKind) -> Self {
+        self.dialect = dialect;
+        self
+    }
+
+    /// 设置查询限制
+    pub fn with_limits(mut self, limits: QueryLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// 设置时区
+    pub fn with_timezone(mut self, timezone: TimeZone) -> Self {
+        self.timezone = Some(timezone);
+        self
+    }
+
+    /// 开启审计
+    pub fn with_audit(mut self, audit: AuditConfig) -> Self {
+        self.audit = Some(audit);
+        self
+    }
+
+    /// 审计写入，没有开启审计时返回空，审计表默认使用当前schema
+    /// @param actor 操作人
+    pub fn auditor(&self, actor: Option<String>) -> Option<Auditor> {
+        self.audit.as_ref().map(|audit| {
+            let schema = audit.schema.clone().unwrap_or_else(|| self.schema());
+            Auditor::new(schema, audit.table.to_string(), actor)
+        })
+    }
+
+    /// 按请求上下文识别租户，返回使用租户schema的配置，
+    /// 查询、保存、修改、删除构造器都使用返回配置的 schema()，
+    /// 没有设置租户识别时返回原配置，无法识别或者不在白名单中返回错误
+    /// @param context 请求上下文
+    pub fn resolve_tenant(&self, context: &TenantContext) -> Result<ExpressionConfig, CtsError> {
+        let mut config = self.clone();
+        let resolver = match &self.tenant_resolver {
+            None => return Ok(config),
+            Some(data) => data,
+        };
+        let schema = resolver
+            .resolve(context)
+            .ok_or(ParamError("无法识别租户".to_string()))?;
+        if !self.tenant_schemas.contains(&schema) {
+            return Err(ParamError(format!("租户{schema}不存在")));
+        }
+        config.schema = Some(schema);
+        Ok(config)
+    }
+
+    /// 注册虚拟图层
+    pub fn with_layer(mut self, layer: VirtualLayer) -> Self {
+        self.layers.push(layer);
+        self
+    }
+
+    /// 查找虚拟图层
+    pub fn find_layer(&self, name: &str) -> Option<&VirtualLayer> {
+        self.layers.iter().find(|item| item.name == name)
+    }
+
+    /// 查找主表的关联配置
+    /// @param source 主表名称
+    /// @param name 关联名称
+    pub fn find_join(&self, source: &str, name: &str) -> Option<&JoinConfig> {
+        self.joins
+            .iter()
+            .find(|item| item.source == source && item.name == name)
+    }
+
+    /// 设置结果序列化配置
+    pub fn with_serialize_options(mut self, options: SerializeOptions) -> Self {
+        self.serialize_options = Some(Arc::new(options));
+        self
+    }
+
+    /// 获取结果序列化配置，没有设置时使用默认配置
+    /// > 序列化配置没有设置时区时，timestamptz 按配置的时区输出
+    pub fn serialize_options(&self) -> Arc<SerializeOptions> {
+        let mut options = match &self.serialize_options {
+            None => Arc::new(SerializeOptions::default()),
+            Some(data) => data.clone(),
+        };
+        if let Some(timezone) = self.timezone {
+            if options.timezone.is_none() {
+                Arc::make_mut(&mut options).timezone = Some(timezone.offset());
+            }
+        }
+        options
+    }
+
+    /// 设置表结构缓存
+    pub fn with_metadata_cache(mut self, cache: Arc<MetadataCache>) -> Self {
+        self.metadata_cache = Some(cache);
+        self
+    }
+
+    /// 获取表结构缓存，没有设置时使用全局共享缓存
+    pub fn metadata_cache(&self) -> Arc<MetadataCache> {
+        match &self.metadata_cache {
+            None => MetadataCache::global(),
+            Some(data) => data.clone(),
+        }
     }
 
     pub fn schema(&self) -> String {
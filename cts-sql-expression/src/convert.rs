@@ -2,16 +2,23 @@ pub mod csv;
 pub mod geojson;
 pub mod json;
 
+use std::sync::Arc;
+
+use crate::error::CtsError;
 use crate::response::{CtsResult, PageValue};
+use cts_pgrow::SerializeOptions;
 use serde_json::{json, Value};
 
 pub trait PgRowConvert {
-    fn convert(&self, data: CtsResult) -> Value;
+    /// 转换查询结果
+    /// @param data 查询结果
+    /// @param options 序列化配置
+    fn convert(&self, data: CtsResult, options: &Arc<SerializeOptions>) -> Result<Value, CtsError>;
 }
 
-pub fn page_to_value<F>(page: PageValue, func: F) -> Value
+pub fn page_to_value<F>(page: PageValue, func: F) -> Result<Value, CtsError>
 where
-    F: Fn(CtsResult) -> Value,
+    F: Fn(CtsResult) -> Result<Value, CtsError>,
 {
     let current_page = page.current_page;
     let page_size = page.page_size;
@@ -19,12 +26,17 @@ where
     let total = page.total;
     let list = page.list;
     // 数量数组
-    let result = func(CtsResult::List(list));
-    json!({
+    let result = func(CtsResult::List(list))?;
+    Ok(json!({
         "currentPage": current_page,
         "pageSize": page_size,
         "pages": pages,
         "total": total,
         "list": result
-    })
+    }))
+}
+
+/// 转换错误
+pub fn convert_error(err: serde_json::Error) -> CtsError {
+    CtsError::ConvertError(format!("结果转换错误: {err}"))
 }
@@ -1,7 +1,9 @@
-use crate::convert::{page_to_value, PgRowConvert};
+use crate::convert::{convert_error, page_to_value, PgRowConvert};
+use crate::error::CtsError;
 use crate::response::CtsResult;
-use cts_pgrow::SerVecPgRow;
+use cts_pgrow::{SerVecPgRow, SerializeOptions};
 use serde_json::Value;
+use std::sync::Arc;
 
 /// csv转换工具
 /// > 将CstResult 转换成 数组[a,b,c,d]或者page
@@ -17,26 +19,26 @@ use serde_json::Value;
 pub struct CsvConvert;
 
 impl PgRowConvert for CsvConvert {
-    fn convert(&self, data: CtsResult) -> Value {
-        handler_result(data)
+    fn convert(&self, data: CtsResult, options: &Arc<SerializeOptions>) -> Result<Value, CtsError> {
+        handler_result(data, options)
     }
 }
 
-fn handler_result(data: CtsResult) -> Value {
+fn handler_result(data: CtsResult, options: &Arc<SerializeOptions>) -> Result<Value, CtsError> {
     match data {
         CtsResult::Single(single) => {
-            let vec_pg = SerVecPgRow::from(single);
-            serde_json::to_value(&vec_pg).unwrap()
+            let vec_pg = SerVecPgRow::from(single).with_options(options.clone());
+            serde_json::to_value(&vec_pg).map_err(convert_error)
         }
         CtsResult::List(list) => {
             let mut result = Vec::new();
             for row in list.into_iter() {
-                let vec_pg = SerVecPgRow::from(row);
-                let value: Value = serde_json::to_value(&vec_pg).unwrap();
+                let vec_pg = SerVecPgRow::from(row).with_options(options.clone());
+                let value: Value = serde_json::to_value(&vec_pg).map_err(convert_error)?;
                 result.push(value)
             }
-            Value::Array(result)
+            Ok(Value::Array(result))
         }
-        CtsResult::Page(page) => page_to_value(page, handler_result),
+        CtsResult::Page(page) => page_to_value(page, |data| handler_result(data, options)),
     }
 }
@@ -1,9 +1,11 @@
-use crate::convert::PgRowConvert;
+use crate::convert::{convert_error, PgRowConvert};
+use crate::error::CtsError;
 use crate::expression::GEOMETRY;
 use crate::response::CtsResult;
-use cts_pgrow::SerMapPgRow;
+use cts_pgrow::{SerMapPgRow, SerializeOptions};
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
+use std::sync::Arc;
 
 
 /// geojson转换工具
@@ -25,7 +27,18 @@ use serde_json::{Map, Value};
 ///         ]
 ///      }
 /// ```
-pub struct GeoJsonConvert;
+/// - geometry 空间字段名称，第一个作为要素空间数据，其他空间字段解析成 GeoJSON 对象放入 properties
+pub struct GeoJsonConvert {
+    pub geometry: Vec<String>,
+}
+
+impl Default for GeoJsonConvert {
+    fn default() -> Self {
+        Self {
+            geometry: vec![GEOMETRY.to_string()],
+        }
+    }
+}
 
 #[derive(Debug, Serialize, Deserialize)]
 struct Feature {
@@ -51,21 +64,29 @@ impl FeatureCollection {
 }
 
 impl PgRowConvert for GeoJsonConvert {
-    fn convert(&self, data: CtsResult) -> Value {
-        handler_result(data)
+    fn convert(
+        &self,
+        data: CtsResult,
+        options: &Arc<SerializeOptions>,
+    ) -> Result<Value, CtsError> {
+        handler_result(data, options, &self.geometry)
     }
 }
 
-fn handler_result(data: CtsResult) -> Value {
+fn handler_result(
+    data: CtsResult,
+    options: &Arc<SerializeOptions>,
+    geometry_keys: &[String],
+) -> Result<Value, CtsError> {
     match data {
         CtsResult::Single(single) => {
-            handler_result(CtsResult::List(vec![single]))
+            handler_result(CtsResult::List(vec![single]), options, geometry_keys)
         }
         CtsResult::List(list) => {
             let mut result = Vec::new();
             for (index, row) in list.into_iter().enumerate() {
-                let row_map = SerMapPgRow::from(row);
-                let value = row_map.into();
+                let row_map = SerMapPgRow::from(row).with_options(options.clone());
+                let value = Value::try_from(row_map).map_err(convert_error)?;
                 let mut geometry = Value::String("".to_string());
                 let mut properties = Map::new();
                 // 判断是否为对象
@@ -73,10 +94,21 @@ fn handler_result(data: CtsResult) -> Value {
                     // 遍历map对象
                     for (key, value) in map.into_iter() {
                         // 判断是否为空间字段
-                        if key == GEOMETRY {
-                            if let Value::String(data) = value {
-                                geometry = serde_json::from_str(&data).unwrap()
+                        let index = geometry_keys.iter().position(|item| item == &key);
+                        if index == Some(0) {
+                            // json 类型的空间数据已经是对象，文本才需要解析
+                            match value {
+                                Value::String(data) => {
+                                    geometry =
+                                        serde_json::from_str(&data).map_err(convert_error)?
+                                }
+                                Value::Object(_) => geometry = value,
+                                _ => {}
                             }
+                        } else if let (Some(_), Value::String(data)) = (index, &value) {
+                            // 其他空间字段
+                            let data = serde_json::from_str(data).map_err(convert_error)?;
+                            properties.insert(key, data);
                         } else {
                             // 插入map对象
                             properties.insert(key, value);
@@ -93,11 +125,11 @@ fn handler_result(data: CtsResult) -> Value {
             }
             // 创建对象
             let feature_collection = FeatureCollection::new(result);
-            serde_json::to_value(feature_collection).unwrap()
+            serde_json::to_value(feature_collection).map_err(convert_error)
         }
         CtsResult::Page(page) => {
             let list = page.list;
-            handler_result(CtsResult::List(list))
+            handler_result(CtsResult::List(list), options, geometry_keys)
         }
     }
 }
@@ -1,7 +1,9 @@
-use crate::convert::{page_to_value, PgRowConvert};
+use crate::convert::{convert_error, page_to_value, PgRowConvert};
+use crate::error::CtsError;
 use crate::response::CtsResult;
-use cts_pgrow::SerMapPgRow;
+use cts_pgrow::{SerMapPgRow, SerializeOptions};
 use serde_json::Value;
+use std::sync::Arc;
 
 /// json转换工具
 /// > 将CstResult 转换成json或者page
@@ -33,26 +35,26 @@ use serde_json::Value;
 /// ```
 pub struct JsonConvert;
 impl PgRowConvert for JsonConvert {
-    fn convert(&self, data: CtsResult) -> Value {
-        handler_result(data)
+    fn convert(&self, data: CtsResult, options: &Arc<SerializeOptions>) -> Result<Value, CtsError> {
+        handler_result(data, options)
     }
 }
 
-fn handler_result(data: CtsResult) -> Value {
+fn handler_result(data: CtsResult, options: &Arc<SerializeOptions>) -> Result<Value, CtsError> {
     match data {
         CtsResult::Single(single) => {
-            let row_map = SerMapPgRow::from(single);
-            row_map.into()
+            let row_map = SerMapPgRow::from(single).with_options(options.clone());
+            Value::try_from(row_map).map_err(convert_error)
         }
         CtsResult::List(list) => {
             let mut result = Vec::new();
             for row in list.into_iter() {
-                let row_map = SerMapPgRow::from(row);
-                let value = row_map.into();
+                let row_map = SerMapPgRow::from(row).with_options(options.clone());
+                let value = Value::try_from(row_map).map_err(convert_error)?;
                 result.push(value)
             }
-            Value::Array(result)
+            Ok(Value::Array(result))
         }
-        CtsResult::Page(page) => page_to_value(page, handler_result),
+        CtsResult::Page(page) => page_to_value(page, |data| handler_result(data, options)),
     }
 }
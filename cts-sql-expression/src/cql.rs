@@ -0,0 +1,55 @@
+pub mod geometry;
+pub mod json;
+pub mod parse;
+pub mod print;
+
+use serde_json::Value;
+
+use crate::error::CtsError;
+use crate::expression::CtsValue;
+
+/// # 文本查询语言
+/// > 类似 CQL2-text 以及 sql where 的文本过滤条件，编译成 filter_parse 使用的过滤参数，也可以把过滤参数输出成文本，
+/// > 方便在 url 以及保存的查询中使用。关键字不区分大小写，字段名称只支持字母、数字、下划线以及点
+/// ```txt
+/// status = 'open' AND area > 100 AND INTERSECTS(geom, POINT(120 30))
+/// name LIKE '%路' OR NOT (level BETWEEN 1 AND 3)
+/// type IN ('a', 'b') AND deleted_at IS NULL
+/// ```
+/// ```rust
+/// use cts_sql_expression::cql::{parse_text, to_text};
+/// use cts_sql_expression::expression::parse::filter::filter_parse;
+///
+/// let filter = parse_text("status = 'open' and area > 100").unwrap();
+/// assert_eq!(
+///     filter_parse(&filter).unwrap().unwrap(),
+///     "(status = 'open') and (area > 100)"
+/// );
+/// assert_eq!(to_text(&filter).unwrap(), "status = 'open' AND area > 100");
+/// ```
+pub fn parse_text(text: &str) -> Result<Vec<CtsValue>, CtsError> {
+    parse::Parser::new(text)?.parse()
+}
+
+/// 将过滤参数输出成文本查询语言
+pub fn to_text(filter: &[CtsValue]) -> Result<String, CtsError> {
+    print::print(filter)
+}
+
+/// OGC CQL2-JSON 转换成过滤参数，支持逻辑、比较、like、between、in、isNull、
+/// s_intersects 以及 t_intersects、t_before、t_after、t_during、t_equals，其他操作符返回错误
+/// ```rust
+/// use cts_sql_expression::cql::{parse_json, to_text};
+///
+/// let data = serde_json::json!({"op": "<>", "args": [{"property": "status"}, "closed"]});
+/// let filter = parse_json(&data).unwrap();
+/// assert_eq!(to_text(&filter).unwrap(), "status <> 'closed'");
+/// ```
+pub fn parse_json(data: &Value) -> Result<Vec<CtsValue>, CtsError> {
+    json::from_json(data)
+}
+
+/// 将过滤参数输出成 OGC CQL2-JSON
+pub fn to_json(filter: &[CtsValue]) -> Result<Value, CtsError> {
+    json::to_json(filter)
+}
@@ -0,0 +1,279 @@
+use serde_json::{json, Number, Value};
+
+use crate::error::CtsError;
+use crate::error::CtsError::FilterError;
+
+/// GeoJSON 几何对象或者 CQL2 的 {"bbox": [...]} 转换成 WKT
+pub fn geojson_to_wkt(data: &Value) -> Result<String, CtsError> {
+    if let Some(bbox) = data.get("bbox").filter(|_| data.get("type").is_none()) {
+        return bbox_to_wkt(bbox);
+    }
+    let kind = data
+        .get("type")
+        .and_then(Value::as_str)
+        .ok_or(geometry_error())?;
+    if kind == "GeometryCollection" {
+        let list = data
+            .get("geometries")
+            .and_then(Value::as_array)
+            .ok_or(geometry_error())?
+            .iter()
+            .map(geojson_to_wkt)
+            .collect::<Result<Vec<_>, _>>()?;
+        return Ok(format!("GEOMETRYCOLLECTION({})", list.join(", ")));
+    }
+    let coordinates = data.get("coordinates").ok_or(geometry_error())?;
+    // 坐标嵌套层数
+    let depth = match kind {
+        "Point" => 0,
+        "LineString" | "MultiPoint" => 1,
+        "Polygon" | "MultiLineString" => 2,
+        "MultiPolygon" => 3,
+        _ => return Err(FilterError(format!("不支持的空间对象类型{kind}"))),
+    };
+    let text = match kind {
+        "Point" => format!("({})", position(coordinates)?),
+        _ => coordinates_text(coordinates, depth)?,
+    };
+    Ok(format!("{}{text}", kind.to_uppercase()))
+}
+
+/// WKT 转换成 GeoJSON 几何对象
+pub fn wkt_to_geojson(wkt: &str) -> Result<Value, CtsError> {
+    let mut reader = WktReader {
+        tokens: tokenize(wkt)?,
+        index: 0,
+    };
+    let geometry = reader.geometry()?;
+    match reader.tokens.get(reader.index) {
+        None => Ok(geometry),
+        Some(_) => Err(geometry_error()),
+    }
+}
+
+fn bbox_to_wkt(bbox: &Value) -> Result<String, CtsError> {
+    let list: Vec<&Number> = bbox
+        .as_array()
+        .ok_or(geometry_error())?
+        .iter()
+        .map(|item| item.as_number().ok_or(geometry_error()))
+        .collect::<Result<_, _>>()?;
+    // 三维范围忽略高程
+    let (minx, miny, maxx, maxy) = match list.as_slice() {
+        [minx, miny, maxx, maxy] => (minx, miny, maxx, maxy),
+        [minx, miny, _, maxx, maxy, _] => (minx, miny, maxx, maxy),
+        _ => return Err(FilterError("bbox必须为4个或者6个数字".to_string())),
+    };
+    Ok(format!(
+        "POLYGON(({minx} {miny}, {maxx} {miny}, {maxx} {maxy}, {minx} {maxy}, {minx} {miny}))"
+    ))
+}
+
+fn coordinates_text(data: &Value, depth: usize) -> Result<String, CtsError> {
+    let list = data.as_array().ok_or(geometry_error())?;
+    let items = list
+        .iter()
+        .map(|item| match depth {
+            1 => position(item),
+            _ => coordinates_text(item, depth - 1),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(format!("({})", items.join(", ")))
+}
+
+fn position(data: &Value) -> Result<String, CtsError> {
+    let list = data.as_array().ok_or(geometry_error())?;
+    if list.len() < 2 || list.iter().any(|item| !item.is_number()) {
+        return Err(geometry_error());
+    }
+    Ok(list
+        .iter()
+        .map(|item| item.to_string())
+        .collect::<Vec<_>>()
+        .join(" "))
+}
+
+fn geometry_error() -> CtsError {
+    FilterError("空间对象格式错误".to_string())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum WktToken {
+    Word(String),
+    Number(Number),
+    LeftParen,
+    RightParen,
+    Comma,
+}
+
+struct WktReader {
+    tokens: Vec<WktToken>,
+    index: usize,
+}
+
+impl WktReader {
+    fn geometry(&mut self) -> Result<Value, CtsError> {
+        let kind = match self.next() {
+            Some(WktToken::Word(kind)) => kind.to_uppercase(),
+            _ => return Err(geometry_error()),
+        };
+        // 跳过 Z、M、ZM 维度标识
+        while matches!(self.peek(), Some(WktToken::Word(_))) {
+            self.index += 1;
+        }
+        let (name, key, coordinates) = match kind.as_str() {
+            "GEOMETRYCOLLECTION" => {
+                self.expect(&WktToken::LeftParen)?;
+                let mut list = vec![self.geometry()?];
+                while self.peek() == Some(&WktToken::Comma) {
+                    self.index += 1;
+                    list.push(self.geometry()?);
+                }
+                self.expect(&WktToken::RightParen)?;
+                ("GeometryCollection", "geometries", Value::Array(list))
+            }
+            "POINT" => match self.group()? {
+                Value::Array(mut list) if list.len() == 1 => {
+                    ("Point", "coordinates", list.remove(0))
+                }
+                _ => return Err(geometry_error()),
+            },
+            // MULTIPOINT((1 2), (3 4)) 与 MULTIPOINT(1 2, 3 4) 两种写法
+            "MULTIPOINT" => {
+                let list = match self.group()? {
+                    Value::Array(list) => list
+                        .into_iter()
+                        .map(|item| match item {
+                            Value::Array(mut data) if data.len() == 1 && data[0].is_array() => {
+                                data.remove(0)
+                            }
+                            data => data,
+                        })
+                        .collect(),
+                    _ => return Err(geometry_error()),
+                };
+                ("MultiPoint", "coordinates", Value::Array(list))
+            }
+            "LINESTRING" => ("LineString", "coordinates", self.group()?),
+            "POLYGON" => ("Polygon", "coordinates", self.group()?),
+            "MULTILINESTRING" => ("MultiLineString", "coordinates", self.group()?),
+            "MULTIPOLYGON" => ("MultiPolygon", "coordinates", self.group()?),
+            _ => return Err(FilterError(format!("不支持的空间对象类型{kind}"))),
+        };
+        Ok(json!({ "type": name, key: coordinates }))
+    }
+
+    // 括号内的坐标或者坐标数组
+    fn group(&mut self) -> Result<Value, CtsError> {
+        self.expect(&WktToken::LeftParen)?;
+        let mut list = Vec::new();
+        loop {
+            let item = match self.peek() {
+                Some(WktToken::LeftParen) => self.group()?,
+                _ => self.position()?,
+            };
+            list.push(item);
+            match self.next() {
+                Some(WktToken::Comma) => continue,
+                Some(WktToken::RightParen) => break,
+                _ => return Err(geometry_error()),
+            }
+        }
+        Ok(Value::Array(list))
+    }
+
+    fn position(&mut self) -> Result<Value, CtsError> {
+        let mut list = Vec::new();
+        while let Some(WktToken::Number(data)) = self.peek() {
+            list.push(Value::Number(data.clone()));
+            self.index += 1;
+        }
+        match list.len() {
+            0 | 1 => Err(geometry_error()),
+            _ => Ok(Value::Array(list)),
+        }
+    }
+
+    fn peek(&self) -> Option<&WktToken> {
+        self.tokens.get(self.index)
+    }
+
+    fn next(&mut self) -> Option<WktToken> {
+        let token = self.peek().cloned();
+        self.index += 1;
+        token
+    }
+
+    fn expect(&mut self, token: &WktToken) -> Result<(), CtsError> {
+        match self.next() {
+            Some(data) if &data == token => Ok(()),
+            _ => Err(geometry_error()),
+        }
+    }
+}
+
+fn tokenize(wkt: &str) -> Result<Vec<WktToken>, CtsError> {
+    let mut result = Vec::new();
+    let mut chars = wkt.chars().peekable();
+    while let Some(current) = chars.next() {
+        let token = match current {
+            _ if current.is_whitespace() => continue,
+            '(' => WktToken::LeftParen,
+            ')' => WktToken::RightParen,
+            ',' => WktToken::Comma,
+            _ if current.is_ascii_alphabetic() => {
+                let mut word = current.to_string();
+                while let Some(item) = chars.next_if(|item| item.is_ascii_alphabetic()) {
+                    word.push(item);
+                }
+                WktToken::Word(word)
+            }
+            _ if current.is_ascii_digit() || matches!(current, '-' | '+' | '.') => {
+                let mut text = current.to_string();
+                while let Some(item) = chars.next_if(|item| {
+                    item.is_ascii_digit() || matches!(item, '.' | 'e' | 'E' | '-' | '+')
+                }) {
+                    text.push(item);
+                }
+                let number = match text.parse::<i64>() {
+                    Ok(data) => Number::from(data),
+                    Err(_) => text
+                        .parse::<f64>()
+                        .ok()
+                        .and_then(Number::from_f64)
+                        .ok_or(geometry_error())?,
+                };
+                WktToken::Number(number)
+            }
+            _ => return Err(geometry_error()),
+        };
+        result.push(token);
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_geometry() {
+        let polygon = json!({
+            "type": "Polygon",
+            "coordinates": [[[0, 0], [1.5, 0], [1.5, 1], [0, 0]]]
+        });
+        let wkt = geojson_to_wkt(&polygon).unwrap();
+        assert_eq!(wkt, "POLYGON((0 0, 1.5 0, 1.5 1, 0 0))");
+        assert_eq!(wkt_to_geojson(&wkt).unwrap(), polygon);
+
+        let point = wkt_to_geojson("POINT(120 -30.5)").unwrap();
+        assert_eq!(point, json!({"type": "Point", "coordinates": [120, -30.5]}));
+        let points = wkt_to_geojson("MULTIPOINT((1 2), (3 4))").unwrap();
+        assert_eq!(points["coordinates"], json!([[1, 2], [3, 4]]));
+        assert_eq!(
+            geojson_to_wkt(&json!({"bbox": [0, 0, 1, 1]})).unwrap(),
+            "POLYGON((0 0, 1 0, 1 1, 0 1, 0 0))"
+        );
+        assert!(wkt_to_geojson("POINT(1)").is_err());
+    }
+}
@@ -0,0 +1,384 @@
+use serde_json::{json, Map, Value};
+
+use crate::cql::geometry::{geojson_to_wkt, wkt_to_geojson};
+use crate::error::CtsError;
+use crate::error::CtsError::FilterError;
+use crate::expression::parse::filter::spatial::DEFAULT_SRID;
+use crate::expression::parse::filter::temporal::IsoDateTime;
+use crate::expression::parse::handler_name;
+use crate::expression::{CtsValue, Single};
+
+/// CQL2-JSON 转换成过滤参数，错误路径为 CQL2-JSON 中的位置，例如 `.args[1].args[0]`
+pub fn from_json(data: &Value) -> Result<Vec<CtsValue>, CtsError> {
+    let object = data
+        .as_object()
+        .ok_or(FilterError("CQL2表达式必须为对象".to_string()))?;
+    let ope = object
+        .get("op")
+        .and_then(Value::as_str)
+        .ok_or(FilterError("CQL2表达式缺少op".to_string()))?;
+    let args = object
+        .get("args")
+        .and_then(Value::as_array)
+        .ok_or(FilterError("CQL2表达式缺少args".to_string()))?;
+    let filter = match ope {
+        "and" | "or" => {
+            if args.is_empty() {
+                return Err(FilterError("参数错误，参数长度不够".to_string()));
+            }
+            let mut result = vec![name(ope)];
+            for (index, item) in args.iter().enumerate() {
+                let child = from_json(item).map_err(|err| arg_path(err, index))?;
+                result.push(CtsValue::Array(child));
+            }
+            result
+        }
+        "not" => {
+            check_args(args, 1)?;
+            let child = from_json(&args[0]).map_err(|err| arg_path(err, 0))?;
+            vec![name("not"), CtsValue::Array(child)]
+        }
+        "=" | "<>" | "<" | "<=" | ">" | ">=" => {
+            check_args(args, 2)?;
+            let ope = if ope == "<>" { "!=" } else { ope };
+            vec![name(ope), property(args)?, literal(args, 1)?]
+        }
+        "like" => {
+            check_args(args, 2)?;
+            vec![name("like"), property(args)?, literal(args, 1)?]
+        }
+        "between" => {
+            check_args(args, 3)?;
+            vec![
+                name("between"),
+                property(args)?,
+                literal(args, 1)?,
+                literal(args, 2)?,
+            ]
+        }
+        "in" => {
+            check_args(args, 2)?;
+            let values = args[1]
+                .as_array()
+                .ok_or(arg_path(FilterError("IN参数错误".to_string()), 1))?
+                .iter()
+                .enumerate()
+                .map(|(index, item)| {
+                    to_literal(item)
+                        .map_err(|err| arg_path(err, 1).with_path(&format!("[{index}]")))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            vec![name("in"), property(args)?, CtsValue::Array(values)]
+        }
+        "isNull" => {
+            check_args(args, 1)?;
+            vec![name("is null"), property(args)?]
+        }
+        "s_intersects" => {
+            check_args(args, 2)?;
+            let wkt = geojson_to_wkt(&args[1]).map_err(|err| arg_path(err, 1))?;
+            vec![name("intersects"), property(args)?, name(&wkt)]
+        }
+        "t_intersects" | "t_before" | "t_after" | "t_equals" | "t_during" => {
+            check_args(args, 2)?;
+            temporal(ope, &property_name(args)?, &args[1]).map_err(|err| arg_path(err, 1))?
+        }
+        _ => return Err(FilterError(format!("不支持的CQL2操作符{ope}")).with_path(".op")),
+    };
+    Ok(filter)
+}
+
+/// 过滤参数转换成 CQL2-JSON，空间条件只支持4326坐标系
+pub fn to_json(filter: &[CtsValue]) -> Result<Value, CtsError> {
+    if filter.len() < 2 {
+        return Err(FilterError("过滤参数长度不够，至少2位。".to_string()));
+    }
+    let ope = handler_name(&filter[0])?.to_lowercase();
+    let data = match ope.as_str() {
+        "and" | "or" => {
+            let args = filter
+                .iter()
+                .skip(1)
+                .map(|item| children(item).and_then(to_json))
+                .collect::<Result<Vec<_>, _>>()?;
+            expression(&ope, args)
+        }
+        "not" => {
+            let mut args = filter
+                .iter()
+                .skip(1)
+                .map(|item| children(item).and_then(to_json))
+                .collect::<Result<Vec<_>, _>>()?;
+            let inner = match args.len() {
+                1 => args.remove(0),
+                _ => expression("and", args),
+            };
+            expression("not", vec![inner])
+        }
+        ">" | "<" | ">=" | "<=" | "=" | "!=" => {
+            let ope = if ope == "!=" { "<>" } else { ope.as_str() };
+            expression(ope, vec![field(filter)?, value(filter, 2)?])
+        }
+        "like" => expression("like", vec![field(filter)?, value(filter, 2)?]),
+        "in" | "not in" => {
+            let values = match filter.get(2) {
+                Some(CtsValue::Array(list)) => {
+                    list.iter().map(from_literal).collect::<Result<_, _>>()?
+                }
+                _ => vec![value(filter, 2)?],
+            };
+            negate(
+                &ope,
+                expression("in", vec![field(filter)?, Value::Array(values)]),
+            )
+        }
+        "between" | "not between" => {
+            let (low, high) = (value(filter, 2)?, value(filter, 3)?);
+            // 两端都是日期或者时间时按时间区间输出
+            let data = match (low.as_str(), high.as_str()) {
+                (Some(start), Some(end)) if is_temporal(start) && is_temporal(end) => expression(
+                    "t_intersects",
+                    vec![field(filter)?, json!({ "interval": [start, end] })],
+                ),
+                _ => expression("between", vec![field(filter)?, low, high]),
+            };
+            negate(&ope, data)
+        }
+        "is null" | "is not null" => negate(&ope, expression("isNull", vec![field(filter)?])),
+        "before" | "after" => expression(
+            &format!("t_{ope}"),
+            vec![field(filter)?, time_literal(&value(filter, 2)?)?],
+        ),
+        "during" => {
+            let (start, end) = (value(filter, 2)?, value(filter, 3)?);
+            expression(
+                "t_during",
+                vec![field(filter)?, json!({ "interval": [start, end] })],
+            )
+        }
+        "intersects" => {
+            if let Some(srid) = filter.get(3) {
+                if !matches!(srid, CtsValue::Single(Single::Integer(data)) if *data == DEFAULT_SRID)
+                {
+                    return Err(FilterError("CQL2-JSON只支持4326坐标系".to_string()));
+                }
+            }
+            let geometry = wkt_to_geojson(&handler_name(&filter[2])?)?;
+            expression("s_intersects", vec![field(filter)?, geometry])
+        }
+        _ => return Err(FilterError(format!("不支持的过滤操作符{ope}"))),
+    };
+    Ok(data)
+}
+
+// 时间条件，属性为时间点，区间两端可以使用 ".." 表示不限
+pub(crate) fn temporal(ope: &str, field: &str, data: &Value) -> Result<Vec<CtsValue>, CtsError> {
+    let instant = data
+        .get("timestamp")
+        .or(data.get("date"))
+        .and_then(Value::as_str);
+    let interval = match data.get("interval").and_then(Value::as_array) {
+        Some(list) if list.len() == 2 => {
+            Some((interval_bound(&list[0])?, interval_bound(&list[1])?))
+        }
+        Some(_) => return Err(FilterError("时间区间格式错误".to_string())),
+        None => None,
+    };
+    let compare = |ope: &str, value: &str| vec![name(ope), name(field), name(value)];
+    let filter = match (ope, instant, interval) {
+        ("t_equals" | "t_intersects", Some(instant), _) => compare("=", instant),
+        ("t_before", Some(instant), _) | ("t_before", None, Some((Some(instant), _))) => {
+            vec![name("before"), name(field), name(instant)]
+        }
+        ("t_after", Some(instant), _) | ("t_after", None, Some((_, Some(instant)))) => {
+            vec![name("after"), name(field), name(instant)]
+        }
+        ("t_during", None, Some((Some(start), Some(end)))) => {
+            vec![name("during"), name(field), name(start), name(end)]
+        }
+        ("t_during", None, Some((Some(start), None))) => {
+            vec![name("after"), name(field), name(start)]
+        }
+        ("t_during", None, Some((None, Some(end)))) => vec![name("before"), name(field), name(end)],
+        ("t_intersects", None, Some((Some(start), Some(end)))) => {
+            vec![name("between"), name(field), name(start), name(end)]
+        }
+        ("t_intersects", None, Some((Some(start), None))) => compare(">=", start),
+        ("t_intersects", None, Some((None, Some(end)))) => compare("<=", end),
+        ("t_intersects", None, Some((None, None))) => vec![name("is not null"), name(field)],
+        _ => return Err(FilterError(format!("{ope}参数错误"))),
+    };
+    Ok(filter)
+}
+
+// 时间区间的一端，".." 表示不限
+fn interval_bound(data: &Value) -> Result<Option<&str>, CtsError> {
+    match data.as_str() {
+        Some("..") => Ok(None),
+        Some(data) => Ok(Some(data)),
+        None => Err(FilterError("时间区间格式错误".to_string())),
+    }
+}
+
+fn check_args(args: &[Value], len: usize) -> Result<(), CtsError> {
+    if args.len() < len {
+        return Err(FilterError("参数错误，参数长度不够".to_string()));
+    }
+    if args.len() > len {
+        return Err(arg_path(FilterError("参数错误，参数过多".to_string()), len));
+    }
+    Ok(())
+}
+
+fn property(args: &[Value]) -> Result<CtsValue, CtsError> {
+    Ok(name(&property_name(args)?))
+}
+
+// 第一个参数必须为属性
+fn property_name(args: &[Value]) -> Result<String, CtsError> {
+    match args[0].get("property").and_then(Value::as_str) {
+        Some(data) if !data.is_empty() => Ok(data.to_string()),
+        _ => Err(arg_path(
+            FilterError("CQL2参数错误，第一个参数必须为属性".to_string()),
+            0,
+        )),
+    }
+}
+
+fn literal(args: &[Value], index: usize) -> Result<CtsValue, CtsError> {
+    to_literal(&args[index]).map_err(|err| arg_path(err, index))
+}
+
+fn to_literal(data: &Value) -> Result<CtsValue, CtsError> {
+    let value = match data {
+        Value::String(data) => Single::String(data.to_string()),
+        Value::Bool(data) => Single::Bool(*data),
+        Value::Number(data) => match data.as_i64() {
+            Some(data) => Single::Integer(data),
+            None => Single::Double(data.as_f64().unwrap_or_default()),
+        },
+        Value::Object(object) => match time_value(object) {
+            Some(data) => Single::String(data.to_string()),
+            None => return Err(FilterError("CQL2参数错误，只支持常量".to_string())),
+        },
+        _ => return Err(FilterError("CQL2参数错误，只支持常量".to_string())),
+    };
+    Ok(CtsValue::Single(value))
+}
+
+fn time_value(object: &Map<String, Value>) -> Option<&str> {
+    object
+        .get("timestamp")
+        .or(object.get("date"))
+        .and_then(Value::as_str)
+}
+
+fn from_literal(data: &CtsValue) -> Result<Value, CtsError> {
+    match data {
+        CtsValue::Single(value) => Ok(match value {
+            Single::String(data) => json!(data),
+            Single::Integer(data) => json!(data),
+            Single::Double(data) => json!(data),
+            Single::Bool(data) => json!(data),
+        }),
+        CtsValue::Array(_) => Err(FilterError("参数错误".to_string())),
+    }
+}
+
+pub(crate) fn is_temporal(data: &str) -> bool {
+    IsoDateTime::parse(data).is_some()
+}
+
+// 时间常量，日期使用 date，其他使用 timestamp
+fn time_literal(data: &Value) -> Result<Value, CtsError> {
+    match data.as_str().and_then(IsoDateTime::parse) {
+        Some(IsoDateTime::Date(_)) => Ok(json!({ "date": data })),
+        Some(_) => Ok(json!({ "timestamp": data })),
+        None => Err(FilterError("时间格式错误，必须为ISO 8601格式".to_string())),
+    }
+}
+
+fn expression(ope: &str, args: Vec<Value>) -> Value {
+    json!({ "op": ope, "args": args })
+}
+
+// 否定操作符转换成 not 表达式
+fn negate(ope: &str, data: Value) -> Value {
+    if ope.starts_with("not ") || ope == "is not null" {
+        expression("not", vec![data])
+    } else {
+        data
+    }
+}
+
+fn children(data: &CtsValue) -> Result<&[CtsValue], CtsError> {
+    match data {
+        CtsValue::Array(list) => Ok(list),
+        CtsValue::Single(_) => Err(FilterError("参数错误".to_string())),
+    }
+}
+
+fn field(filter: &[CtsValue]) -> Result<Value, CtsError> {
+    Ok(json!({ "property": handler_name(&filter[1])? }))
+}
+
+fn value(filter: &[CtsValue], index: usize) -> Result<Value, CtsError> {
+    let data = filter
+        .get(index)
+        .ok_or(FilterError("参数错误，参数长度不够".to_string()))?;
+    from_literal(data)
+}
+
+fn name(data: &str) -> CtsValue {
+    CtsValue::Single(Single::String(data.to_string()))
+}
+
+fn arg_path(err: CtsError, index: usize) -> CtsError {
+    err.with_path(&format!(".args[{index}]"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expression::parse::filter::filter_parse;
+
+    #[test]
+    fn test_json() {
+        let data = json!({
+            "op": "and",
+            "args": [
+                {"op": "=", "args": [{"property": "status"}, "open"]},
+                {"op": "not", "args": [{"op": "in", "args": [{"property": "type"}, [1, 2]]}]},
+                {"op": "s_intersects", "args": [
+                    {"property": "geom"},
+                    {"type": "Point", "coordinates": [120, 30]}
+                ]},
+                {"op": "t_intersects", "args": [
+                    {"property": "updated_at"},
+                    {"interval": ["2024-01-01", "2024-12-31"]}
+                ]},
+                {"op": "t_before", "args": [
+                    {"property": "created_at"},
+                    {"timestamp": "2024-01-01T00:00:00Z"}
+                ]}
+            ]
+        });
+        let filter = from_json(&data).unwrap();
+        assert_eq!(
+            filter_parse(&filter).unwrap().unwrap(),
+            "(status = 'open') and (not (type in (1,2))) \
+             and (st_intersects(geom, st_geomfromtext('POINT(120 30)', 4326))) \
+             and (updated_at between '2024-01-01' and '2024-12-31') \
+             and (created_at < '2024-01-01T00:00:00+00:00'::timestamptz)"
+        );
+        assert_eq!(to_json(&filter).unwrap(), data);
+
+        let err = from_json(&json!({
+            "op": "or",
+            "args": [{"op": "s_within", "args": []}]
+        }))
+        .unwrap_err();
+        assert_eq!(err.path(), Some(".args[0].op"));
+        assert_eq!(err.to_string(), ".args[0].op: 不支持的CQL2操作符s_within");
+    }
+}
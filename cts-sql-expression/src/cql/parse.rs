@@ -0,0 +1,452 @@
+use crate::error::CtsError;
+use crate::error::CtsError::FilterError;
+use crate::expression::{CtsValue, Single};
+
+/// 词法单元
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    String(String),
+    Integer(i64),
+    Double(f64),
+    /// 比较操作符
+    Operator(&'static str),
+    LeftParen,
+    RightParen,
+    Comma,
+}
+
+/// 词法单元以及在文本中的字节位置
+#[derive(Debug, Clone)]
+struct Lexeme {
+    token: Token,
+    start: usize,
+    end: usize,
+}
+
+/// 文本查询语言解析器，递归下降解析，优先级从低到高为 OR、AND、NOT、条件
+pub struct Parser<'a> {
+    source: &'a str,
+    tokens: Vec<Lexeme>,
+    index: usize,
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(source: &'a str) -> Result<Self, CtsError> {
+        Ok(Self {
+            source,
+            tokens: tokenize(source)?,
+            index: 0,
+        })
+    }
+
+    /// 解析成过滤参数
+    pub fn parse(&mut self) -> Result<Vec<CtsValue>, CtsError> {
+        if self.tokens.is_empty() {
+            return Err(FilterError("查询语句不能为空".to_string()));
+        }
+        let filter = self.parse_or()?;
+        match self.tokens.get(self.index) {
+            None => Ok(filter),
+            Some(_) => Err(self.error("多余的内容")),
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Vec<CtsValue>, CtsError> {
+        let mut list = vec![self.parse_and()?];
+        while self.keyword("or") {
+            list.push(self.parse_and()?);
+        }
+        Ok(logic("or", list))
+    }
+
+    fn parse_and(&mut self) -> Result<Vec<CtsValue>, CtsError> {
+        let mut list = vec![self.parse_not()?];
+        while self.keyword("and") {
+            list.push(self.parse_not()?);
+        }
+        Ok(logic("and", list))
+    }
+
+    fn parse_not(&mut self) -> Result<Vec<CtsValue>, CtsError> {
+        if self.keyword("not") {
+            let inner = self.parse_not()?;
+            return Ok(vec![name("not"), CtsValue::Array(inner)]);
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Vec<CtsValue>, CtsError> {
+        if self.accept(&Token::LeftParen) {
+            let filter = self.parse_or()?;
+            self.expect(&Token::RightParen, "缺少右括号")?;
+            return Ok(filter);
+        }
+        // 空间相交函数
+        if matches!(
+            self.peek_ident().as_deref(),
+            Some("intersects" | "s_intersects")
+        ) && matches!(self.tokens.get(self.index + 1), Some(item) if item.token == Token::LeftParen)
+        {
+            self.index += 2;
+            return self.parse_intersects();
+        }
+        let field = self.field()?;
+        if let Some(Token::Operator(ope)) = self.peek() {
+            self.index += 1;
+            let value = self.literal()?;
+            return Ok(vec![name(ope), name(&field), value]);
+        }
+        // 时间条件
+        if let Some(ope) = self
+            .peek_ident()
+            .filter(|item| matches!(item.as_str(), "before" | "after" | "during" | "last"))
+        {
+            self.index += 1;
+            return self.parse_temporal(&ope, &field);
+        }
+        let negated = self.keyword("not");
+        if self.keyword("in") {
+            self.expect(&Token::LeftParen, "IN后面缺少左括号")?;
+            let mut values = vec![self.literal()?];
+            while self.accept(&Token::Comma) {
+                values.push(self.literal()?);
+            }
+            self.expect(&Token::RightParen, "IN缺少右括号")?;
+            let ope = if negated { "not in" } else { "in" };
+            return Ok(vec![name(ope), name(&field), CtsValue::Array(values)]);
+        }
+        if self.keyword("between") {
+            let low = self.literal()?;
+            if !self.keyword("and") {
+                return Err(self.error("BETWEEN缺少AND"));
+            }
+            let high = self.literal()?;
+            let ope = if negated { "not between" } else { "between" };
+            return Ok(vec![name(ope), name(&field), low, high]);
+        }
+        if self.keyword("like") {
+            let pattern = match self.next() {
+                Some(Token::String(pattern)) => pattern,
+                _ => return Err(self.previous_error("LIKE后面必须为字符串")),
+            };
+            let like = vec![name("like"), name(&field), name(&pattern)];
+            return Ok(match negated {
+                true => vec![name("not"), CtsValue::Array(like)],
+                false => like,
+            });
+        }
+        if !negated && self.keyword("is") {
+            let ope = if self.keyword("not") {
+                "is not null"
+            } else {
+                "is null"
+            };
+            if !self.keyword("null") {
+                return Err(self.error("IS后面缺少NULL"));
+            }
+            return Ok(vec![name(ope), name(&field)]);
+        }
+        Err(self.error("缺少比较操作符"))
+    }
+
+    // field BEFORE '2024-01-01'、field DURING '2024-01-01' AND '2024-02-01'、field LAST 7 DAY
+    fn parse_temporal(&mut self, ope: &str, field: &str) -> Result<Vec<CtsValue>, CtsError> {
+        let mut result = vec![name(ope), name(field)];
+        if ope == "last" {
+            match self.next() {
+                Some(Token::Integer(amount)) => {
+                    result.push(CtsValue::Single(Single::Integer(amount)))
+                }
+                _ => return Err(self.previous_error("LAST后面必须为整数")),
+            }
+            match self.next() {
+                Some(Token::Ident(unit) | Token::String(unit)) => result.push(name(&unit)),
+                _ => return Err(self.previous_error("LAST缺少时间单位")),
+            }
+            return Ok(result);
+        }
+        result.push(self.time()?);
+        if ope == "during" {
+            if !self.keyword("and") {
+                return Err(self.error("DURING缺少AND"));
+            }
+            result.push(self.time()?);
+        }
+        Ok(result)
+    }
+
+    fn time(&mut self) -> Result<CtsValue, CtsError> {
+        match self.next() {
+            Some(Token::String(data)) => Ok(name(&data)),
+            _ => Err(self.previous_error("时间必须为字符串")),
+        }
+    }
+
+    // INTERSECTS(field, WKT[, srid])，WKT 可以直接书写也可以使用字符串
+    fn parse_intersects(&mut self) -> Result<Vec<CtsValue>, CtsError> {
+        let field = self.field()?;
+        self.expect(&Token::Comma, "INTERSECTS缺少逗号")?;
+        let geometry = match self.peek() {
+            Some(Token::String(wkt)) => {
+                self.index += 1;
+                wkt
+            }
+            Some(Token::Ident(_)) => self.wkt()?,
+            _ => return Err(self.error("INTERSECTS缺少空间对象")),
+        };
+        let mut result = vec![name("intersects"), name(&field), name(&geometry)];
+        if self.accept(&Token::Comma) {
+            match self.next() {
+                Some(Token::Integer(srid)) => result.push(CtsValue::Single(Single::Integer(srid))),
+                _ => return Err(self.previous_error("坐标系必须为整数")),
+            }
+        }
+        self.expect(&Token::RightParen, "INTERSECTS缺少右括号")?;
+        Ok(result)
+    }
+
+    // 读取 WKT 原文，从类型名称到匹配的右括号
+    fn wkt(&mut self) -> Result<String, CtsError> {
+        let start = self.tokens[self.index].start;
+        self.index += 1;
+        // 跳过 Z、M、ZM 等维度标识
+        while matches!(self.peek(), Some(Token::Ident(_))) {
+            self.index += 1;
+        }
+        if !self.accept(&Token::LeftParen) {
+            return Err(self.error("空间对象缺少左括号"));
+        }
+        let mut depth = 1;
+        while depth > 0 {
+            match self.next() {
+                Some(Token::LeftParen) => depth += 1,
+                Some(Token::RightParen) => depth -= 1,
+                Some(Token::Integer(_) | Token::Double(_) | Token::Comma | Token::Ident(_)) => {}
+                Some(_) => return Err(self.previous_error("空间对象格式错误")),
+                None => return Err(self.error("空间对象缺少右括号")),
+            }
+        }
+        let end = self.tokens[self.index - 1].end;
+        Ok(self.source[start..end].to_string())
+    }
+
+    fn field(&mut self) -> Result<String, CtsError> {
+        match self.next() {
+            Some(Token::Ident(field)) if !is_keyword(&field) => Ok(field),
+            _ => Err(self.previous_error("缺少字段名称")),
+        }
+    }
+
+    fn literal(&mut self) -> Result<CtsValue, CtsError> {
+        let value = match self.next() {
+            Some(Token::String(data)) => Single::String(data),
+            Some(Token::Integer(data)) => Single::Integer(data),
+            Some(Token::Double(data)) => Single::Double(data),
+            Some(Token::Ident(data)) if data.eq_ignore_ascii_case("true") => Single::Bool(true),
+            Some(Token::Ident(data)) if data.eq_ignore_ascii_case("false") => Single::Bool(false),
+            _ => return Err(self.previous_error("缺少值")),
+        };
+        Ok(CtsValue::Single(value))
+    }
+
+    fn peek(&self) -> Option<Token> {
+        self.tokens.get(self.index).map(|item| item.token.clone())
+    }
+
+    fn peek_ident(&self) -> Option<String> {
+        match self.peek() {
+            Some(Token::Ident(data)) => Some(data.to_lowercase()),
+            _ => None,
+        }
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.peek();
+        self.index += 1;
+        token
+    }
+
+    fn accept(&mut self, token: &Token) -> bool {
+        match self.tokens.get(self.index) {
+            Some(item) if &item.token == token => {
+                self.index += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn expect(&mut self, token: &Token, message: &str) -> Result<(), CtsError> {
+        match self.accept(token) {
+            true => Ok(()),
+            false => Err(self.error(message)),
+        }
+    }
+
+    // 匹配关键字，不区分大小写
+    fn keyword(&mut self, keyword: &str) -> bool {
+        match self.peek_ident() {
+            Some(data) if data == keyword => {
+                self.index += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // 当前位置的错误
+    fn error(&self, message: &str) -> CtsError {
+        let position = match self.tokens.get(self.index) {
+            Some(item) => item.start,
+            None => self.source.len(),
+        };
+        FilterError(format!("查询语句错误，位置{position}：{message}"))
+    }
+
+    // 上一个词法单元位置的错误
+    fn previous_error(&mut self, message: &str) -> CtsError {
+        self.index -= 1;
+        self.error(message)
+    }
+}
+
+// 多个条件时使用逻辑操作符连接
+fn logic(ope: &str, mut list: Vec<Vec<CtsValue>>) -> Vec<CtsValue> {
+    if list.len() == 1 {
+        return list.remove(0);
+    }
+    let mut result = vec![name(ope)];
+    result.extend(list.into_iter().map(CtsValue::Array));
+    result
+}
+
+fn name(data: &str) -> CtsValue {
+    CtsValue::Single(Single::String(data.to_string()))
+}
+
+pub(crate) fn is_keyword(data: &str) -> bool {
+    matches!(
+        data.to_lowercase().as_str(),
+        "and" | "or" | "not" | "in" | "between" | "like" | "is" | "null" | "true" | "false"
+    )
+}
+
+fn tokenize(source: &str) -> Result<Vec<Lexeme>, CtsError> {
+    let chars: Vec<(usize, char)> = source.char_indices().collect();
+    let end_of = |index: usize| chars.get(index).map(|item| item.0).unwrap_or(source.len());
+    let error =
+        |start: usize, message: &str| FilterError(format!("查询语句错误，位置{start}：{message}"));
+    let mut result = Vec::new();
+    let mut index = 0;
+    while index < chars.len() {
+        let (start, current) = chars[index];
+        let next = chars.get(index + 1).map(|item| item.1);
+        let (token, len) = match current {
+            _ if current.is_whitespace() => {
+                index += 1;
+                continue;
+            }
+            '(' => (Token::LeftParen, 1),
+            ')' => (Token::RightParen, 1),
+            ',' => (Token::Comma, 1),
+            '=' => (Token::Operator("="), 1),
+            '<' if next == Some('=') => (Token::Operator("<="), 2),
+            '<' if next == Some('>') => (Token::Operator("!="), 2),
+            '<' => (Token::Operator("<"), 1),
+            '>' if next == Some('=') => (Token::Operator(">="), 2),
+            '>' => (Token::Operator(">"), 1),
+            '!' if next == Some('=') => (Token::Operator("!="), 2),
+            // 字符串，两个单引号表示一个单引号
+            '\'' => {
+                let mut data = String::new();
+                let mut offset = index + 1;
+                loop {
+                    match chars.get(offset).map(|item| item.1) {
+                        None => return Err(error(start, "字符串缺少结束引号")),
+                        Some('\'') if chars.get(offset + 1).map(|item| item.1) == Some('\'') => {
+                            data.push('\'');
+                            offset += 2;
+                        }
+                        Some('\'') => break,
+                        Some(item) => {
+                            data.push(item);
+                            offset += 1;
+                        }
+                    }
+                }
+                (Token::String(data), offset + 1 - index)
+            }
+            _ if current.is_ascii_digit()
+                || (matches!(current, '-' | '+' | '.')
+                    && next.is_some_and(|item| item.is_ascii_digit())) =>
+            {
+                let mut offset = index + 1;
+                while chars.get(offset).is_some_and(|item| {
+                    item.1.is_ascii_digit()
+                        || matches!(item.1, '.' | 'e' | 'E')
+                        || (matches!(item.1, '-' | '+') && matches!(chars[offset - 1].1, 'e' | 'E'))
+                }) {
+                    offset += 1;
+                }
+                let text = &source[start..end_of(offset)];
+                let token = match text.parse::<i64>() {
+                    Ok(data) => Token::Integer(data),
+                    Err(_) => match text.parse::<f64>() {
+                        Ok(data) => Token::Double(data),
+                        Err(_) => return Err(error(start, "数字格式错误")),
+                    },
+                };
+                (token, offset - index)
+            }
+            _ if current.is_alphabetic() || current == '_' => {
+                let mut offset = index + 1;
+                while chars
+                    .get(offset)
+                    .is_some_and(|item| item.1.is_alphanumeric() || matches!(item.1, '_' | '.'))
+                {
+                    offset += 1;
+                }
+                let text = &source[start..end_of(offset)];
+                (Token::Ident(text.to_string()), offset - index)
+            }
+            _ => return Err(error(start, &format!("不支持的字符{current}"))),
+        };
+        result.push(Lexeme {
+            token,
+            start,
+            end: end_of(index + len),
+        });
+        index += len;
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cql::parse_text;
+    use crate::expression::parse::filter::filter_parse;
+
+    #[test]
+    fn test_parse() {
+        let filter = parse_text(
+            "status = 'ok' AND (area >= 1.5 OR type NOT IN ('a', -2)) \
+             AND INTERSECTS(geom, POINT(120 30)) AND NOT name LIKE '%路' AND x IS NOT NULL \
+             AND created_at DURING '2024-01-01' AND '2024-02-01' AND updated_at LAST 7 DAY",
+        )
+        .unwrap();
+        assert_eq!(
+            filter_parse(&filter).unwrap().unwrap(),
+            "(status = 'ok') and ((area >= 1.5) or (type not in ('a',-2))) \
+             and (st_intersects(geom, st_geomfromtext('POINT(120 30)', 4326))) \
+             and (not (name like '%路')) and (x is not null) \
+             and ((created_at >= '2024-01-01'::date and created_at < '2024-02-01'::date)) \
+             and (updated_at >= now() - interval '7 day')"
+        );
+
+        let err = parse_text("status = 'open' AND").unwrap_err();
+        assert_eq!(err.to_string(), "查询语句错误，位置19：缺少字段名称");
+        assert!(parse_text("a BETWEEN 1 3").is_err());
+        assert!(parse_text("(a = 1").is_err());
+    }
+}
@@ -0,0 +1,163 @@
+use crate::error::CtsError;
+use crate::error::CtsError::FilterError;
+use crate::expression::parse::handler_name;
+use crate::expression::{CtsValue, Single};
+
+/// 将过滤参数输出成文本查询语言，关键字大写，只在优先级需要时添加括号
+pub fn print(filter: &[CtsValue]) -> Result<String, CtsError> {
+    if filter.len() < 2 {
+        return Err(FilterError("过滤参数长度不够，至少2位。".to_string()));
+    }
+    let ope = handler_name(&filter[0])?.to_lowercase();
+    let text = match ope.as_str() {
+        "or" | "and" => {
+            let mut result = Vec::new();
+            for item in filter.iter().skip(1) {
+                let child = children(item)?;
+                let text = print(child)?;
+                // AND 中的 OR 需要括号
+                match logic_name(child).as_deref() {
+                    Some("or") if ope == "and" => result.push(format!("({text})")),
+                    _ => result.push(text),
+                }
+            }
+            result.join(&format!(" {} ", ope.to_uppercase()))
+        }
+        "not" => {
+            let list = filter
+                .iter()
+                .skip(1)
+                .map(|item| children(item).and_then(print_operand))
+                .collect::<Result<Vec<_>, _>>()?;
+            match list.len() {
+                1 => format!("NOT {}", list[0]),
+                _ => format!("NOT ({})", list.join(" AND ")),
+            }
+        }
+        ">" | "<" | ">=" | "<=" | "=" | "!=" => {
+            let ope = if ope == "!=" { "<>" } else { ope.as_str() };
+            format!("{} {ope} {}", field(filter)?, literal(value(filter, 2)?)?)
+        }
+        "in" | "not in" => {
+            let values = match value(filter, 2)? {
+                CtsValue::Array(list) => list
+                    .iter()
+                    .map(literal)
+                    .collect::<Result<Vec<_>, _>>()?
+                    .join(", "),
+                data => literal(data)?,
+            };
+            format!("{} {} ({values})", field(filter)?, ope.to_uppercase())
+        }
+        "between" | "not between" => format!(
+            "{} {} {} AND {}",
+            field(filter)?,
+            ope.to_uppercase(),
+            literal(value(filter, 2)?)?,
+            literal(value(filter, 3)?)?
+        ),
+        "like" => format!("{} LIKE {}", field(filter)?, literal(value(filter, 2)?)?),
+        "is null" | "is not null" => format!("{} {}", field(filter)?, ope.to_uppercase()),
+        "before" | "after" => format!(
+            "{} {} {}",
+            field(filter)?,
+            ope.to_uppercase(),
+            literal(value(filter, 2)?)?
+        ),
+        "during" => format!(
+            "{} DURING {} AND {}",
+            field(filter)?,
+            literal(value(filter, 2)?)?,
+            literal(value(filter, 3)?)?
+        ),
+        "last" => format!(
+            "{} LAST {} {}",
+            field(filter)?,
+            literal(value(filter, 2)?)?,
+            handler_name(value(filter, 3)?)?.to_uppercase()
+        ),
+        "intersects" => {
+            let geometry = handler_name(value(filter, 2)?)?;
+            match filter.get(3) {
+                None => format!("INTERSECTS({}, {geometry})", field(filter)?),
+                Some(srid) => format!(
+                    "INTERSECTS({}, {geometry}, {})",
+                    field(filter)?,
+                    literal(srid)?
+                ),
+            }
+        }
+        _ => return Err(FilterError(format!("不支持的过滤操作符{ope}"))),
+    };
+    Ok(text)
+}
+
+// NOT 后面的逻辑条件需要括号
+fn print_operand(filter: &[CtsValue]) -> Result<String, CtsError> {
+    let text = print(filter)?;
+    match logic_name(filter) {
+        Some(_) => Ok(format!("({text})")),
+        None => Ok(text),
+    }
+}
+
+fn logic_name(filter: &[CtsValue]) -> Option<String> {
+    match filter.first() {
+        Some(CtsValue::Single(Single::String(ope)))
+            if matches!(ope.to_lowercase().as_str(), "or" | "and") =>
+        {
+            Some(ope.to_lowercase())
+        }
+        _ => None,
+    }
+}
+
+fn children(data: &CtsValue) -> Result<&[CtsValue], CtsError> {
+    match data {
+        CtsValue::Array(list) => Ok(list),
+        CtsValue::Single(_) => Err(FilterError("参数错误".to_string())),
+    }
+}
+
+fn value(filter: &[CtsValue], index: usize) -> Result<&CtsValue, CtsError> {
+    filter
+        .get(index)
+        .ok_or(FilterError("参数错误，参数长度不够".to_string()))
+}
+
+fn field(filter: &[CtsValue]) -> Result<String, CtsError> {
+    handler_name(value(filter, 1)?)
+}
+
+fn literal(data: &CtsValue) -> Result<String, CtsError> {
+    match data {
+        CtsValue::Single(value) => Ok(match value {
+            Single::String(data) => format!("'{}'", data.replace('\'', "''")),
+            Single::Integer(data) => data.to_string(),
+            // 保留小数点，重新解析时仍然是小数
+            Single::Double(data) => format!("{data:?}"),
+            Single::Bool(data) => data.to_string().to_uppercase(),
+        }),
+        CtsValue::Array(_) => Err(FilterError("参数错误".to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cql::{parse_text, to_text};
+
+    #[test]
+    fn test_print() {
+        let text = "a = 'it''s' AND (b > 1.0 OR c <> TRUE) AND NOT (d IS NULL OR e LIKE 'x%') \
+                    AND f NOT BETWEEN 1 AND 2 AND INTERSECTS(geom, POLYGON((0 0, 1 0, 1 1, 0 0)), 3857) \
+                    AND g BEFORE '2024-01-01T08:00:00+08:00' AND h LAST 7 DAY";
+        let filter = parse_text(text).unwrap();
+        assert_eq!(to_text(&filter).unwrap(), text);
+        // 输出的文本重新解析后结果不变
+        let again = parse_text(&to_text(&filter).unwrap()).unwrap();
+        assert_eq!(
+            serde_json::to_value(&again).unwrap(),
+            serde_json::to_value(&filter).unwrap()
+        );
+    }
+}
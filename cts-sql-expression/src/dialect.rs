@@ -0,0 +1,317 @@
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::expression::parse::filter::temporal::IsoDateTime;
+
+/// # 数据库方言
+/// > 屏蔽不同数据库之间的语法差异，CtsParam 的过滤、分组、统计、排序语法在各个数据库中保持一致，
+/// > 方言负责标识符引用、常量、时间计算、参数占位符、分页、空间函数名称以及表结构查询
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DialectKind {
+    #[default]
+    Postgres,
+    MySql,
+    Sqlite,
+}
+
+/// 空间函数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpatialFunction {
+    AsGeoJson,
+    AsText,
+    AsBinary,
+    Intersects,
+    GeomFromText,
+}
+
+pub trait Dialect: Debug + Send + Sync {
+    fn kind(&self) -> DialectKind;
+
+    /// 标识符引用，标识符中的引号会被转义
+    fn quote_ident(&self, name: &str) -> String {
+        format!("\"{}\"", name.replace('"', "\"\""))
+    }
+
+    /// 参数占位符
+    /// @param index 参数序号，从1开始
+    fn placeholder(&self, index: usize) -> String;
+
+    /// 字符串常量，转义单引号
+    fn string_literal(&self, value: &str) -> String {
+        format!("'{}'", value.replace('\'', "''"))
+    }
+
+    /// ISO 8601 时间常量
+    fn datetime_literal(&self, time: &IsoDateTime) -> String;
+
+    /// 参与时间比较的字段表达式
+    fn datetime_field(&self, field: &str) -> String {
+        field.to_string()
+    }
+
+    /// 当前时间减去一段时间
+    /// @param amount 时间长度
+    /// @param unit 时间单位，second、minute、hour、day、week、month、year
+    fn now_minus(&self, amount: i64, unit: &str) -> String;
+
+    /// 分页语句
+    fn limit_offset(&self, limit: i64, offset: i64) -> String {
+        format!(" LIMIT {limit} OFFSET {offset}")
+    }
+
+    /// 空间函数名称
+    fn spatial_function(&self, function: SpatialFunction) -> &'static str;
+
+    /// 带schema的表名，schema为空时只返回表名
+    fn table_name(&self, schema: Option<&str>, table: &str) -> String {
+        match schema {
+            None => self.quote_ident(table),
+            Some(schema) => format!("{}.{}", self.quote_ident(schema), self.quote_ident(table)),
+        }
+    }
+
+    /// 查询表字段的sql，参数依次为 schema、表名，返回字段名称以及类型名称，
+    /// 没有默认schema的方言需要处理 schema 为空的情况
+    fn columns_sql(&self) -> String;
+
+    /// 默认schema
+    fn default_schema(&self) -> Option<&'static str>;
+
+    /// 是否为空间类型
+    fn is_spatial_type(&self, type_name: &str) -> bool {
+        matches!(
+            type_name.to_lowercase().as_str(),
+            "geometry"
+                | "geography"
+                | "point"
+                | "linestring"
+                | "polygon"
+                | "multipoint"
+                | "multilinestring"
+                | "multipolygon"
+                | "geometrycollection"
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PostgresDialect;
+
+impl Dialect for PostgresDialect {
+    fn kind(&self) -> DialectKind {
+        DialectKind::Postgres
+    }
+
+    fn placeholder(&self, index: usize) -> String {
+        format!("${index}")
+    }
+
+    fn datetime_literal(&self, time: &IsoDateTime) -> String {
+        time.to_sql()
+    }
+
+    fn now_minus(&self, amount: i64, unit: &str) -> String {
+        format!("now() - interval '{amount} {unit}'")
+    }
+
+    fn spatial_function(&self, function: SpatialFunction) -> &'static str {
+        match function {
+            SpatialFunction::AsGeoJson => "st_asgeojson",
+            SpatialFunction::AsText => "st_astext",
+            SpatialFunction::AsBinary => "st_asbinary",
+            SpatialFunction::Intersects => "st_intersects",
+            SpatialFunction::GeomFromText => "st_geomfromtext",
+        }
+    }
+
+    fn columns_sql(&self) -> String {
+        format!(
+            "select column_name::text as name, udt_name::text as type_name from information_schema.columns \
+             where table_schema = {} and table_name = {} order by ordinal_position",
+            self.placeholder(1),
+            self.placeholder(2)
+        )
+    }
+
+    fn default_schema(&self) -> Option<&'static str> {
+        Some("public")
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MySqlDialect;
+
+impl Dialect for MySqlDialect {
+    fn kind(&self) -> DialectKind {
+        DialectKind::MySql
+    }
+
+    fn quote_ident(&self, name: &str) -> String {
+        format!("`{}`", name.replace('`', "``"))
+    }
+
+    fn placeholder(&self, _index: usize) -> String {
+        "?".to_string()
+    }
+
+    /// 默认 sql_mode 下反斜杠也是转义字符
+    fn string_literal(&self, value: &str) -> String {
+        format!("'{}'", value.replace('\\', "\\\\").replace('\'', "''"))
+    }
+
+    /// DATETIME 不保存时区，带时区的时间转换成 UTC 时间
+    fn datetime_literal(&self, time: &IsoDateTime) -> String {
+        match time {
+            IsoDateTime::Date(date) => format!("DATE '{}'", date.format("%Y-%m-%d")),
+            IsoDateTime::Local(time) => {
+                format!("TIMESTAMP '{}'", time.format("%Y-%m-%d %H:%M:%S%.f"))
+            }
+            IsoDateTime::Zoned(time) => format!(
+                "TIMESTAMP '{}'",
+                time.with_timezone(&Utc).format("%Y-%m-%d %H:%M:%S%.f")
+            ),
+        }
+    }
+
+    fn now_minus(&self, amount: i64, unit: &str) -> String {
+        format!("now() - INTERVAL {amount} {}", unit.to_uppercase())
+    }
+
+    fn limit_offset(&self, limit: i64, offset: i64) -> String {
+        format!(" LIMIT {offset}, {limit}")
+    }
+
+    fn spatial_function(&self, function: SpatialFunction) -> &'static str {
+        match function {
+            SpatialFunction::AsGeoJson => "ST_AsGeoJSON",
+            SpatialFunction::AsText => "ST_AsText",
+            SpatialFunction::AsBinary => "ST_AsBinary",
+            SpatialFunction::Intersects => "ST_Intersects",
+            SpatialFunction::GeomFromText => "ST_GeomFromText",
+        }
+    }
+
+    /// 没有设置 schema 时查询连接的数据库
+    fn columns_sql(&self) -> String {
+        format!(
+            "select column_name as name, data_type as type_name from information_schema.columns \
+             where table_schema = coalesce({}, database()) and table_name = {} order by ordinal_position",
+            self.placeholder(1),
+            self.placeholder(2)
+        )
+    }
+
+    /// MySQL 的 schema 即数据库，默认使用连接的数据库
+    fn default_schema(&self) -> Option<&'static str> {
+        None
+    }
+}
+
+/// SQLite 方言，空间函数使用 SpatiaLite
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SqliteDialect;
+
+impl Dialect for SqliteDialect {
+    fn kind(&self) -> DialectKind {
+        DialectKind::Sqlite
+    }
+
+    fn placeholder(&self, index: usize) -> String {
+        format!("?{index}")
+    }
+
+    /// 时间以文本保存，使用 datetime() 统一成 UTC 的 `YYYY-MM-DD HH:MM:SS` 格式后比较
+    fn datetime_literal(&self, time: &IsoDateTime) -> String {
+        let time = match time {
+            IsoDateTime::Date(date) => date.format("%Y-%m-%d").to_string(),
+            IsoDateTime::Local(time) => time.format("%Y-%m-%dT%H:%M:%S%.f").to_string(),
+            IsoDateTime::Zoned(time) => time.to_rfc3339(),
+        };
+        format!("datetime('{time}')")
+    }
+
+    fn datetime_field(&self, field: &str) -> String {
+        format!("datetime({field})")
+    }
+
+    /// datetime 修饰符不支持周，按7天计算
+    fn now_minus(&self, amount: i64, unit: &str) -> String {
+        let (amount, unit) = match unit {
+            "week" => (amount * 7, "day"),
+            unit => (amount, unit),
+        };
+        format!("datetime('now', '-{amount} {unit}')")
+    }
+
+    fn spatial_function(&self, function: SpatialFunction) -> &'static str {
+        match function {
+            SpatialFunction::AsGeoJson => "AsGeoJSON",
+            SpatialFunction::AsText => "AsText",
+            SpatialFunction::AsBinary => "AsBinary",
+            SpatialFunction::Intersects => "ST_Intersects",
+            SpatialFunction::GeomFromText => "GeomFromText",
+        }
+    }
+
+    fn columns_sql(&self) -> String {
+        format!(
+            "select name, lower(type) as type_name from pragma_table_info({}, {}) order by cid",
+            self.placeholder(2),
+            self.placeholder(1)
+        )
+    }
+
+    fn default_schema(&self) -> Option<&'static str> {
+        Some("main")
+    }
+}
+
+impl DialectKind {
+    /// 创建方言
+    pub fn dialect(&self) -> Arc<dyn Dialect> {
+        match self {
+            DialectKind::Postgres => Arc::new(PostgresDialect),
+            DialectKind::MySql => Arc::new(MySqlDialect),
+            DialectKind::Sqlite => Arc::new(SqliteDialect),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dialect() {
+        let mysql = DialectKind::MySql.dialect();
+        assert_eq!(mysql.table_name(Some("db"), "road"), "`db`.`road`");
+        assert_eq!(mysql.limit_offset(10, 20), " LIMIT 20, 10");
+        assert_eq!(mysql.placeholder(2), "?");
+        assert_eq!(mysql.string_literal("a\\' or 1=1"), "'a\\\\'' or 1=1'");
+        let time = IsoDateTime::parse("2024-01-01T08:00:00+08:00").unwrap();
+        assert_eq!(
+            mysql.datetime_literal(&time),
+            "TIMESTAMP '2024-01-01 00:00:00'"
+        );
+        assert_eq!(mysql.now_minus(7, "day"), "now() - INTERVAL 7 DAY");
+        // 没有设置 schema 时使用连接的数据库
+        assert_eq!(mysql.default_schema(), None);
+        assert!(mysql
+            .columns_sql()
+            .contains("table_schema = coalesce(?, database())"));
+
+        let postgres = DialectKind::Postgres.dialect();
+        assert_eq!(postgres.quote_ident("a\"b"), "\"a\"\"b\"");
+        assert_eq!(postgres.placeholder(2), "$2");
+
+        let sqlite = DialectKind::Sqlite.dialect();
+        assert_eq!(sqlite.limit_offset(10, 20), " LIMIT 10 OFFSET 20");
+        assert!(sqlite.is_spatial_type("POINT"));
+        assert_eq!(sqlite.now_minus(2, "week"), "datetime('now', '-14 day')");
+        assert!(sqlite.columns_sql().contains("pragma_table_info(?2, ?1)"));
+    }
+}
@@ -1,5 +1,10 @@
 use std::fmt::{Display, Formatter};
 
+use serde::{Deserialize, Serialize};
+
+/// # 错误类型
+/// > 每个错误对应一个错误编码以及 HTTP 状态，数据库错误只返回通用提示，详细信息通过 source 获取，
+/// > 请求参数错误可以带上参数路径，例如 `filter[2][1]`
 #[derive(Debug)]
 pub enum CtsError {
     AggregateError(String),
@@ -8,18 +13,445 @@ pub enum CtsError {
     GroupError(String),
     OrderError(String),
     ParamError(String),
+    ConvertError(String),
+    /// 没有权限访问字段或者数据
+    PermissionError(String),
+    /// 查询超时
+    TimeoutError(String),
+    /// 数据库错误，不返回给客户端
+    DatabaseError(sqlx::Error),
+    /// 带请求参数路径的错误
+    PathError { path: String, source: Box<CtsError> },
+    /// 参数校验错误，包含全部问题
+    ValidationError(Vec<CtsError>),
+}
+
+/// 错误编码
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    InvalidAggregate,
+    InvalidField,
+    InvalidFilter,
+    InvalidGroup,
+    InvalidOrder,
+    InvalidParam,
+    ConvertFailed,
+    PermissionDenied,
+    Timeout,
+    DatabaseError,
+}
+
+/// 错误信息语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Locale {
+    #[default]
+    #[serde(rename = "zh-CN")]
+    ZhCn,
+    #[serde(rename = "en")]
+    En,
+}
+
+impl CtsError {
+    /// 错误编码
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            CtsError::AggregateError(_) => ErrorCode::InvalidAggregate,
+            CtsError::FieldError(_) => ErrorCode::InvalidField,
+            CtsError::FilterError(_) => ErrorCode::InvalidFilter,
+            CtsError::GroupError(_) => ErrorCode::InvalidGroup,
+            CtsError::OrderError(_) => ErrorCode::InvalidOrder,
+            CtsError::ParamError(_) => ErrorCode::InvalidParam,
+            CtsError::ConvertError(_) => ErrorCode::ConvertFailed,
+            CtsError::PermissionError(_) => ErrorCode::PermissionDenied,
+            CtsError::TimeoutError(_) => ErrorCode::Timeout,
+            CtsError::DatabaseError(_) => ErrorCode::DatabaseError,
+            CtsError::PathError { source, .. } => source.code(),
+            CtsError::ValidationError(list) => match list.as_slice() {
+                [data] => data.code(),
+                _ => ErrorCode::InvalidParam,
+            },
+        }
+    }
+
+    /// 请求参数路径，没有时返回空
+    pub fn path(&self) -> Option<&str> {
+        match self {
+            CtsError::PathError { path, .. } => Some(path),
+            _ => None,
+        }
+    }
+
+    /// 在参数路径前面添加上级路径
+    /// @param segment 上级路径，例如 `filter`、`[2]`
+    pub fn with_path(self, segment: &str) -> CtsError {
+        match self {
+            CtsError::PathError { path, source } => CtsError::PathError {
+                path: format!("{segment}{path}"),
+                source,
+            },
+            data => CtsError::PathError {
+                path: segment.to_string(),
+                source: Box::new(data),
+            },
+        }
+    }
+
+    /// 按语言返回错误信息，英文没有对应翻译时返回错误编码的通用描述
+    pub fn message(&self, locale: Locale) -> String {
+        let message = match self {
+            CtsError::PathError { path, source } => {
+                return format!("{path}: {}", source.message(locale));
+            }
+            CtsError::ValidationError(list) => {
+                return list
+                    .iter()
+                    .map(|item| item.message(locale))
+                    .collect::<Vec<_>>()
+                    .join("; ");
+            }
+            CtsError::DatabaseError(_) => None,
+            CtsError::AggregateError(data)
+            | CtsError::FieldError(data)
+            | CtsError::FilterError(data)
+            | CtsError::GroupError(data)
+            | CtsError::OrderError(data)
+            | CtsError::ParamError(data)
+            | CtsError::ConvertError(data)
+            | CtsError::PermissionError(data)
+            | CtsError::TimeoutError(data) => Some(data.as_str()),
+        };
+        match (locale, message) {
+            (Locale::ZhCn, Some(data)) => data.to_string(),
+            (Locale::En, Some(data)) => match translate(self.code(), data) {
+                Some(data) => data,
+                None => self.code().message(locale).to_string(),
+            },
+            (_, None) => self.code().message(locale).to_string(),
+        }
+    }
+}
+
+impl ErrorCode {
+    /// 数字编码，用于 ResResult.code
+    pub fn number(&self) -> i32 {
+        match self {
+            ErrorCode::InvalidParam => 40000,
+            ErrorCode::InvalidFilter => 40001,
+            ErrorCode::InvalidField => 40002,
+            ErrorCode::InvalidGroup => 40003,
+            ErrorCode::InvalidOrder => 40004,
+            ErrorCode::InvalidAggregate => 40005,
+            ErrorCode::PermissionDenied => 40300,
+            ErrorCode::ConvertFailed => 50000,
+            ErrorCode::DatabaseError => 50001,
+            ErrorCode::Timeout => 50400,
+        }
+    }
+
+    /// HTTP 状态码
+    pub fn http_status(&self) -> u16 {
+        match self {
+            ErrorCode::PermissionDenied => 403,
+            ErrorCode::ConvertFailed | ErrorCode::DatabaseError => 500,
+            ErrorCode::Timeout => 504,
+            _ => 400,
+        }
+    }
+
+    /// 通用描述
+    pub fn message(&self, locale: Locale) -> &'static str {
+        match (self, locale) {
+            (ErrorCode::InvalidAggregate, Locale::ZhCn) => "统计参数错误",
+            (ErrorCode::InvalidAggregate, Locale::En) => "invalid aggregate parameter",
+            (ErrorCode::InvalidField, Locale::ZhCn) => "查询字段参数错误",
+            (ErrorCode::InvalidField, Locale::En) => "invalid field parameter",
+            (ErrorCode::InvalidFilter, Locale::ZhCn) => "过滤参数错误",
+            (ErrorCode::InvalidFilter, Locale::En) => "invalid filter parameter",
+            (ErrorCode::InvalidGroup, Locale::ZhCn) => "分组参数错误",
+            (ErrorCode::InvalidGroup, Locale::En) => "invalid group parameter",
+            (ErrorCode::InvalidOrder, Locale::ZhCn) => "排序参数错误",
+            (ErrorCode::InvalidOrder, Locale::En) => "invalid order parameter",
+            (ErrorCode::InvalidParam, Locale::ZhCn) => "参数错误",
+            (ErrorCode::InvalidParam, Locale::En) => "invalid parameter",
+            (ErrorCode::ConvertFailed, Locale::ZhCn) => "结果转换错误",
+            (ErrorCode::ConvertFailed, Locale::En) => "failed to convert query result",
+            (ErrorCode::PermissionDenied, Locale::ZhCn) => "没有权限",
+            (ErrorCode::PermissionDenied, Locale::En) => "permission denied",
+            (ErrorCode::Timeout, Locale::ZhCn) => "查询超时",
+            (ErrorCode::Timeout, Locale::En) => "query timed out",
+            (ErrorCode::DatabaseError, Locale::ZhCn) => "数据库查询错误",
+            (ErrorCode::DatabaseError, Locale::En) => "database query failed",
+        }
+    }
+}
+
+impl Locale {
+    /// 解析 Accept-Language 请求头，只区分中文以及英文，默认中文
+    pub fn from_accept_language(value: &str) -> Locale {
+        let first = value
+            .split(',')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_lowercase();
+        if first.starts_with("en") {
+            Locale::En
+        } else {
+            Locale::ZhCn
+        }
+    }
+}
+
+/// # 错误信息英文翻译
+/// > 按错误编码以及中文模板查找，模板中的 `{}` 为参数，匹配后把参数按顺序填入英文模板，
+/// > 同一错误编码下没有占位符的模板写在前面，优先完整匹配
+static MESSAGES: &[(ErrorCode, &str, &str)] = &[
+    (ErrorCode::InvalidAggregate, "统计参数错误", "invalid aggregate parameter"),
+    (ErrorCode::InvalidAggregate, "统计操作符不支持", "unsupported aggregate operator"),
+    (ErrorCode::InvalidAggregate, "统计【字段】必须为字符串", "aggregate field must be a string"),
+    (ErrorCode::InvalidAggregate, "统计【别名】必须为字符串", "aggregate alias must be a string"),
+    (ErrorCode::InvalidField, "查询字段类型只能是字符串", "field must be a string"),
+    (ErrorCode::InvalidField, "查询字段数组不能为空，请检查数据格式。", "field array must not be empty"),
+    (ErrorCode::InvalidField, "数据格式不对，请检查数据格式。", "invalid data format"),
+    (ErrorCode::InvalidField, "存在字段权限限制，查询字段不能使用*", "* is not allowed when field policies are configured"),
+    (ErrorCode::InvalidField, "存在字段权限限制，查询字段只能是字段名称{}", "only column names can be selected when field policies are configured, found {}"),
+    (ErrorCode::InvalidField, "别名错误{}", "invalid alias {}"),
+    (ErrorCode::InvalidFilter, "参数错误", "invalid parameter"),
+    (ErrorCode::InvalidFilter, "参数错误，参数长度不够", "invalid parameter, not enough arguments"),
+    (ErrorCode::InvalidFilter, "参数错误，参数过多", "invalid parameter, too many arguments"),
+    (ErrorCode::InvalidFilter, "过滤参数错误", "invalid filter parameter"),
+    (ErrorCode::InvalidFilter, "过滤参数长度不够，至少2位。", "filter needs at least 2 elements"),
+    (ErrorCode::InvalidFilter, "坐标系参数错误", "invalid srid parameter"),
+    (ErrorCode::InvalidFilter, "空间对象参数错误，必须为WKT格式", "geometry must be WKT"),
+    (ErrorCode::InvalidFilter, "空间对象格式错误", "invalid geometry"),
+    (ErrorCode::InvalidFilter, "IN参数错误", "invalid IN parameter"),
+    (ErrorCode::InvalidFilter, "LIKE参数错误", "invalid LIKE parameter"),
+    (ErrorCode::InvalidFilter, "BETWEEN参数错误", "invalid BETWEEN parameter"),
+    (ErrorCode::InvalidFilter, "数据不能为空", "value must not be empty"),
+    (ErrorCode::InvalidFilter, "时间参数必须为字符串", "time must be a string"),
+    (ErrorCode::InvalidFilter, "时间格式错误，必须为ISO 8601格式", "time must be ISO 8601"),
+    (ErrorCode::InvalidFilter, "时间区间格式错误", "invalid time interval"),
+    (ErrorCode::InvalidFilter, "时间长度必须为正整数", "time amount must be a positive integer"),
+    (ErrorCode::InvalidFilter, "查询语句不能为空", "filter text must not be empty"),
+    (ErrorCode::InvalidFilter, "bbox必须为4个或者6个数字", "bbox must contain 4 or 6 numbers"),
+    (ErrorCode::InvalidFilter, "bbox参数错误，格式为[minx,miny,maxx,maxy]", "bbox must be [minx,miny,maxx,maxy]"),
+    (ErrorCode::InvalidFilter, "CQL2表达式必须为对象", "CQL2 expression must be an object"),
+    (ErrorCode::InvalidFilter, "CQL2表达式缺少op", "CQL2 expression is missing op"),
+    (ErrorCode::InvalidFilter, "CQL2表达式缺少args", "CQL2 expression is missing args"),
+    (ErrorCode::InvalidFilter, "CQL2参数错误，只支持常量", "CQL2 arguments must be literals"),
+    (ErrorCode::InvalidFilter, "CQL2参数错误，第一个参数必须为属性", "the first CQL2 argument must be a property"),
+    (ErrorCode::InvalidFilter, "CQL2-JSON只支持4326坐标系", "CQL2-JSON only supports srid 4326"),
+    (ErrorCode::InvalidFilter, "不支持转换null值", "null values cannot be converted"),
+    (ErrorCode::InvalidFilter, "不支持转换sql片段", "sql fragments cannot be converted"),
+    (ErrorCode::InvalidFilter, "不支持转换字段之间的比较", "comparisons between fields cannot be converted"),
+    (ErrorCode::InvalidFilter, "不支持的过滤操作符{}", "unsupported filter operator {}"),
+    (ErrorCode::InvalidFilter, "不支持的CQL2操作符{}", "unsupported CQL2 operator {}"),
+    (ErrorCode::InvalidFilter, "不支持的时间单位{}", "unsupported time unit {}"),
+    (ErrorCode::InvalidFilter, "不支持的空间对象类型{}", "unsupported geometry type {}"),
+    (ErrorCode::InvalidFilter, "字段名称错误{}", "invalid field name {}"),
+    (ErrorCode::InvalidFilter, "时间格式错误{}，必须为ISO 8601格式", "invalid time {}, must be ISO 8601"),
+    (ErrorCode::InvalidFilter, "时间格式错误{}", "invalid time {}"),
+    (ErrorCode::InvalidFilter, "查询语句错误，位置{}：{}", "invalid filter text at {}: {}"),
+    (ErrorCode::InvalidFilter, "{}参数错误", "invalid {} parameter"),
+    (ErrorCode::InvalidGroup, "分组字段不能为空", "group field must not be empty"),
+    (ErrorCode::InvalidGroup, "不支持的时间单位{}", "unsupported time unit {}"),
+    (ErrorCode::InvalidGroup, "字段名称错误{}", "invalid field name {}"),
+    (ErrorCode::InvalidOrder, "排序参数错误", "invalid order parameter"),
+    (ErrorCode::InvalidOrder, "排序参数类型错误", "invalid order parameter type"),
+    (ErrorCode::InvalidParam, "参数错误", "invalid parameter"),
+    (ErrorCode::InvalidParam, "类型错误", "invalid type"),
+    (ErrorCode::InvalidParam, "坐标系参数错误", "invalid srid parameter"),
+    (ErrorCode::InvalidParam, "bbox参数错误", "invalid bbox parameter"),
+    (ErrorCode::InvalidParam, "datetime参数错误", "invalid datetime parameter"),
+    (ErrorCode::InvalidParam, "filter参数必须为JSON格式", "filter must be JSON"),
+    (ErrorCode::InvalidParam, "参数错误，bbox必须为4个数字", "bbox must contain 4 numbers"),
+    (ErrorCode::InvalidParam, "参数错误，bbox范围错误", "bbox minimum must not exceed maximum"),
+    (ErrorCode::InvalidParam, "参数错误，bbox格式为[minx,miny,maxx,maxy]", "bbox must be [minx,miny,maxx,maxy]"),
+    (ErrorCode::InvalidParam, "参数错误，pageSize不能小于0", "pageSize must not be negative"),
+    (ErrorCode::InvalidParam, "参数错误，offset不能小于0", "offset must not be negative"),
+    (ErrorCode::InvalidParam, "参数错误，page必须大于0", "page must be greater than 0"),
+    (ErrorCode::InvalidParam, "参数错误，pageSize必须大于0", "pageSize must be greater than 0"),
+    (ErrorCode::InvalidParam, "参数错误，page以及pageSize必须大于0", "page and pageSize must be greater than 0"),
+    (ErrorCode::InvalidParam, "参数错误，该数据不包含空间字段", "the table has no spatial column"),
+    (ErrorCode::InvalidParam, "参数错误，geometry不能为空", "geometry must not be empty"),
+    (ErrorCode::InvalidParam, "参数错误，统计查询不支持关联表", "joins are not supported in aggregate queries"),
+    (ErrorCode::InvalidParam, "无法识别租户", "unable to resolve tenant"),
+    (ErrorCode::InvalidParam, "该数据库不支持CSV格式", "CSV format is not supported by this database"),
+    (ErrorCode::InvalidParam, "时间截断分组只支持postgres", "dateTrunc is only supported by postgres"),
+    (ErrorCode::InvalidParam, "集合没有配置时间字段", "the collection has no temporal column"),
+    (ErrorCode::InvalidParam, "没有需要写入的数据", "no data to write"),
+    (ErrorCode::InvalidParam, "每行数据的字段必须一致", "every row must have the same fields"),
+    (ErrorCode::InvalidParam, "冲突字段不能为空", "conflict columns must not be empty"),
+    (ErrorCode::InvalidParam, "参数错误，pageSize不能超过{}", "pageSize must not exceed {}"),
+    (ErrorCode::InvalidParam, "参数错误，关联{}未配置", "join {} is not configured"),
+    (ErrorCode::InvalidParam, "参数错误，关联{}不包含字段{}", "join {} has no field {}"),
+    (ErrorCode::InvalidParam, "参数错误，关联{}没有返回字段", "join {} returns no fields"),
+    (ErrorCode::InvalidParam, "参数错误，参数{}类型应该是{}", "parameter {} must be {}"),
+    (ErrorCode::InvalidParam, "参数错误，图层{}不支持参数{}", "layer {} does not support parameter {}"),
+    (ErrorCode::InvalidParam, "参数错误，图层{}缺少参数{}", "layer {} is missing parameter {}"),
+    (ErrorCode::InvalidParam, "参数错误，空间字段{}不存在", "spatial column {} does not exist"),
+    (ErrorCode::InvalidParam, "图层{}模板参数{}未声明", "layer {} template parameter {} is not declared"),
+    (ErrorCode::InvalidParam, "图层{}模板格式错误", "layer {} template is invalid"),
+    (ErrorCode::InvalidParam, "字段名称错误{}", "invalid field name {}"),
+    (ErrorCode::InvalidParam, "字段{}不能更新", "field {} cannot be updated"),
+    (ErrorCode::InvalidParam, "冲突字段{}不在写入数据中", "conflict column {} is not in the data"),
+    (ErrorCode::InvalidParam, "更新字段{}不在写入数据中", "update column {} is not in the data"),
+    (ErrorCode::InvalidParam, "数据表{}不存在", "table {} does not exist"),
+    (ErrorCode::InvalidParam, "租户{}不存在", "tenant {} does not exist"),
+    (ErrorCode::InvalidParam, "时区格式错误{}", "invalid timezone {}"),
+    (ErrorCode::InvalidParam, "查询结果超过{}条，请使用分页查询", "more than {} rows, use paging"),
+    (ErrorCode::InvalidParam, "不支持的查询语言{}", "unsupported filter language {}"),
+    (ErrorCode::InvalidParam, "不支持的返回格式{}", "unsupported format {}"),
+    (ErrorCode::InvalidParam, "不支持的查询参数{}", "unsupported query parameter {}"),
+    (ErrorCode::InvalidParam, "脱敏函数名称{}错误", "invalid mask function {}"),
+    (ErrorCode::InvalidParam, "{}参数错误", "invalid {} parameter"),
+    (ErrorCode::PermissionDenied, "无权使用字段{}", "field {} is not allowed"),
+    (ErrorCode::PermissionDenied, "无权查询字段{}", "field {} cannot be queried"),
+    (ErrorCode::PermissionDenied, "无权修改字段{}", "field {} cannot be modified"),
+    (ErrorCode::PermissionDenied, "脱敏字段{}不能用于过滤、排序、分组以及统计", "masked field {} cannot be used in filter, order, group or aggregate"),
+    (ErrorCode::ConvertFailed, "结果转换错误: {}", "failed to convert query result: {}"),
+    (ErrorCode::Timeout, "查询超时，执行时间超过{}毫秒", "query timed out after {} ms"),
+];
+
+// 按错误编码查找翻译，把中文信息中的参数填入英文模板
+fn translate(code: ErrorCode, message: &str) -> Option<String> {
+    MESSAGES
+        .iter()
+        .filter(|(item, _, _)| *item == code)
+        .find_map(|(_, zh, en)| {
+            let args = match_template(zh, message)?;
+            let mut args = args.into_iter();
+            let parts: Vec<&str> = en.split("{}").collect();
+            let mut data = parts[0].to_string();
+            for part in &parts[1..] {
+                data.push_str(args.next().unwrap_or_default());
+                data.push_str(part);
+            }
+            Some(data)
+        })
+}
+
+// 模板匹配，返回 {} 对应的参数，参数不能为空
+fn match_template<'a>(template: &str, message: &'a str) -> Option<Vec<&'a str>> {
+    let mut parts = template.split("{}");
+    let mut rest = message.strip_prefix(parts.next()?)?;
+    let parts: Vec<&str> = parts.collect();
+    let mut args = Vec::new();
+    for (index, part) in parts.iter().enumerate() {
+        // 最后一段必须在结尾，中间的段取最近的位置
+        let position = match index + 1 == parts.len() {
+            true => rest.strip_suffix(part).map(|item| item.len())?,
+            false => rest.get(1..)?.find(part)? + 1,
+        };
+        args.push(&rest[..position]);
+        rest = &rest[position + part.len()..];
+    }
+    match (parts.is_empty() && !rest.is_empty()) || args.iter().any(|item| item.is_empty()) {
+        true => None,
+        false => Some(args),
+    }
 }
 
 impl Display for CtsError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let msg = match self {
-            CtsError::AggregateError(data) => data,
-            CtsError::FieldError(data) => data,
-            CtsError::FilterError(data) => data,
-            CtsError::GroupError(data) => data,
-            CtsError::OrderError(data) => data,
-            CtsError::ParamError(data) => data,
-        };
-        write!(f, "{}", msg)
+        write!(f, "{}", self.message(Locale::ZhCn))
+    }
+}
+
+impl std::error::Error for CtsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CtsError::DatabaseError(err) => Some(err),
+            CtsError::PathError { source, .. } => Some(source.as_ref()),
+            CtsError::ValidationError(list) => list.first().map(|item| item as _),
+            _ => None,
+        }
+    }
+}
+
+impl From<sqlx::Error> for CtsError {
+    fn from(err: sqlx::Error) -> Self {
+        CtsError::DatabaseError(err)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(feature = "response")]
+impl CtsError {
+    /// 转换成 HTTP 响应，data 中返回错误编码以及参数路径，参数校验错误在 errors 中返回全部问题
+    /// @param locale 错误信息语言
+    pub fn to_response(&self, locale: Locale) -> axum::response::Response {
+        let code = self.code();
+        let mut detail = serde_json::json!({ "error": code, "path": self.path() });
+        if let CtsError::ValidationError(list) = self {
+            detail["errors"] = list
+                .iter()
+                .map(|item| {
+                    serde_json::json!({
+                        "error": item.code(),
+                        "path": item.path(),
+                        "message": item.message(locale),
+                    })
+                })
+                .collect();
+        }
+        let status = http::StatusCode::from_u16(code.http_status())
+            .unwrap_or(http::StatusCode::INTERNAL_SERVER_ERROR);
+        response_utils::res::ResResult::with_error_detail(
+            &self.message(locale),
+            code.number(),
+            status,
+            detail,
+        )
+    }
+}
+
+#[cfg(feature = "response")]
+impl axum::response::IntoResponse for CtsError {
+    fn into_response(self) -> axum::response::Response {
+        self.to_response(Locale::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn test_error() {
+        let err = CtsError::FilterError("LIKE参数错误".to_string())
+            .with_path("[1]")
+            .with_path("filter");
+        assert_eq!(err.path(), Some("filter[1]"));
+        assert_eq!(err.code(), ErrorCode::InvalidFilter);
+        assert_eq!(err.to_string(), "filter[1]: LIKE参数错误");
+        assert_eq!(
+            err.message(Locale::En),
+            "filter[1]: invalid LIKE parameter"
+        );
+
+        // 数据库错误不返回详细信息
+        let err = CtsError::from(sqlx::Error::Protocol("select secret".to_string()));
+        assert_eq!(err.message(Locale::En), "database query failed");
+        assert_eq!(err.code().http_status(), 500);
+        assert!(err.source().is_some());
+        assert_eq!(Locale::from_accept_language("en-US,en;q=0.9"), Locale::En);
+
+        // 带参数的错误信息按模板翻译
+        let err = CtsError::PermissionError("无权使用字段phone".to_string());
+        assert_eq!(err.message(Locale::En), "field phone is not allowed");
+        let err = CtsError::FilterError("查询语句错误，位置3：缺少括号".to_string());
+        assert_eq!(err.message(Locale::En), "invalid filter text at 3: 缺少括号");
+        let err = CtsError::ParamError("参数错误，图层road缺少参数level".to_string());
+        assert_eq!(
+            err.message(Locale::En),
+            "layer road is missing parameter level"
+        );
+        // 同样的信息按错误编码区分，没有模板时返回通用描述
+        let err = CtsError::OrderError("参数错误".to_string());
+        assert_eq!(err.message(Locale::En), "invalid order parameter");
+        let err = CtsError::FilterError("时间格式错误2024，必须为ISO 8601格式".to_string());
+        assert_eq!(err.message(Locale::En), "invalid time 2024, must be ISO 8601");
+    }
+}
@@ -1,9 +1,11 @@
 pub mod delete_sql;
+pub mod dialect_sql;
 pub mod parse;
 mod query_builder;
 pub mod save_sql;
 pub mod sql;
 pub mod update_sql;
+pub mod upsert_sql;
 
 use crate::error::CtsError;
 use crate::error::CtsError::ParamError;
@@ -1,43 +1,121 @@
 use sqlx::{Pool, Postgres};
 
+use crate::ast::Predicate;
+use crate::audit::{AuditOperation, Auditor};
+use crate::config::ExpressionConfig;
+use crate::tenant::set_search_path;
 
 /// delete sql构造器
-/// @param 请求参数
+/// > 配置中的强制过滤条件总是生效，不满足条件的数据不会被删除
+///
 /// @param pool 数据库连接池
 /// @table 查询表名
-/// @schema 对应数据库的schema
+/// @config 查询配置，提供schema以及强制过滤条件
 /// @id 数据唯一字段
 pub struct DeleteSqlBuilder<'a> {
     pool: &'a Pool<Postgres>,
     table: String,
     schema: String,
     id: String,
+    predicates: Vec<Predicate>,
+    search_path: bool,
+    auditor: Option<Auditor>,
 }
 
 impl<'a> DeleteSqlBuilder<'a> {
-    pub fn new(pool: &'a Pool<Postgres>, table: String, schema: String, id: String) -> Self {
+    pub fn new(
+        pool: &'a Pool<Postgres>,
+        table: String,
+        config: &ExpressionConfig,
+        id: String,
+    ) -> Self {
         Self {
             pool,
             table,
-            schema,
+            schema: config.schema(),
             id,
+            predicates: config.predicates.clone(),
+            search_path: config.search_path,
+            auditor: config.auditor(None),
         }
     }
 
+    /// 添加强制过滤条件，与配置中的强制过滤条件一起生效
+    pub fn with_predicates(mut self, predicates: Vec<Predicate>) -> Self {
+        self.predicates.extend(predicates);
+        self
+    }
+
+    /// 记录审计，为空时不记录，默认使用配置中的审计
+    /// @param auditor 审计写入，通过 `ExpressionConfig::auditor` 获取
+    pub fn with_auditor(mut self, auditor: Option<Auditor>) -> Self {
+        self.auditor = auditor;
+        self
+    }
+
+    /// 审计记录的操作人，没有开启审计时忽略
+    /// @param actor 操作人
+    pub fn with_actor(mut self, actor: String) -> Self {
+        self.auditor = self.auditor.map(|auditor| auditor.with_actor(Some(actor)));
+        self
+    }
+
     pub fn build(&self) -> String {
-        let sql = format!(
+        let mut sql = format!(
             "DELETE FROM {}.{} WHERE id = $1",
             self.schema, self.table
         );
+        if let Some(predicate) = Predicate::and_all(self.predicates.iter().cloned()) {
+            sql.push_str(&format!(" AND ({predicate})"));
+        }
         sql
     }
 
     pub async fn execute(&self) -> Result<(), sqlx::Error> {
-        let sql = self.build();
-        sqlx::query(&sql)
-            .bind(&self.id)
-            .execute(self.pool)
-            .await?;
+        let sql = match &self.auditor {
+            None => self.build(),
+            Some(auditor) => auditor.wrap(
+                &self.build(),
+                AuditOperation::Delete,
+                &self.schema,
+                &self.table,
+                None,
+            ),
+        };
+        // 需要设置 search_path 时在事务中删除，事务结束后设置失效
+        let query = sqlx::query(&sql).bind(&self.id);
+        match self.search_path {
+            true => {
+                let mut tx = self.pool.begin().await?;
+                set_search_path(&mut tx, &self.schema).await?;
+                query.execute(&mut *tx).await?;
+                tx.commit().await?;
+            }
+            false => {
+                query.execute(self.pool).await?;
+            }
+        }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use sqlx::postgres::PgPoolOptions;
+
+    use super::*;
+    use crate::ast::field;
+
+    #[tokio::test]
+    async fn test_build() {
+        let pool = PgPoolOptions::new()
+            .connect_lazy("postgres://localhost/test")
+            .unwrap();
+        let config = ExpressionConfig::new(None).with_predicate(field("org_id").eq("org-1"));
+        let builder = DeleteSqlBuilder::new(&pool, "road".to_string(), &config, "1".to_string());
+        assert_eq!(
+            builder.build(),
+            "DELETE FROM public.road WHERE id = $1 AND (org_id = 'org-1')"
+        );
+    }
+}
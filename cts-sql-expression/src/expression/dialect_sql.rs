@@ -0,0 +1,527 @@
+use std::sync::Arc;
+
+use serde_json::{json, Map, Value};
+use sqlx::any::{AnyRow, AnyTypeInfoKind};
+use sqlx::{AnyPool, Column, Row, ValueRef};
+
+use crate::ast::Predicate;
+use crate::config::{ExpressionConfig, QueryMode};
+use crate::dialect::{Dialect, DialectKind, SpatialFunction};
+use crate::error::CtsError;
+use crate::error::CtsError::{DatabaseError, ParamError};
+use crate::expression::parse::aggregate::AggregateParse;
+use crate::expression::parse::date_trunc::DateTruncParse;
+use crate::expression::parse::field::FieldParse;
+use crate::expression::parse::group::GroupByParse;
+use crate::expression::parse::order::OrderByParse;
+use crate::expression::query_builder::QueryBuilder;
+use crate::expression::{SqlParse, GEOMETRY};
+use crate::limits::QueryLimits;
+use crate::policy::FieldPolicies;
+use crate::request::{CtsFormat, CtsParam, GeometryFormat};
+use crate::timezone::TimeZone;
+use crate::validate::ParamValidator;
+
+/// # 多数据库sql构造器
+/// > 通过 sqlx Any 连接池查询 MySQL、SQLite 等数据库，请求参数语法与 SqlBuilder 一致，
+/// > 数据库差异由配置中的方言处理，空间查询只支持返回第一个空间字段以及范围过滤
+/// ```rust,ignore
+/// sqlx::any::install_default_drivers();
+/// let pool = AnyPool::connect("sqlite::memory:").await?;
+/// let config = ExpressionConfig::new_normal(None).with_dialect(DialectKind::Sqlite);
+/// let value = DialectSqlBuilder::new(&pool, "road".to_string(), config, param).query().await?;
+/// ```
+pub struct DialectSqlBuilder<'a> {
+    param: CtsParam,
+    pool: &'a AnyPool,
+    table: String,
+    schema: Option<String>,
+    query_mode: QueryMode,
+    dialect: Arc<dyn Dialect>,
+    predicates: Vec<Predicate>,
+    field_policies: FieldPolicies,
+    limits: QueryLimits,
+    timezone: Option<TimeZone>,
+}
+
+impl<'a> DialectSqlBuilder<'a> {
+    pub fn new(
+        pool: &'a AnyPool,
+        table: String,
+        config: ExpressionConfig,
+        param: CtsParam,
+    ) -> Self {
+        let dialect = config.dialect.dialect();
+        // 没有设置schema时使用方言默认schema
+        let schema = config
+            .schema
+            .clone()
+            .or(dialect.default_schema().map(|item| item.to_string()));
+        let field_policies = config.field_policies(&table);
+        let mut param = match config.query_mode {
+            QueryMode::Normal => param.search_param(),
+            QueryMode::Spatial => param,
+        };
+        if let Some(page) = param.page.as_mut() {
+            config.limits.normalize_page(page);
+        }
+        Self {
+            param,
+            pool,
+            table,
+            schema,
+            query_mode: config.query_mode,
+            dialect,
+            predicates: config.predicates,
+            field_policies,
+            limits: config.limits,
+            timezone: config.timezone,
+        }
+    }
+
+    /// 生成查询sql
+    pub async fn parse(&self) -> Result<String, CtsError> {
+        let param = &self.param;
+        // 参数校验，一次返回全部问题
+        ParamValidator(param).validate()?;
+        // 字段权限检查
+        self.field_policies.check_param(param)?;
+        let filter = self.parse_filter().await?;
+        let group = GroupByParse(&param.group_by)
+            .parse()
+            .map_err(|err| err.with_path("groupBy"))?;
+        let field = FieldParse(&param.out_fields)
+            .parse_with_policies(&self.field_policies)
+            .map_err(|err| err.with_path("outFields"))?;
+        let aggregate = AggregateParse(&param.aggregate)
+            .parse()
+            .map_err(|err| err.with_path("aggregate"))?;
+        // 时间截断分组只支持 postgres
+        if param.date_trunc.is_some() && self.dialect.kind() != DialectKind::Postgres {
+            return Err(ParamError("时间截断分组只支持postgres".to_string()).with_path("dateTrunc"));
+        }
+        let date_trunc = DateTruncParse {
+            param: &param.date_trunc,
+            timezone: self.trunc_timezone().await?,
+        };
+        let trunc_field = date_trunc
+            .parse()
+            .map_err(|err| err.with_path("dateTrunc"))?;
+        let trunc_group = date_trunc
+            .group()
+            .map_err(|err| err.with_path("dateTrunc"))?;
+        let group = match (group, trunc_group) {
+            (Some(group), Some(trunc)) => Some(format!("{group},{trunc}")),
+            (group, trunc) => group.or(trunc),
+        };
+        let order = OrderByParse(&param.order_by)
+            .parse()
+            .map_err(|err| err.with_path("orderBy"))?;
+        let mut builder = QueryBuilder::new_select();
+        let fields = match (aggregate, field) {
+            (None, None) if trunc_field.is_some() => String::new(),
+            (None, None) => self.get_table_columns().await?,
+            (None, Some(fields)) => match self.return_geometry() {
+                true => format!("{fields},{}", self.get_geometry_field().await?),
+                false => fields,
+            },
+            (Some(agg), None) => agg,
+            (Some(agg), Some(fields)) => format!("{fields}, {agg}"),
+        };
+        let fields = match (trunc_field, fields.is_empty()) {
+            (Some(trunc), true) => trunc,
+            (Some(trunc), false) => format!("{trunc},{fields}"),
+            (None, _) => fields,
+        };
+        builder.push(fields);
+        builder.push(" from ");
+        builder.push(self.table_name());
+        if let Some(data) = filter {
+            builder.push(" where ");
+            builder.push(data);
+        }
+        if let Some(data) = group {
+            builder.push(" group by ");
+            builder.push(data);
+        }
+        if let Some(data) = order {
+            builder.push(" order by ");
+            builder.push(data);
+        }
+        match &param.page {
+            Some(page) => {
+                self.limits
+                    .check_page(page)
+                    .map_err(|err| err.with_path("page"))?;
+                builder.push(
+                    self.dialect
+                        .limit_offset(page.page_size as i64, page.skip()),
+                );
+            }
+            None => {
+                if let Some(limit) = self.limits.unpaged_limit() {
+                    builder.push(limit);
+                }
+            }
+        }
+        Ok(builder.build())
+    }
+
+    fn table_name(&self) -> String {
+        self.dialect.table_name(self.schema.as_deref(), &self.table)
+    }
+
+    fn return_geometry(&self) -> bool {
+        matches!(self.query_mode, QueryMode::Spatial)
+            && matches!(self.param.return_geometry, Some(true))
+    }
+
+    /// 查询表字段名称以及类型，没有 schema 时由方言决定查询范围，MySQL 查询连接的数据库
+    pub async fn table_columns(&self) -> Result<Vec<(String, String)>, CtsError> {
+        let list = sqlx::query(&self.dialect.columns_sql())
+            .bind(self.schema.clone())
+            .bind(&self.table)
+            .fetch_all(self.pool)
+            .await
+            .map_err(|err| self.limits.query_error(err))?;
+        if list.is_empty() {
+            return Err(ParamError(format!("数据表{}不存在", self.table)));
+        }
+        list.iter()
+            .map(|row| {
+                let name = row.try_get::<String, _>("name")?;
+                let type_name = row.try_get::<String, _>("type_name")?;
+                Ok((name, type_name))
+            })
+            .collect::<Result<Vec<_>, sqlx::Error>>()
+            .map_err(|err| self.limits.query_error(err))
+    }
+
+    // 时间截断的时区只作用于 timestamptz 字段，关联表字段无法确定类型时按 timestamptz 处理
+    async fn trunc_timezone(&self) -> Result<Option<TimeZone>, CtsError> {
+        let (Some(timezone), Some(param)) = (self.timezone, &self.param.date_trunc) else {
+            return Ok(None);
+        };
+        let name = match param.field.split_once('.') {
+            None => param.field.as_str(),
+            Some((table, name)) if table == self.table => name,
+            Some(_) => return Ok(Some(timezone)),
+        };
+        let columns = self.table_columns().await?;
+        match columns.iter().find(|(item, _)| item == name) {
+            Some((_, type_name)) if type_name != "timestamptz" => Ok(None),
+            _ => Ok(Some(timezone)),
+        }
+    }
+
+    // 查询表字段方法，空间查询时去掉空间字段，需要时单独返回
+    async fn get_table_columns(&self) -> Result<String, CtsError> {
+        let policies = &self.field_policies;
+        if matches!(self.query_mode, QueryMode::Normal) && policies.is_empty() {
+            return Ok("*".to_string());
+        }
+        let columns = self.table_columns().await?;
+        let mut fields = Vec::new();
+        for (name, type_name) in columns.iter() {
+            if policies.is_hidden(name)
+                || (matches!(self.query_mode, QueryMode::Spatial)
+                    && self.dialect.is_spatial_type(type_name))
+            {
+                continue;
+            }
+            fields.push(match policies.access(name) {
+                None => self.dialect.quote_ident(name),
+                Some(_) => policies.select(name, None)?,
+            });
+        }
+        if self.return_geometry() {
+            fields.push(self.get_geometry_field().await?);
+        }
+        Ok(fields.join(","))
+    }
+
+    // 第一个空间字段
+    async fn get_geometry_column(&self) -> Result<String, CtsError> {
+        let columns = self.table_columns().await?;
+        columns
+            .into_iter()
+            .find(|(name, type_name)| {
+                self.dialect.is_spatial_type(type_name) && !self.field_policies.is_hidden(name)
+            })
+            .map(|(name, _)| name)
+            .ok_or(ParamError("参数错误，该数据不包含空间字段".to_string()))
+    }
+
+    // 返回的空间字段查询语句
+    async fn get_geometry_field(&self) -> Result<String, CtsError> {
+        let column = self.dialect.quote_ident(&self.get_geometry_column().await?);
+        let function = match self.param.geo_format {
+            None | Some(GeometryFormat::GeoJson) => SpatialFunction::AsGeoJson,
+            Some(GeometryFormat::WKT) | Some(GeometryFormat::Text) => SpatialFunction::AsText,
+            Some(GeometryFormat::Byte) | Some(GeometryFormat::WKB) => SpatialFunction::AsBinary,
+        };
+        Ok(format!(
+            "{}({column}) as {GEOMETRY}",
+            self.dialect.spatial_function(function)
+        ))
+    }
+
+    // 解析过滤条件，包含范围过滤以及强制过滤条件
+    async fn parse_filter(&self) -> Result<Option<String>, CtsError> {
+        let param = &self.param;
+        let mut conditions = Vec::new();
+        // 过滤条件按方言渲染，标识符、字符串以及时间不能使用 postgres 的写法
+        if let Some(filter) = &param.filter {
+            let sql = Predicate::from_filter(filter)
+                .and_then(|predicate| predicate.to_dialect_sql(self.dialect.as_ref()))
+                .map_err(|err| err.with_path("filter"))?;
+            conditions.push(sql);
+        }
+        if let Some(bbox) = &param.bbox {
+            if bbox.len() != 4 || bbox.iter().any(|item| !item.is_finite()) {
+                return Err(ParamError(
+                    "参数错误，bbox格式为[minx,miny,maxx,maxy]".to_string(),
+                ));
+            }
+            let column = self.dialect.quote_ident(&self.get_geometry_column().await?);
+            let (x1, y1, x2, y2) = (bbox[0], bbox[1], bbox[2], bbox[3]);
+            let polygon = format!("POLYGON(({x1} {y1},{x2} {y1},{x2} {y2},{x1} {y2},{x1} {y1}))");
+            conditions.push(format!(
+                "{}({column}, {}('{polygon}', {}))",
+                self.dialect.spatial_function(SpatialFunction::Intersects),
+                self.dialect.spatial_function(SpatialFunction::GeomFromText),
+                param.bbox_crs.unwrap_or(4326)
+            ));
+        }
+        if let Some(predicate) = Predicate::and_all(self.predicates.iter().cloned()) {
+            conditions.push(predicate.to_dialect_sql(self.dialect.as_ref())?);
+        }
+        match conditions.len() {
+            0 => Ok(None),
+            1 => Ok(conditions.pop()),
+            _ => {
+                let conditions: Vec<String> =
+                    conditions.iter().map(|item| format!("({item})")).collect();
+                Ok(Some(conditions.join(" and ")))
+            }
+        }
+    }
+
+    // 解析分页查询sql函数
+    async fn parse_page_count(&self) -> Result<String, CtsError> {
+        let filter = self.parse_filter().await?;
+        let mut builder = QueryBuilder::new("select count(*) as count");
+        builder.push(" from ");
+        builder.push(self.table_name());
+        if let Some(data) = filter {
+            builder.push(" where ");
+            builder.push(data);
+        }
+        Ok(builder.build())
+    }
+
+    /// 查询数据，支持 JSON 以及 GeoJSON 格式，分页结果与 SqlBuilder 一致
+    pub async fn query(&mut self) -> Result<Value, CtsError> {
+        let format = self.format();
+        let query = self.parse().await?;
+        let rows = sqlx::query(&query)
+            .fetch_all(self.pool)
+            .await
+            .map_err(|err| self.limits.query_error(err))?;
+        let list = rows.iter().map(row_to_map).collect::<Result<Vec<_>, _>>()?;
+        let list = match format {
+            CtsFormat::GeoJson => to_feature_collection(list),
+            CtsFormat::Json => Value::Array(list.into_iter().map(Value::Object).collect()),
+            CtsFormat::CSV => return Err(ParamError("该数据库不支持CSV格式".to_string())),
+        };
+        let page = match &self.param.page {
+            Some(page) if self.param.aggregate.is_none() => page,
+            _ => return Ok(list),
+        };
+        let row = sqlx::query(&self.parse_page_count().await?)
+            .fetch_one(self.pool)
+            .await
+            .map_err(|err| self.limits.query_error(err))?;
+        let total = row
+            .try_get::<i64, _>(0)
+            .map_err(|err| self.limits.query_error(err))?;
+        let pages = (total as f64 / page.page_size as f64).ceil() as i64;
+        Ok(json!({
+            "currentPage": page.page,
+            "pageSize": page.page_size,
+            "pages": pages,
+            "total": total,
+            "list": list,
+        }))
+    }
+
+    fn format(&mut self) -> CtsFormat {
+        match &self.param.format {
+            None | Some(CtsFormat::Json) => CtsFormat::Json,
+            Some(CtsFormat::GeoJson) => {
+                self.param.geo_format = Some(GeometryFormat::GeoJson);
+                self.param.return_geometry = Some(true);
+                CtsFormat::GeoJson
+            }
+            Some(CtsFormat::CSV) => CtsFormat::CSV,
+        }
+    }
+}
+
+/// 将数据行转换成json对象，按数据实际类型转换
+fn row_to_map(row: &AnyRow) -> Result<Map<String, Value>, CtsError> {
+    let mut map = Map::new();
+    for (index, column) in row.columns().iter().enumerate() {
+        let raw = row.try_get_raw(index).map_err(DatabaseError)?;
+        let kind = if raw.is_null() {
+            AnyTypeInfoKind::Null
+        } else {
+            raw.type_info().kind()
+        };
+        let value = match kind {
+            AnyTypeInfoKind::Null => Ok(Value::Null),
+            AnyTypeInfoKind::Bool => row.try_get::<bool, _>(index).map(Value::from),
+            AnyTypeInfoKind::SmallInt | AnyTypeInfoKind::Integer | AnyTypeInfoKind::BigInt => {
+                row.try_get::<i64, _>(index).map(Value::from)
+            }
+            AnyTypeInfoKind::Real => row.try_get::<f32, _>(index).map(|v| Value::from(v as f64)),
+            AnyTypeInfoKind::Double => row.try_get::<f64, _>(index).map(Value::from),
+            AnyTypeInfoKind::Text => row.try_get::<String, _>(index).map(Value::from),
+            AnyTypeInfoKind::Blob => row.try_get::<Vec<u8>, _>(index).map(Value::from),
+        }
+        .map_err(DatabaseError)?;
+        map.insert(column.name().to_string(), value);
+    }
+    Ok(map)
+}
+
+/// 转换成 GeoJSON 要素集合，GEOMETRY 字段作为要素空间数据
+fn to_feature_collection(list: Vec<Map<String, Value>>) -> Value {
+    let features: Vec<Value> = list
+        .into_iter()
+        .map(|mut properties| {
+            let geometry = match properties.remove(GEOMETRY) {
+                Some(Value::String(data)) => serde_json::from_str(&data).unwrap_or(Value::Null),
+                _ => Value::Null,
+            };
+            json!({"type": "Feature", "geometry": geometry, "properties": properties})
+        })
+        .collect();
+    json!({"type": "FeatureCollection", "features": features})
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialect::DialectKind;
+    use sqlx::any::{install_default_drivers, AnyPoolOptions};
+
+    async fn pool() -> AnyPool {
+        install_default_drivers();
+        // 内存数据库每个连接相互独立，只使用一个连接
+        let pool = AnyPoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::query(
+            "create table road (id integer primary key, name text, level integer, length real, created_at text)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "insert into road (name, level, length, created_at) values ('a', 1, 1.5, '2024-01-01 08:00:00'), \
+             ('b', 2, 2.5, '2024-03-01T00:00:00'), ('c', 2, null, datetime('now', '-1 day'))",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_query() {
+        let pool = pool().await;
+        let config = ExpressionConfig::new_normal(None).with_dialect(DialectKind::Sqlite);
+        let param: CtsParam = serde_json::from_value(json!({
+            "filter": ["=", "level", 2],
+            "orderBy": [["name", "desc"]],
+            "page": {"page": 1, "pageSize": 1}
+        }))
+        .unwrap();
+        let mut builder = DialectSqlBuilder::new(&pool, "road".to_string(), config, param);
+        assert_eq!(
+            builder.parse().await.unwrap(),
+            "select * from \"main\".\"road\" where \"level\" = 2 order by name desc LIMIT 1 OFFSET 0"
+        );
+        let value = builder.query().await.unwrap();
+        assert_eq!(value["total"], 2);
+        assert_eq!(value["pages"], 2);
+        assert_eq!(value["list"][0]["name"], "c");
+        assert_eq!(value["list"][0]["length"], Value::Null);
+
+        // 统计，普通查询模式会去掉分组以及统计参数
+        let config = ExpressionConfig::new(None).with_dialect(DialectKind::Sqlite);
+        let param: CtsParam = serde_json::from_value(json!({
+            "groupBy": ["level"],
+            "outFields": ["level"],
+            "aggregate": ["count", "id"],
+            "orderBy": ["level"]
+        }))
+        .unwrap();
+        let value = DialectSqlBuilder::new(&pool, "road".to_string(), config, param)
+            .query()
+            .await
+            .unwrap();
+        assert_eq!(value, json!([{"level": 1, "id": 1}, {"level": 2, "id": 2}]));
+
+        // 没有 schema 时绑定空值，由方言决定查询范围
+        let config = ExpressionConfig::new(None).with_dialect(DialectKind::Sqlite);
+        let param: CtsParam = serde_json::from_value(json!({})).unwrap();
+        let mut builder = DialectSqlBuilder::new(&pool, "road".to_string(), config, param);
+        builder.schema = None;
+        assert_eq!(builder.table_columns().await.unwrap().len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_temporal() {
+        let pool = pool().await;
+        let query = |filter: Value| {
+            let config = ExpressionConfig::new_normal(None).with_dialect(DialectKind::Sqlite);
+            let param: CtsParam = serde_json::from_value(json!({
+                "filter": filter,
+                "outFields": ["name"],
+                "orderBy": ["name"]
+            }))
+            .unwrap();
+            let pool = &pool;
+            async move {
+                DialectSqlBuilder::new(pool, "road".to_string(), config, param)
+                    .query()
+                    .await
+            }
+        };
+        let value = query(json!(["during", "created_at", "2024-01-01", "2024-02-01"]))
+            .await
+            .unwrap();
+        assert_eq!(value, json!([{"name": "a"}]));
+        // 带时区的时间转换成UTC后比较
+        let value = query(json!(["after", "created_at", "2024-03-01T07:00:00+08:00"]))
+            .await
+            .unwrap();
+        assert_eq!(value, json!([{"name": "b"}, {"name": "c"}]));
+        let value = query(json!(["last", "created_at", 1, "week"]))
+            .await
+            .unwrap();
+        assert_eq!(value, json!([{"name": "c"}]));
+
+        let err = query(json!(["before", "created_at", "yesterday"]))
+            .await
+            .unwrap_err();
+        match &err {
+            CtsError::ValidationError(list) => assert_eq!(list[0].path(), Some("filter[2]")),
+            _ => panic!("{err}"),
+        }
+    }
+}
@@ -8,8 +8,11 @@ pub mod field;
 pub mod aggregate;
 pub mod order;
 pub mod page;
+pub mod bbox;
+pub mod join;
+pub mod date_trunc;
 
-fn handler_name(data: &CtsValue) -> Result<String, CtsError> {
+pub(crate) fn handler_name(data: &CtsValue) -> Result<String, CtsError> {
     match data {
         CtsValue::Single(Single::String(field)) => {
             Ok(field.to_string())
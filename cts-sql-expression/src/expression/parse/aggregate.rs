@@ -2,7 +2,7 @@ use crate::error::CtsError;
 use crate::error::CtsError::AggregateError;
 use crate::expression::{CtsValue, Single, SqlParse};
 
-static OPERATORS: [&str; 5] = ["sum", "count", "max", "min", "avg"];
+pub(crate) static OPERATORS: [&str; 5] = ["sum", "count", "max", "min", "avg"];
 
 pub struct AggregateParse<'a>(pub &'a Option<Vec<CtsValue>>);
 
@@ -22,79 +22,59 @@ impl SqlParse for AggregateParse<'_> {
 }
 
 fn handler_parse(data: &[CtsValue]) -> Result<Option<String>, CtsError> {
-    let mut result = Vec::new();
     // 判断是否为空
     if data.is_empty() {
         return Err(AggregateError("统计参数错误".to_string()));
     }
-    // 遍历数据
-    for datum in data.iter() {
-        match datum {
-            CtsValue::Single(ope) => {
-                // 判断类型是否为字符串，其他类型抛出错误
-                return match ope {
-                    Single::String(ope_str) => {
-                        // 如果是字符串，该项为操作符，判断操作函数是否支持
-                        if !OPERATORS.contains(&ope_str.to_lowercase().as_str()) {
-                            return Err(AggregateError("统计操作符不支持".to_string()));
-                        }
-                        // 判断数组是否等于2
-                        match data.len() {
-                            2 => {
-                                let second = &data[1];
-                                match second {
-                                    CtsValue::Single(field) => match field {
-                                        Single::String(field_str) => Ok(Some(format!(
-                                            "{ope_str}({field_str}) as {field_str}"
-                                        ))),
-                                        _ => Err(AggregateError("统计【字段】必须为字符串".to_string())),
-                                    },
-                                    CtsValue::Array(_) => Err(AggregateError("统计参数错误".to_string())),
-                                }
-                            }
-                            3 => {
-                                let second = &data[1];
-                                match second {
-                                    CtsValue::Single(field) => match field {
-                                        Single::String(field_str) => {
-                                            let third = &data[2];
-                                            match third {
-                                                CtsValue::Single(value) => match value {
-                                                    Single::String(value_str) => Ok(Some(format!(
-                                                        "{ope_str}({field_str}) as {value_str}"
-                                                    ))),
-                                                    _ => {
-                                                        Err(AggregateError("统计【别名】必须为字符串".to_string()))
-                                                    }
-                                                },
-                                                CtsValue::Array(_) => {
-                                                    Err(AggregateError("统计参数错误".to_string()))
-                                                }
-                                            }
-                                        }
-                                        _ => Err(AggregateError("统计【字段】必须为字符串".to_string())),
-                                    },
-                                    CtsValue::Array(_) => Err(AggregateError("统计参数错误".to_string())),
-                                }
-                            }
-                            _ => Err(AggregateError("统计参数错误".to_string())),
-                        }
-                    }
-                    _ => Err(AggregateError("统计操作符不支持".to_string())),
-                };
-            }
-            CtsValue::Array(sub_data) => {
-                let expression = handler_parse(sub_data)?;
-                if let Some(expr) = expression {
-                    result.push(expr);
-                }
-            }
+    // 第一项为字符串时整个数组为一个统计函数，例如 ["sum", field, alias]
+    if let CtsValue::Single(_) = &data[0] {
+        return Ok(Some(handler_item(data)?));
+    }
+    let mut result = Vec::new();
+    // 遍历数据，每一项都必须是统计函数数组
+    for (index, datum) in data.iter().enumerate() {
+        let expression = match datum {
+            CtsValue::Array(sub_data) => handler_item(sub_data),
+            CtsValue::Single(_) => Err(AggregateError("统计参数错误".to_string())),
         }
+        .map_err(|err| err.with_path(&format!("[{index}]")))?;
+        result.push(expression);
     }
-
     Ok(Some(result.join(",")))
 }
 
+/// 解析单个统计函数 [操作符, 字段] 或者 [操作符, 字段, 别名]
+pub(crate) fn handler_item(data: &[CtsValue]) -> Result<String, CtsError> {
+    if !(2..=3).contains(&data.len()) {
+        return Err(AggregateError("统计参数错误".to_string()));
+    }
+    // 操作符，判断操作函数是否支持
+    let ope = match &data[0] {
+        CtsValue::Single(Single::String(ope)) if OPERATORS.contains(&ope.to_lowercase().as_str()) => ope,
+        _ => return Err(AggregateError("统计操作符不支持".to_string()).with_path("[0]")),
+    };
+    // 字段
+    let field = match &data[1] {
+        CtsValue::Single(Single::String(field)) => field,
+        CtsValue::Single(_) => {
+            return Err(AggregateError("统计【字段】必须为字符串".to_string()).with_path("[1]"))
+        }
+        CtsValue::Array(_) => return Err(AggregateError("统计参数错误".to_string()).with_path("[1]")),
+    };
+    // 别名，没有时使用字段名称
+    let alias = match data.get(2) {
+        None => field,
+        Some(CtsValue::Single(Single::String(alias))) => alias,
+        Some(CtsValue::Single(_)) => {
+            return Err(AggregateError("统计【别名】必须为字符串".to_string()).with_path("[2]"))
+        }
+        Some(CtsValue::Array(_)) => {
+            return Err(AggregateError("统计参数错误".to_string()).with_path("[2]"))
+        }
+    };
+    Ok(format!("{ope}({field}) as {alias}"))
+}
+
 #[cfg(test)]
 mod tests {
     use crate::expression::parse::aggregate::AggregateParse;
@@ -117,4 +97,25 @@ mod tests {
         let cc = bbb.parse().unwrap();
         println!("{}", cc.unwrap());
     }
+
+    #[test]
+    fn parse_mixed() {
+        // 统计函数数组中不能混入单个操作符
+        let aaa = Some(vec![
+            CtsValue::Array(vec![
+                CtsValue::Single(Single::String("sum".to_string())),
+                CtsValue::Single(Single::String("field".to_string())),
+            ]),
+            CtsValue::Single(Single::String("count".to_string())),
+        ]);
+        let err = AggregateParse(&aaa).parse().unwrap_err();
+        assert_eq!(err.path(), Some("[1]"));
+
+        let bbb = Some(vec![
+            CtsValue::Single(Single::String("count".to_string())),
+            CtsValue::Single(Single::String("id".to_string())),
+            CtsValue::Single(Single::String("total".to_string())),
+        ]);
+        assert_eq!(AggregateParse(&bbb).parse().unwrap().unwrap(), "count(id) as total");
+    }
 }
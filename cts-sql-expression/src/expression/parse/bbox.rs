@@ -0,0 +1,99 @@
+use crate::error::CtsError;
+use crate::error::CtsError::FilterError;
+use crate::expression::SqlParse;
+
+/// 默认范围坐标系
+pub const DEFAULT_CRS: i32 = 4326;
+
+/// # 范围过滤解析
+/// > 范围参数为 `[minx, miny, maxx, maxy]`，坐标系默认 4326，与空间字段坐标系不同时转换到字段坐标系
+/// ```sql
+/// st_intersects(shape, st_transform(st_makeenvelope(119, 29, 121, 31, 4326), 4490))
+/// ```
+pub struct BboxParse<'a> {
+    pub bbox: &'a Option<Vec<f64>>,
+    pub crs: Option<i32>,
+    /// 空间字段名称
+    pub column: &'a str,
+    /// 空间字段类型，geometry、geography 或者 raster
+    pub udt_name: &'a str,
+    /// 空间字段坐标系
+    pub srid: Option<i32>,
+}
+
+impl SqlParse for BboxParse<'_> {
+    fn parse(&self) -> Result<Option<String>, CtsError> {
+        let bbox = match self.bbox {
+            None => return Ok(None),
+            Some(data) => data,
+        };
+        if bbox.len() != 4 || bbox.iter().any(|value| !value.is_finite()) {
+            return Err(FilterError(
+                "bbox参数错误，格式为[minx,miny,maxx,maxy]".to_string(),
+            ));
+        }
+        let crs = self.crs.unwrap_or(DEFAULT_CRS);
+        let envelope = format!(
+            "st_makeenvelope({}, {}, {}, {}, {crs})",
+            bbox[0], bbox[1], bbox[2], bbox[3]
+        );
+        let column = self.column;
+        let expression = match self.udt_name {
+            // geography 固定使用 4326
+            "geography" => {
+                let envelope = transform(envelope, crs, DEFAULT_CRS);
+                format!("st_intersects({column}, {envelope}::geography)")
+            }
+            udt_name => {
+                let envelope = match self.srid {
+                    Some(srid) if srid > 0 => transform(envelope, crs, srid),
+                    _ => envelope,
+                };
+                if udt_name == "raster" {
+                    format!("st_intersects(st_envelope({column}), {envelope})")
+                } else {
+                    format!("st_intersects({column}, {envelope})")
+                }
+            }
+        };
+        Ok(Some(expression))
+    }
+}
+
+fn transform(envelope: String, from: i32, to: i32) -> String {
+    if from == to {
+        envelope
+    } else {
+        format!("st_transform({envelope}, {to})")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_bbox() {
+        let bbox = Some(vec![119.0, 29.0, 121.5, 31.0]);
+        let parse = BboxParse {
+            bbox: &bbox,
+            crs: None,
+            column: "shape",
+            udt_name: "geometry",
+            srid: Some(4490),
+        };
+        assert_eq!(
+            parse.parse().unwrap().unwrap(),
+            "st_intersects(shape, st_transform(st_makeenvelope(119, 29, 121.5, 31, 4326), 4490))"
+        );
+        let bbox = Some(vec![1.0, 2.0]);
+        let parse = BboxParse {
+            bbox: &bbox,
+            crs: None,
+            column: "shape",
+            udt_name: "geometry",
+            srid: None,
+        };
+        assert!(parse.parse().is_err());
+    }
+}
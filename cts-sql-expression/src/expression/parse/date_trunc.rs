@@ -0,0 +1,105 @@
+use crate::error::CtsError;
+use crate::error::CtsError::GroupError;
+use crate::expression::SqlParse;
+use crate::request::DateTruncParam;
+use crate::timezone::TimeZone;
+
+/// 时间截断单位
+pub static TRUNC_UNITS: [&str; 8] = [
+    "second", "minute", "hour", "day", "week", "month", "quarter", "year",
+];
+
+/// # 时间截断分组解析
+/// > 截断后的时间作为查询字段以及分组字段，设置时区时先把 timestamptz 转换到该时区再截断，
+/// > 按天、周、月统计时与业务时区一致；timestamp 字段不需要转换，由调用方按字段类型决定是否传入时区
+/// ```sql
+/// date_trunc('day', created_at AT TIME ZONE INTERVAL '+08:00') as created_at
+/// ```
+pub struct DateTruncParse<'a> {
+    pub param: &'a Option<DateTruncParam>,
+    pub timezone: Option<TimeZone>,
+}
+
+impl SqlParse for DateTruncParse<'_> {
+    /// 查询字段
+    fn parse(&self) -> Result<Option<String>, CtsError> {
+        let Some(param) = self.param else {
+            return Ok(None);
+        };
+        let alias = param.alias.as_deref().unwrap_or(&param.field);
+        check_name(alias).map_err(|err| err.with_path(".alias"))?;
+        Ok(self.group()?.map(|group| format!("{group} as {alias}")))
+    }
+}
+
+impl DateTruncParse<'_> {
+    /// 分组表达式，不使用别名，避免别名与表字段同名时按表字段分组
+    pub fn group(&self) -> Result<Option<String>, CtsError> {
+        let Some(param) = self.param else {
+            return Ok(None);
+        };
+        check_name(&param.field).map_err(|err| err.with_path(".field"))?;
+        let unit = param.unit.to_lowercase();
+        if !TRUNC_UNITS.contains(&unit.as_str()) {
+            return Err(GroupError(format!("不支持的时间单位{unit}")).with_path(".unit"));
+        }
+        let field = match self.timezone {
+            Some(timezone) => format!("{} AT TIME ZONE {}", param.field, timezone.to_sql()),
+            None => param.field.to_string(),
+        };
+        Ok(Some(format!("date_trunc('{unit}', {field})")))
+    }
+}
+
+// 字段名称只能包含字母、数字、下划线以及点
+fn check_name(name: &str) -> Result<(), CtsError> {
+    match !name.is_empty()
+        && name
+            .chars()
+            .all(|item| item.is_alphanumeric() || matches!(item, '_' | '.'))
+    {
+        true => Ok(()),
+        false => Err(GroupError(format!("字段名称错误{name}"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn date_trunc() {
+        let param = Some(DateTruncParam {
+            field: "created_at".to_string(),
+            unit: "Month".to_string(),
+            alias: Some("month".to_string()),
+        });
+        let parse = DateTruncParse {
+            param: &param,
+            timezone: Some("+08:00".parse().unwrap()),
+        };
+        assert_eq!(
+            parse.parse().unwrap().unwrap(),
+            "date_trunc('month', created_at AT TIME ZONE INTERVAL '+08:00') as month"
+        );
+        let parse = DateTruncParse {
+            param: &param,
+            timezone: None,
+        };
+        assert_eq!(
+            parse.group().unwrap().unwrap(),
+            "date_trunc('month', created_at)"
+        );
+
+        let param = Some(DateTruncParam {
+            field: "created_at".to_string(),
+            unit: "decade".to_string(),
+            alias: None,
+        });
+        let parse = DateTruncParse {
+            param: &param,
+            timezone: None,
+        };
+        assert_eq!(parse.parse().unwrap_err().path(), Some(".unit"));
+    }
+}
@@ -1,6 +1,9 @@
+use std::collections::HashMap;
+
 use crate::error::CtsError;
 use crate::error::CtsError::FieldError;
 use crate::expression::{CtsValue, Single, SqlParse};
+use crate::policy::FieldPolicies;
 
 /// # 字段解析器
 /// > 字段解析器，主要是解析查询字段参数，字段参数只支持字符串一维数组或者二维数组
@@ -31,6 +34,58 @@ impl SqlParse for FieldParse<'_> {
         }
     }
 }
+impl FieldParse<'_> {
+    /// 按字段权限解析查询字段，隐藏字段返回错误，脱敏字段替换成脱敏表达式
+    /// @param policies 字段权限
+    pub fn parse_with_policies(
+        &self,
+        policies: &FieldPolicies,
+    ) -> Result<Option<String>, CtsError> {
+        if policies.is_empty() {
+            return self.parse();
+        }
+        match self.0 {
+            None => Ok(None),
+            Some(data) => {
+                let mut result = Vec::new();
+                for datum in data.iter() {
+                    let field = match datum {
+                        CtsValue::Array(data_array) if data_array.len() > 1 => {
+                            let field = handler_array(&data_array[0])?;
+                            let alias = handler_array(&data_array[1])?;
+                            policies.select(&field, Some(&alias))?
+                        }
+                        _ => policies.select(&handler_cts_value(datum)?, None)?,
+                    };
+                    result.push(field);
+                }
+                Ok(Some(result.join(",")))
+            }
+        }
+    }
+
+    /// 输出列名与表字段的对应关系，key为输出列名(别名)，value为表字段名称
+    pub fn sources(&self) -> Result<HashMap<String, String>, CtsError> {
+        let mut result = HashMap::new();
+        if let Some(data) = self.0 {
+            for datum in data.iter() {
+                match datum {
+                    CtsValue::Array(data_array) if data_array.len() > 1 => {
+                        let field = handler_array(&data_array[0])?;
+                        let alias = handler_array(&data_array[1])?;
+                        result.insert(alias, field);
+                    }
+                    _ => {
+                        let field = handler_cts_value(datum)?;
+                        result.insert(field.clone(), field);
+                    }
+                }
+            }
+        }
+        Ok(result)
+    }
+}
+
 // 处理字段数组解析
 pub fn handler_array(value: &CtsValue) -> Result<String, CtsError> {
     // 判断类型，如果是数组提示错误，是字符串直接收集
@@ -1,23 +1,19 @@
-pub mod compare;
-pub mod inclusion;
-pub mod logic;
-pub mod null;
+pub mod spatial;
+pub mod temporal;
 
+use crate::ast::Predicate;
 use crate::error::CtsError;
 use crate::error::CtsError::FilterError;
-use crate::expression::parse::filter::compare::CompareParse;
-use crate::expression::parse::filter::inclusion::{BetweenParse, InParse, LikeParse};
-use crate::expression::parse::filter::logic::{NotParse, OrAndParse};
-use crate::expression::parse::filter::null::NullParse;
-use crate::expression::parse::handler_name;
 use crate::expression::{CtsValue, SqlParse};
-/// 过滤条件解析
+/// 过滤条件解析，先解析成语法树再渲染成sql，与 `Predicate::from_filter` 使用同一套语法
 /// ```sql
 /// [express,field,value]
 /// ["or",["=",field,value],["=",field2,value]]
 /// ["like",field,"%aaaa"]
 /// ["in",field,[value1,value2]]
 /// ["between",field,value1,valu2]
+/// ["intersects",field,"POINT(120 30)",4326]
+/// ["during",field,"2024-01-01","2024-02-01"]
 /// ```
 pub struct FilterParse<'a>(pub &'a Option<Vec<CtsValue>>);
 
@@ -35,25 +31,21 @@ impl SqlParse for FilterParse<'_> {
     }
 }
 
-pub fn filter_parse(data: &Vec<CtsValue>) -> Result<Option<String>, CtsError> {
-    // 判断数组长度是否小于3
-    if data.len() < 2 {
-        return Err(FilterError("过滤参数长度不够，至少2位。".to_string()));
+pub fn filter_parse(data: &[CtsValue]) -> Result<Option<String>, CtsError> {
+    let predicate = Predicate::from_filter(data)?;
+    Ok(Some(predicate.to_sql()))
+}
+
+/// 检查过滤条件的参数个数，包含操作符
+/// @param len 参数个数
+pub(crate) fn check_len(data: &[CtsValue], len: usize) -> Result<(), CtsError> {
+    if data.len() < len {
+        return Err(FilterError("参数错误，参数长度不够".to_string()));
+    }
+    if data.len() > len {
+        return Err(FilterError("参数错误，参数过多".to_string()).with_path(&format!("[{len}]")));
     }
-    let first = &data[0];
-    let ope = handler_name(first)?;
-    //
-    let expression = match ope.to_lowercase().as_str() {
-        ">" | "<" | ">=" | "<=" | "=" | "!=" => CompareParse(data).parse()?,
-        "or" | "and" | "" => OrAndParse(data).parse()?,
-        "not" => NotParse(data).parse()?,
-        "in" | "not in" => InParse(data).parse()?,
-        "between" | "not between" => BetweenParse(data).parse()?,
-        "like" => LikeParse(data).parse()?,
-        "is null" | "is not null" => NullParse(data).parse()?,
-        _ => return Err(FilterError("过滤参数错误".to_string())),
-    };
-    Ok(expression)
+    Ok(())
 }
 
 #[cfg(test)]
@@ -84,4 +76,77 @@ mod tests {
         let aa = filter_parse(&data).unwrap();
         println!("{}", aa.unwrap())
     }
+
+    fn parse(filter: &str) -> Result<String, crate::error::CtsError> {
+        let filter: Vec<CtsValue> = serde_json::from_str(filter).unwrap();
+        filter_parse(&filter).map(|item| item.unwrap())
+    }
+
+    #[test]
+    fn compare() {
+        assert_eq!(parse(r#"[">", "aaa", "bbb"]"#).unwrap(), "aaa > 'bbb'");
+        assert_eq!(parse(r#"["=", "name", "o'k"]"#).unwrap(), "name = 'o''k'");
+        assert_eq!(parse(r#"["is null", "aa"]"#).unwrap(), "aa is null");
+    }
+
+    #[test]
+    fn inclusion() {
+        assert_eq!(
+            parse(r#"["not between", "age", 10, 100]"#).unwrap(),
+            "age not between 10 and 100"
+        );
+        // 缺少字段
+        assert!(parse(r#"["between", 10, 100]"#).is_err());
+        assert_eq!(
+            parse(r#"["in", "field", ["in", "in", 123, "in"]]"#).unwrap(),
+            "field in ('in','in',123,'in')"
+        );
+        assert_eq!(parse(r#"["in", "field", []]"#).unwrap(), "1 != 1");
+        assert_eq!(parse(r#"["like", "name", "%asdf"]"#).unwrap(), "name like '%asdf'");
+    }
+
+    #[test]
+    fn logic() {
+        assert_eq!(
+            parse(r#"["or", ["=", "field1", "aa"], ["=", "field2", "bb"], [">", "field2", "bb"]]"#)
+                .unwrap(),
+            "(field1 = 'aa') or (field2 = 'bb') or (field2 > 'bb')"
+        );
+        assert_eq!(
+            parse(r#"["not", ["=", "field1", "aa"], ["=", "field2", "bb"]]"#).unwrap(),
+            "not ((field1 = 'aa') and (field2 = 'bb'))"
+        );
+        assert_eq!(parse(r#"["and", "b"]"#).unwrap_err().path(), Some("[1]"));
+    }
+
+    #[test]
+    fn intersects() {
+        assert_eq!(
+            parse(r#"["intersects", "geom", "POINT(120 30)"]"#).unwrap(),
+            "st_intersects(geom, st_geomfromtext('POINT(120 30)', 4326))"
+        );
+        assert!(parse(r#"["intersects", "geom", "POINT(1 2)') or (1=1"]"#).is_err());
+        assert_eq!(
+            parse(r#"["intersects", "geom", "POINT(1 2)", 0]"#).unwrap_err().path(),
+            Some("[3]")
+        );
+    }
+
+    #[test]
+    fn temporal() {
+        assert_eq!(
+            parse(r#"["before", "created_at", "2024-01-01T08:00:00+08:00"]"#).unwrap(),
+            "created_at < '2024-01-01T08:00:00+08:00'::timestamptz"
+        );
+        assert_eq!(
+            parse(r#"["during", "day", "2024-01-01", "2024-02-01 12:00:00"]"#).unwrap(),
+            "(day >= '2024-01-01'::date and day < '2024-02-01T12:00:00'::timestamp)"
+        );
+        assert_eq!(
+            parse(r#"["last", "created_at", 7, "days"]"#).unwrap(),
+            "created_at >= now() - interval '7 day'"
+        );
+        let err = parse(r#"["after", "created_at", "2024-01-01' or '1'='1"]"#).unwrap_err();
+        assert_eq!(err.path(), Some("[2]"));
+    }
 }
@@ -0,0 +1,21 @@
+use crate::error::CtsError;
+use crate::error::CtsError::FilterError;
+use crate::expression::{CtsValue, Single};
+
+/// 没有指定坐标系时使用的坐标系
+pub static DEFAULT_SRID: i64 = 4326;
+
+/// WKT 只包含字母、数字、空格、括号、逗号、小数点以及负号
+pub(crate) fn handler_wkt(data: &CtsValue) -> Result<String, CtsError> {
+    match data {
+        CtsValue::Single(Single::String(wkt))
+            if !wkt.trim().is_empty()
+                && wkt
+                    .chars()
+                    .all(|item| item.is_ascii_alphanumeric() || " (),.-+".contains(item)) =>
+        {
+            Ok(wkt.trim().to_string())
+        }
+        _ => Err(FilterError("空间对象参数错误，必须为WKT格式".to_string())),
+    }
+}
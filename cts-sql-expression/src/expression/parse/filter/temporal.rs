@@ -0,0 +1,83 @@
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime};
+
+use crate::error::CtsError;
+use crate::error::CtsError::FilterError;
+use crate::expression::parse::handler_name;
+use crate::expression::{CtsValue, Single};
+
+/// 相对时间单位
+pub static UNITS: [&str; 7] = ["second", "minute", "hour", "day", "week", "month", "year"];
+
+/// ISO 8601 时间值
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IsoDateTime {
+    Date(NaiveDate),
+    /// 没有时区的时间
+    Local(NaiveDateTime),
+    /// 带时区的时间
+    Zoned(DateTime<FixedOffset>),
+}
+
+impl IsoDateTime {
+    /// 解析 ISO 8601 日期或者时间，时间和日期之间可以使用 `T` 或者空格
+    pub fn parse(data: &str) -> Option<Self> {
+        let data = data.trim();
+        if let Ok(date) = NaiveDate::parse_from_str(data, "%Y-%m-%d") {
+            return Some(IsoDateTime::Date(date));
+        }
+        if let Ok(time) = DateTime::parse_from_rfc3339(data) {
+            return Some(IsoDateTime::Zoned(time));
+        }
+        if let Ok(time) = DateTime::parse_from_str(data, "%Y-%m-%d %H:%M:%S%.f%:z") {
+            return Some(IsoDateTime::Zoned(time));
+        }
+        [
+            "%Y-%m-%dT%H:%M:%S%.f",
+            "%Y-%m-%d %H:%M:%S%.f",
+            "%Y-%m-%dT%H:%M",
+        ]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(data, format).ok())
+        .map(IsoDateTime::Local)
+    }
+
+    /// 输出成 sql 常量
+    pub fn to_sql(&self) -> String {
+        match self {
+            IsoDateTime::Date(date) => format!("'{}'::date", date.format("%Y-%m-%d")),
+            IsoDateTime::Local(time) => {
+                format!("'{}'::timestamp", time.format("%Y-%m-%dT%H:%M:%S%.f"))
+            }
+            IsoDateTime::Zoned(time) => format!("'{}'::timestamptz", time.to_rfc3339()),
+        }
+    }
+}
+
+fn handler_datetime(data: &CtsValue) -> Result<String, CtsError> {
+    match data {
+        CtsValue::Single(Single::String(value)) => match IsoDateTime::parse(value) {
+            Some(time) => Ok(time.to_sql()),
+            None => Err(FilterError(format!(
+                "时间格式错误{value}，必须为ISO 8601格式"
+            ))),
+        },
+        _ => Err(FilterError("时间参数必须为字符串".to_string())),
+    }
+}
+
+/// 检查 ISO 8601 时间值，返回原始字符串
+pub(crate) fn handler_time(data: &CtsValue) -> Result<String, CtsError> {
+    handler_datetime(data)?;
+    handler_name(data).map(|value| value.trim().to_string())
+}
+
+/// 时间单位，支持复数形式
+pub(crate) fn handler_unit(data: &CtsValue) -> Result<&'static str, CtsError> {
+    let unit = handler_name(data)?.to_lowercase();
+    let unit = unit.strip_suffix('s').unwrap_or(&unit);
+    UNITS
+        .iter()
+        .find(|item| **item == unit)
+        .copied()
+        .ok_or(FilterError(format!("不支持的时间单位{unit}")))
+}
@@ -0,0 +1,76 @@
+use crate::config::{JoinConfig, JoinKind};
+use crate::error::CtsError;
+use crate::expression::SqlParse;
+
+/// # 关联表解析
+/// > 关联表以相关子查询的方式放在查询字段中，不影响主表字段、过滤以及分页
+/// ```sql
+/// -- one
+/// (select row_to_json(j) from (select name from public.road_type where code = public.road.type_code limit 1) j) as road_type
+/// -- many
+/// (select coalesce(json_agg(j), '[]'::json) from (select name, url from public.road_file where road_id = public.road.id) j) as attachments
+/// ```
+pub struct JoinParse<'a> {
+    pub join: &'a JoinConfig,
+    /// 关联表返回字段，已经校验过
+    pub fields: &'a [String],
+    /// 关联表默认schema
+    pub schema: &'a str,
+    /// 主表引用名称，普通表为 schema.table，虚拟图层为图层名称
+    pub source: &'a str,
+}
+
+impl SqlParse for JoinParse<'_> {
+    fn parse(&self) -> Result<Option<String>, CtsError> {
+        let join = self.join;
+        let join_schema = join.schema.as_deref().unwrap_or(self.schema);
+        let sub_query = format!(
+            "select {} from {join_schema}.{} where {} = {}.{}",
+            self.fields.join(", "),
+            join.table,
+            join.foreign_key,
+            self.source,
+            join.local_key
+        );
+        let expression = match join.kind {
+            JoinKind::One => {
+                format!("(select row_to_json(j) from ({sub_query} limit 1) j)")
+            }
+            JoinKind::Many => {
+                format!("(select coalesce(json_agg(j), '[]'::json) from ({sub_query}) j)")
+            }
+        };
+        Ok(Some(format!("{expression} as {}", join.name)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_join() {
+        let join = JoinConfig {
+            name: "attachments".to_string(),
+            source: "road".to_string(),
+            table: "road_file".to_string(),
+            schema: None,
+            local_key: "id".to_string(),
+            foreign_key: "road_id".to_string(),
+            kind: JoinKind::Many,
+            fields: None,
+        };
+        let fields = vec!["name".to_string(), "url".to_string()];
+        let parse = JoinParse {
+            join: &join,
+            fields: &fields,
+            schema: "public",
+            source: "public.road",
+        };
+        assert_eq!(
+            parse.parse().unwrap().unwrap(),
+            "(select coalesce(json_agg(j), '[]'::json) from (select name, url from public.road_file \
+             where road_id = public.road.id) j) as attachments"
+        );
+    }
+}
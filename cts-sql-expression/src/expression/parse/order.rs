@@ -11,7 +11,7 @@ use crate::expression::{CtsValue, Single, SqlParse};
 /// ```
 pub struct OrderByParse<'a>(pub &'a Option<Vec<CtsValue>>);
 
-static ORDERS: [&str; 2] = ["asc", "desc"];
+pub(crate) static ORDERS: [&str; 2] = ["asc", "desc"];
 
 impl SqlParse for OrderByParse<'_> {
     fn parse(&self) -> Result<Option<String>, CtsError> {
@@ -20,27 +20,10 @@ impl SqlParse for OrderByParse<'_> {
             None => Ok(None),
             Some(data) => {
                 let mut result = Vec::new();
-                for datum in data.iter() {
-                    match datum {
-                        CtsValue::Single(Single::String(order)) => {
-                            result.push(order.to_string());
-                        }
-                        CtsValue::Array(arr) => {
-                            if arr.len() < 2 {
-                                return Err(OrderError("排序参数错误".to_string()));
-                            }
-                            let name = handler_name(&arr[0])?;
-                            let value = handler_name(&arr[1])?;
-                            // 判断value 是否是asc或者desc
-                            if !ORDERS.contains(&&*value.to_lowercase()) {
-                                return Err(OrderError("排序参数错误".to_string()));
-                            }
-                            result.push(format!("{name} {value}"))
-                        }
-                        _ => {
-                            return Err(OrderError("排序参数类型错误".to_string()));
-                        }
-                    }
+                for (index, datum) in data.iter().enumerate() {
+                    let order =
+                        handler_order(datum).map_err(|err| err.with_path(&format!("[{index}]")))?;
+                    result.push(order);
                 }
                 Ok(Some(result.join(",")))
             }
@@ -48,6 +31,26 @@ impl SqlParse for OrderByParse<'_> {
     }
 }
 
+/// 解析单个排序字段 field 或者 [field, asc|desc]
+pub(crate) fn handler_order(datum: &CtsValue) -> Result<String, CtsError> {
+    match datum {
+        CtsValue::Single(Single::String(order)) => Ok(order.to_string()),
+        CtsValue::Array(arr) => {
+            if arr.len() != 2 {
+                return Err(OrderError("排序参数错误".to_string()));
+            }
+            let name = handler_name(&arr[0]).map_err(|err| err.with_path("[0]"))?;
+            let value = handler_name(&arr[1]).map_err(|err| err.with_path("[1]"))?;
+            // 判断value 是否是asc或者desc
+            if !ORDERS.contains(&&*value.to_lowercase()) {
+                return Err(OrderError("排序参数错误".to_string()).with_path("[1]"));
+            }
+            Ok(format!("{name} {value}"))
+        }
+        _ => Err(OrderError("排序参数类型错误".to_string())),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::expression::parse::order::OrderByParse;
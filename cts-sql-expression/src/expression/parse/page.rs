@@ -1,4 +1,5 @@
 use crate::error::CtsError;
+use crate::error::CtsError::ParamError;
 use crate::expression::{ SqlParse};
 use crate::request::PageParam;
 
@@ -15,7 +16,14 @@ impl SqlParse for PageParse<'_> {
             Some(data) => {
                 let page = &data.page;
                 let page_size = &data.page_size;
-                let offset = (page - 1) * page_size;
+                // 页码以及分页大小必须大于0，否则 OFFSET 无效
+                if *page < 1 || *page_size < 1 {
+                    return Err(ParamError("参数错误，page以及pageSize必须大于0".to_string()));
+                }
+                if data.offset.is_some_and(|offset| offset < 0) {
+                    return Err(ParamError("参数错误，offset不能小于0".to_string()));
+                }
+                let offset = data.skip();
                 Ok(Some(format!(" LIMIT {page_size} OFFSET {offset}")))
             }
         }
@@ -31,9 +39,25 @@ mod tests {
         let groups = Some(PageParam {
             page: 1,
             page_size: 10,
+            offset: None,
         });
         let group_parse = PageParse(&groups);
         let aa = group_parse.parse().unwrap();
         print!("{}", aa.unwrap());
     }
+    #[test]
+    fn parse_offset() {
+        let page = Some(PageParam {
+            page: 1,
+            page_size: 20,
+            offset: Some(5),
+        });
+        assert_eq!(PageParse(&page).parse().unwrap().unwrap(), " LIMIT 20 OFFSET 5");
+        let page = Some(PageParam {
+            page: 1,
+            page_size: 20,
+            offset: Some(-1),
+        });
+        assert!(PageParse(&page).parse().is_err());
+    }
 }
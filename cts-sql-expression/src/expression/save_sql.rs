@@ -1,23 +1,31 @@
 use std::collections::HashMap;
 
-use chrono::Local;
 use serde_json::Value;
 use sqlx::{Pool, Postgres};
 use uuid::Uuid;
 
 use super::{CREATED_AT, ID, UPDATED_AT};
+use crate::audit::{AuditOperation, Auditor};
+use crate::config::ExpressionConfig;
+use crate::error::CtsError;
+use crate::tenant::set_search_path;
+use crate::timezone::TimeZone;
 
 /// save sql构造器
+/// > 配置中的字段权限总是生效，隐藏字段以及只读字段不能写入
+///
 /// @param 请求参数
 /// @param pool 数据库连接池
 /// @table 查询表名
-/// @schema 对应数据库的schema
+/// @config 查询配置，提供schema、字段权限以及时区
 pub struct SaveSqlBuilder<'a> {
     data: HashMap<String, Value>,
     pool: &'a Pool<Postgres>,
     table: String,
     schema: String,
     id: String,
+    search_path: bool,
+    auditor: Option<Auditor>,
 }
 
 impl<'a> SaveSqlBuilder<'a> {
@@ -25,23 +33,55 @@ impl<'a> SaveSqlBuilder<'a> {
         mut data: HashMap<String, Value>,
         pool: &'a Pool<Postgres>,
         table: String,
-        schema: String,
-    ) -> Self {
+        config: &ExpressionConfig,
+    ) -> Result<Self, CtsError> {
+        // 检查字段权限，id以及日期字段由构造器生成，不做检查
+        config.field_policies(&table).check_write(
+            data.keys()
+                .filter(|key| ![ID, CREATED_AT, UPDATED_AT].contains(&key.as_str())),
+        )?;
         // 创建
         let uuid_str = Uuid::new_v4().to_string();
         // 插入id字段，如果存在，替换成uuid字符串
         data.insert(ID.to_string(), Value::String(uuid_str.to_string()));
         // 插入日期字段
-        let date = Local::now().to_string();
+        let date = config.timezone.unwrap_or_else(TimeZone::local).now();
         data.insert(CREATED_AT.to_string(), Value::String(date.to_string()));
         data.insert(UPDATED_AT.to_string(), Value::String(date));
-        Self {
+        Ok(Self {
             data,
             pool,
             table,
-            schema,
+            schema: config.schema(),
             id: uuid_str,
-        }
+            search_path: config.search_path,
+            auditor: config.auditor(None),
+        })
+    }
+
+    /// 按时区重新生成创建时间以及修改时间
+    /// @param timezone 时区
+    pub fn with_timezone(mut self, timezone: TimeZone) -> Self {
+        let date = timezone.now();
+        self.data
+            .insert(CREATED_AT.to_string(), Value::String(date.to_string()));
+        self.data
+            .insert(UPDATED_AT.to_string(), Value::String(date));
+        self
+    }
+
+    /// 记录审计，为空时不记录，默认使用配置中的审计
+    /// @param auditor 审计写入，通过 `ExpressionConfig::auditor` 获取
+    pub fn with_auditor(mut self, auditor: Option<Auditor>) -> Self {
+        self.auditor = auditor;
+        self
+    }
+
+    /// 审计记录的操作人，没有开启审计时忽略
+    /// @param actor 操作人
+    pub fn with_actor(mut self, actor: String) -> Self {
+        self.auditor = self.auditor.map(|auditor| auditor.with_actor(Some(actor)));
+        self
     }
 
     pub fn build(&self) -> String {
@@ -69,8 +109,28 @@ impl<'a> SaveSqlBuilder<'a> {
     }
 
     pub async fn execute(&self) -> Result<String, sqlx::Error> {
-        let sql = self.build();
-        let _ = sqlx::query(&sql).execute(self.pool).await?;
+        let sql = match &self.auditor {
+            None => self.build(),
+            Some(auditor) => auditor.wrap(
+                &self.build(),
+                AuditOperation::Insert,
+                &self.schema,
+                &self.table,
+                None,
+            ),
+        };
+        // 需要设置 search_path 时在事务中写入，事务结束后设置失效
+        match self.search_path {
+            true => {
+                let mut tx = self.pool.begin().await?;
+                set_search_path(&mut tx, &self.schema).await?;
+                sqlx::query(&sql).execute(&mut *tx).await?;
+                tx.commit().await?;
+            }
+            false => {
+                sqlx::query(&sql).execute(self.pool).await?;
+            }
+        }
         Ok(self.id.to_string())
     }
 }
@@ -1,18 +1,46 @@
-use crate::config::{ExpressionConfig, QueryMode};
+use crate::ast::Predicate;
+use crate::config::{ExpressionConfig, JoinConfig, QueryMode};
 use crate::error::CtsError;
-use crate::error::CtsError::ParamError;
+use crate::error::CtsError::{ConvertError, ParamError};
 use crate::expression::parse::aggregate::AggregateParse;
+use crate::expression::parse::bbox::BboxParse;
+use crate::expression::parse::date_trunc::DateTruncParse;
 use crate::expression::parse::field::FieldParse;
 use crate::expression::parse::filter::FilterParse;
 use crate::expression::parse::group::GroupByParse;
+use crate::expression::parse::join::JoinParse;
 use crate::expression::parse::order::OrderByParse;
 use crate::expression::parse::page::PageParse;
 use crate::expression::query_builder::QueryBuilder;
-use crate::expression::{Course, SqlParse, GEOMETRY};
+use crate::expression::{SqlParse, GEOMETRY};
+use crate::layer::VirtualLayer;
+use crate::limits::{set_statement_timeout, QueryLimits};
+use crate::metadata::cache::MetadataCache;
+use crate::metadata::field::{build_fields, FieldInfo};
+use crate::metadata::{geometry_expr, ColumnInfo};
+use crate::policy::{FieldPolicies, FieldPolicy};
 use crate::request::{CtsFormat, CtsParam, GeometryFormat};
 use crate::response::{CtsResult, PageValue};
+use crate::tenant::set_search_path;
+use crate::timezone::TimeZone;
+use crate::validate::ParamValidator;
+use cts_pgrow::{from_row_with, SerializeOptions};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use serde_json::Value;
-use sqlx::{Pool, Postgres, Row};
+use sqlx::postgres::PgRow;
+use sqlx::{Column, Describe, Executor, Pool, Postgres, Row, Transaction, TypeInfo};
+use std::sync::Arc;
+
+/// 生成的查询语句，请求中的值以常量的形式写入sql，没有绑定参数，可以直接执行
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeneratedSql {
+    /// 查询语句
+    pub select: String,
+    /// 分页统计语句，没有分页或者有统计参数时为空
+    pub count: Option<String>,
+}
 
 /// sql构造器
 /// @param 请求参数
@@ -26,6 +54,17 @@ pub struct SqlBuilder<'a> {
     table: String,
     schema: String,
     query_mode: QueryMode,
+    serialize_options: Arc<SerializeOptions>,
+    metadata_cache: Arc<MetadataCache>,
+    joins: Vec<JoinConfig>,
+    layer: Option<VirtualLayer>,
+    predicates: Vec<Predicate>,
+    field_policies: FieldPolicies,
+    /// 全部字段权限，关联表按表名称筛选
+    policies: Vec<FieldPolicy>,
+    search_path: bool,
+    limits: QueryLimits,
+    timezone: Option<TimeZone>,
 }
 
 impl<'a> SqlBuilder<'a> {
@@ -36,19 +75,37 @@ impl<'a> SqlBuilder<'a> {
         param: CtsParam,
     ) -> Self {
         let new_schema = config.schema();
-        let param = match config.query_mode {
+        // 虚拟图层
+        let layer = config.find_layer(&table).cloned();
+        // 字段权限
+        let field_policies = config.field_policies(&table);
+        let mut param = match config.query_mode {
             QueryMode::Normal => {
                 // 设置查询参数，去掉空间查询相关参数
                 param.search_param()
             }
             QueryMode::Spatial => param,
         };
+        // 没有设置分页大小时使用默认分页大小
+        if let Some(page) = param.page.as_mut() {
+            config.limits.normalize_page(page);
+        }
         Self {
             param,
             table,
             pool,
+            serialize_options: config.serialize_options(),
+            metadata_cache: config.metadata_cache(),
             query_mode: config.query_mode,
             schema: new_schema,
+            layer,
+            joins: config.joins,
+            predicates: config.predicates,
+            field_policies,
+            policies: config.field_policies,
+            search_path: config.search_path,
+            limits: config.limits,
+            timezone: config.timezone,
         }
     }
 
@@ -68,42 +125,94 @@ impl<'a> SqlBuilder<'a> {
         }
         // 获取数据库设计模式，默认public
         let new_schema = config.schema();
+        // 虚拟图层
+        let layer = config.find_layer(&table).cloned();
+        // 字段权限
+        let field_policies = config.field_policies(&table);
         // 创建builder对象
         Self {
             param,
             table,
             pool,
+            serialize_options: config.serialize_options(),
+            metadata_cache: config.metadata_cache(),
             query_mode: config.query_mode,
             schema: new_schema,
+            layer,
+            joins: config.joins,
+            predicates: config.predicates,
+            field_policies,
+            policies: config.field_policies,
+            search_path: config.search_path,
+            limits: config.limits,
+            timezone: config.timezone,
         }
     }
 
+    /// 添加强制过滤条件，与配置中的强制过滤条件一起生效
+    pub fn with_predicate(mut self, predicate: Predicate) -> Self {
+        self.predicates.push(predicate);
+        self
+    }
+
     // 解析查询sql函数
     async fn parse(&self) -> Result<String, CtsError> {
         let param = &self.param;
+        // 参数校验，一次返回全部问题
+        ParamValidator(param).validate()?;
+        // 字段权限检查
+        self.field_policies.check_param(param)?;
         // filter 解析
-        let filter = FilterParse(&param.filter).parse()?;
+        let filter = self.parse_filter().await?;
         // group 解析
-        let group = GroupByParse(&param.group_by).parse()?;
+        let group = GroupByParse(&param.group_by)
+            .parse()
+            .map_err(|err| err.with_path("groupBy"))?;
         // field 解析
-        let field = FieldParse(&param.out_fields).parse()?;
+        let field = FieldParse(&param.out_fields)
+            .parse_with_policies(&self.field_policies)
+            .map_err(|err| err.with_path("outFields"))?;
         // aggregate 解析
-        let aggregate = AggregateParse(&param.aggregate).parse()?;
+        let aggregate = AggregateParse(&param.aggregate)
+            .parse()
+            .map_err(|err| err.with_path("aggregate"))?;
+        // 时间截断分组解析
+        let date_trunc = DateTruncParse {
+            param: &param.date_trunc,
+            timezone: self.trunc_timezone().await?,
+        };
+        let trunc_field = date_trunc
+            .parse()
+            .map_err(|err| err.with_path("dateTrunc"))?;
+        let trunc_group = date_trunc
+            .group()
+            .map_err(|err| err.with_path("dateTrunc"))?;
+        let group = match (group, trunc_group) {
+            (Some(group), Some(trunc)) => Some(format!("{group},{trunc}")),
+            (group, trunc) => group.or(trunc),
+        };
         // order by 解析
-        let order = OrderByParse(&param.order_by).parse()?;
+        let order = OrderByParse(&param.order_by)
+            .parse()
+            .map_err(|err| err.with_path("orderBy"))?;
         // page 分页解析
-        let page = PageParse(&param.page).parse()?;
+        if let Some(page) = &param.page {
+            self.limits
+                .check_page(page)
+                .map_err(|err| err.with_path("page"))?;
+        }
+        let page = PageParse(&param.page)
+            .parse()
+            .map_err(|err| err.with_path("page"))?;
         // sql构造对象
         let mut builder = QueryBuilder::new_select();
-        // table表名
-        let table = &self.table;
-        // schema 模式
-        let schema = &self.schema;
         // 判断是否有统计参数
         let fields = match &aggregate {
             None => {
                 // 匹配是否有字段
                 match &field {
+                    // 只有时间截断分组时查询截断后的时间
+                    None if trunc_field.is_some() => String::new(),
                     // 没有字段时需要查询表字段
                     None => self.get_table_columns().await?,
                     Some(fields) => {
@@ -115,13 +224,7 @@ impl<'a> SqlBuilder<'a> {
                                 match param.return_geometry {
                                     Some(data) if data => {
                                         // 获取空间字段
-                                        let geometry = self.get_table_geometry().await;
-                                        // 判断是否有空间字段
-                                        let geometry_field = &geometry.ok_or(ParamError(
-                                            "参数错误，该数据不包含空间字段".to_string(),
-                                        ))?;
-                                        let geometry_field =
-                                            self.handler_geometry_format(geometry_field);
+                                        let geometry_field = self.get_geometry_fields().await?;
                                         format!("{fields},{geometry_field}")
                                     }
                                     _ => fields.to_string(),
@@ -141,11 +244,20 @@ impl<'a> SqlBuilder<'a> {
                 }
             }
         };
+        // 时间截断字段放在最前面
+        let fields = match (trunc_field, fields.is_empty()) {
+            (Some(trunc), true) => trunc,
+            (Some(trunc), false) => format!("{trunc},{fields}"),
+            (None, _) => fields,
+        };
         builder.push(fields);
+        // 关联表字段
+        if let Some(join) = self.parse_join().await? {
+            builder.push(",");
+            builder.push(join);
+        }
         builder.push(" from ");
-        builder.push(schema);
-        builder.push(".");
-        builder.push(table);
+        builder.push(self.parse_source()?);
 
         // 判断是否有过滤条件
         if let Some(data) = filter {
@@ -164,90 +276,345 @@ impl<'a> SqlBuilder<'a> {
             builder.push(data);
         }
 
-        // 分页
-        if let Some(data) = page {
-            builder.push(data);
+        // 分页，不分页时限制最大返回行数
+        match page {
+            Some(data) => {
+                builder.push(data);
+            }
+            None => {
+                if let Some(limit) = self.limits.unpaged_limit() {
+                    builder.push(limit);
+                }
+            }
         }
 
         Ok(builder.build())
     }
 
+    // 需要设置 search_path 或者 statement_timeout 时开启事务，事务结束后设置失效
+    async fn begin(&self) -> Result<Option<Transaction<'a, Postgres>>, sqlx::Error> {
+        if !self.search_path && self.limits.statement_timeout.is_none() {
+            return Ok(None);
+        }
+        let mut tx = self.pool.begin().await?;
+        if self.search_path {
+            set_search_path(&mut tx, &self.schema).await?;
+        }
+        if let Some(timeout) = self.limits.statement_timeout {
+            set_statement_timeout(&mut tx, timeout).await?;
+        }
+        Ok(Some(tx))
+    }
+
+    // 查询数据
+    async fn fetch_all(&self, query: &str) -> Result<Vec<PgRow>, sqlx::Error> {
+        let Some(mut tx) = self.begin().await? else {
+            return sqlx::query(query).fetch_all(self.pool).await;
+        };
+        let list = sqlx::query(query).fetch_all(&mut *tx).await?;
+        tx.commit().await?;
+        Ok(list)
+    }
+
+    // 查询单条数据
+    async fn fetch_one(&self, query: &str) -> Result<PgRow, sqlx::Error> {
+        let Some(mut tx) = self.begin().await? else {
+            return sqlx::query(query).fetch_one(self.pool).await;
+        };
+        let row = sqlx::query(query).fetch_one(&mut *tx).await?;
+        tx.commit().await?;
+        Ok(row)
+    }
+
+    // 获取预编译语句结果列
+    async fn describe(&self, query: &str) -> Result<Describe<Postgres>, sqlx::Error> {
+        let Some(mut tx) = self.begin().await? else {
+            return self.pool.describe(query).await;
+        };
+        let describe = (&mut *tx).describe(query).await?;
+        tx.commit().await?;
+        Ok(describe)
+    }
+
+    // 查询数据来源，虚拟图层作为子查询使用
+    fn parse_source(&self) -> Result<String, CtsError> {
+        match &self.layer {
+            None => Ok(format!("{}.{}", self.schema, self.table)),
+            Some(layer) => {
+                let sql = layer.render(self.param.params.as_ref())?;
+                Ok(format!("({sql}) as {}", layer.name))
+            }
+        }
+    }
+
+    // 主表引用名称
+    fn source_name(&self) -> String {
+        match &self.layer {
+            None => format!("{}.{}", self.schema, self.table),
+            Some(layer) => layer.name.clone(),
+        }
+    }
+
+    // 时间截断的时区只作用于 timestamptz 字段，timestamp 字段保存的已经是本地时间，
+    // 关联表字段无法确定类型时按 timestamptz 处理
+    async fn trunc_timezone(&self) -> Result<Option<TimeZone>, CtsError> {
+        let (Some(timezone), Some(param)) = (self.timezone, &self.param.date_trunc) else {
+            return Ok(None);
+        };
+        let name = match param.field.split_once('.') {
+            None => param.field.as_str(),
+            Some((table, name)) if table == self.table => name,
+            Some(_) => return Ok(Some(timezone)),
+        };
+        let columns = self.table_columns().await?;
+        match columns.iter().find(|item| item.name == name) {
+            Some(column) if column.udt_name != "timestamptz" => Ok(None),
+            _ => Ok(Some(timezone)),
+        }
+    }
+
+    // 查询表字段信息，优先读取缓存
+    async fn table_columns(&self) -> Result<Arc<Vec<ColumnInfo>>, CtsError> {
+        match &self.layer {
+            None => {
+                self.metadata_cache
+                    .columns(self.pool, &self.schema, &self.table)
+                    .await
+            }
+            Some(layer) => self.layer_columns(layer).await,
+        }
+    }
+
+    // 查询虚拟图层字段，通过预编译语句获取结果列
+    async fn layer_columns(&self, layer: &VirtualLayer) -> Result<Arc<Vec<ColumnInfo>>, CtsError> {
+        if let Some(columns) = self.metadata_cache.get(&self.schema, &layer.name) {
+            return Ok(columns);
+        }
+        let query = format!("select * from {}", self.parse_source()?);
+        let describe = self
+            .describe(&query)
+            .await
+            .map_err(|err| self.limits.query_error(err))?;
+        let columns = describe
+            .columns()
+            .iter()
+            .enumerate()
+            .map(|(index, column)| {
+                let udt_name = column.type_info().name().to_lowercase();
+                let mut info = ColumnInfo::new(column.name(), &udt_name);
+                info.nullable = describe.nullable(index).unwrap_or(true);
+                info
+            })
+            .collect();
+        Ok(self
+            .metadata_cache
+            .insert(&self.schema, &layer.name, columns))
+    }
+
     // 查询表字段方法
     async fn get_table_columns(&self) -> Result<String, CtsError> {
         let param = &self.param;
-        let table = &self.table;
-        let schema = &self.schema;
         // 判断查询方式是那种
+        let policies = &self.field_policies;
         match &self.query_mode {
-            QueryMode::Normal => Ok("*".to_string()),
+            QueryMode::Normal if policies.is_empty() => Ok("*".to_string()),
+            QueryMode::Normal => {
+                // 有字段权限时展开字段，去掉隐藏字段
+                let result = self.table_columns().await?;
+                let fields = result
+                    .iter()
+                    .filter(|item| !policies.is_hidden(&item.name))
+                    .map(|item| policies.select(&item.name, None))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(fields.join(","))
+            }
             QueryMode::Spatial => {
-                // 查询表字段
-                let pool = self.pool;
-                let query_columns = format!("SELECT column_name,udt_name FROM information_schema.columns WHERE table_schema = '{}' AND table_name   = '{}'", schema, table);
-                // 查询表字段
-                let result = sqlx::query_as::<_, Course>(&query_columns)
-                    .fetch_all(pool)
-                    .await;
                 // 获取表字段列表
-                let result = result.map_err(|err| ParamError(format!("{err}")))?;
-                // 空间字段
-                let mut geometry_field = None;
-                // 字段列表
-                let mut fields = Vec::new();
-                // 遍历字段并收集字段名称
-                for item in result.into_iter() {
-                    // 判断是否有空间字段
-                    if item.udt_name == "geometry" {
-                        geometry_field = Some(item.column_name);
-                    } else {
-                        fields.push(item.column_name);
-                    }
-                }
+                let result = self.table_columns().await?;
+                // 收集非空间字段名称，去掉隐藏字段
+                let mut fields = result
+                    .iter()
+                    .filter(|item| !item.is_spatial() && !policies.is_hidden(&item.name))
+                    .map(|item| policies.select(&item.name, None))
+                    .collect::<Result<Vec<_>, _>>()?;
                 // 判断是返回空间字段
                 if matches!(param.return_geometry, Some(true)) {
-                    // 判断是否返回空间字段
-                    let geom = geometry_field
-                        .ok_or(ParamError("参数错误，该数据不包含空间字段".to_string()))?;
-                    // 处理空间字段
-                    let geometry_field = self.handler_geometry_format(geom.as_str());
                     // 添加空间字段
-                    fields.push(geometry_field);
+                    fields.push(self.get_geometry_fields().await?);
                 }
                 Ok(fields.join(","))
             }
         }
     }
 
-    // 查询表空间字段，可能有或者没有空间字段，返回option类型
-    async fn get_table_geometry(&self) -> Option<String> {
-        let table = &self.table;
-        let schema = &self.schema;
-        // 查询表字段
-        let pool = self.pool;
-        let query_columns = format!("SELECT column_name,udt_name FROM information_schema.columns WHERE table_schema = '{}' AND table_name = '{}' AND udt_name='geometry'", schema, table);
-        // 查询表字段
-        let result = sqlx::query_as::<_, Course>(&query_columns)
-            .fetch_one(pool)
-            .await;
+    // 查询选择的空间字段，没有指定时使用表的第一个空间字段
+    async fn get_table_geometries(&self) -> Result<Vec<ColumnInfo>, CtsError> {
+        let columns = self.table_columns().await?;
+        match &self.param.geometry {
+            None => columns
+                .iter()
+                .find(|item| item.is_spatial() && !self.field_policies.is_hidden(&item.name))
+                .map(|item| vec![item.clone()])
+                .ok_or(ParamError("参数错误，该数据不包含空间字段".to_string())),
+            Some(names) if names.is_empty() => {
+                Err(ParamError("参数错误，geometry不能为空".to_string()))
+            }
+            Some(names) => names
+                .iter()
+                .map(|name| {
+                    columns
+                        .iter()
+                        .find(|item| {
+                            &item.name == name
+                                && item.is_spatial()
+                                && !self.field_policies.is_hidden(name)
+                        })
+                        .cloned()
+                        .ok_or(ParamError(format!("参数错误，空间字段{name}不存在")))
+                })
+                .collect(),
+        }
+    }
+
+    // 返回的空间字段查询语句
+    async fn get_geometry_fields(&self) -> Result<String, CtsError> {
+        let geometries = self.get_table_geometries().await?;
+        let fields: Vec<String> = geometries
+            .iter()
+            .map(|item| self.handler_geometry_format(item))
+            .collect();
+        Ok(fields.join(","))
+    }
+
+    // 返回结果中的空间字段名称，指定空间字段时使用字段名称，否则使用 GEOMETRY
+    fn geometry_keys(&self) -> Vec<String> {
+        match &self.param.geometry {
+            Some(names) => names.clone(),
+            None => vec![GEOMETRY.to_string()],
+        }
+    }
+
+    // 解析关联表查询字段，关联名称以及字段需要在配置中声明
+    async fn parse_join(&self) -> Result<Option<String>, CtsError> {
+        let joins = match &self.param.join {
+            None => return Ok(None),
+            Some(data) => data,
+        };
+        if self.param.aggregate.is_some() {
+            return Err(ParamError("参数错误，统计查询不支持关联表".to_string()));
+        }
+        let mut result = Vec::new();
+        for item in joins.iter() {
+            // 校验关联配置
+            let join = self
+                .joins
+                .iter()
+                .find(|join| join.source == self.table && join.name == item.name)
+                .ok_or(ParamError(format!("参数错误，关联{}未配置", item.name)))?;
+            let join_schema = join.schema.as_deref().unwrap_or(&self.schema);
+            let columns = self
+                .metadata_cache
+                .columns(self.pool, join_schema, &join.table)
+                .await?;
+            // 关联表的字段权限
+            let policies = FieldPolicies::for_table(&self.policies, &join.table);
+            // 允许返回的字段，隐藏字段不返回
+            let allowed: Vec<String> = match &join.fields {
+                Some(fields) => fields.clone(),
+                None => columns
+                    .iter()
+                    .filter(|column| !column.is_spatial())
+                    .map(|column| column.name.clone())
+                    .collect(),
+            }
+            .into_iter()
+            .filter(|field| !policies.is_hidden(field))
+            .collect();
+            let fields = match &item.out_fields {
+                None => allowed,
+                Some(fields) => {
+                    for field in fields.iter() {
+                        if !allowed.contains(field)
+                            || !columns.iter().any(|column| &column.name == field)
+                        {
+                            return Err(ParamError(format!(
+                                "参数错误，关联{}不包含字段{field}",
+                                item.name
+                            )));
+                        }
+                    }
+                    fields.clone()
+                }
+            };
+            if fields.is_empty() {
+                return Err(ParamError(format!(
+                    "参数错误，关联{}没有返回字段",
+                    item.name
+                )));
+            }
+            // 脱敏字段替换成脱敏表达式，存在字段权限时不能使用*
+            let fields = fields
+                .iter()
+                .map(|field| policies.select(field, None))
+                .collect::<Result<Vec<_>, _>>()?;
+            let join = JoinParse {
+                join,
+                fields: &fields,
+                schema: &self.schema,
+                source: &self.source_name(),
+            }
+            .parse()?;
+            result.extend(join);
+        }
+        Ok(Some(result.join(",")))
+    }
 
-        match result {
-            Ok(data) => Some(data.column_name),
-            Err(_) => None,
+    // 解析过滤条件，包含范围过滤
+    async fn parse_filter(&self) -> Result<Option<String>, CtsError> {
+        let param = &self.param;
+        let mut conditions = Vec::new();
+        if let Some(filter) = FilterParse(&param.filter)
+            .parse()
+            .map_err(|err| err.with_path("filter"))?
+        {
+            conditions.push(filter);
+        }
+        if param.bbox.is_some() {
+            // 范围过滤作用于第一个空间字段
+            let geometries = self.get_table_geometries().await?;
+            let geometry = &geometries[0];
+            let bbox = BboxParse {
+                bbox: &param.bbox,
+                crs: param.bbox_crs,
+                column: &geometry.name,
+                udt_name: &geometry.udt_name,
+                srid: geometry.srid,
+            }
+            .parse()?;
+            conditions.extend(bbox);
+        }
+        // 强制过滤条件，请求参数不能覆盖
+        if let Some(predicate) = Predicate::and_all(self.predicates.iter().cloned()) {
+            conditions.push(predicate.to_sql());
+        }
+        match conditions.len() {
+            0 => Ok(None),
+            1 => Ok(conditions.pop()),
+            _ => {
+                let conditions: Vec<String> =
+                    conditions.iter().map(|item| format!("({item})")).collect();
+                Ok(Some(conditions.join(" and ")))
+            }
         }
     }
 
     // 解析分页查询sql函数
     async fn parse_page_count(&self) -> Result<String, CtsError> {
-        let param = &self.param;
         // filter 解析
-        let filter = FilterParse(&param.filter).parse()?;
+        let filter = self.parse_filter().await?;
         let mut builder = QueryBuilder::new("select count(*) as count");
-        let table = &self.table;
-        let schema = &self.schema;
         builder.push(" from ");
-        builder.push(schema);
-        builder.push(".");
-        builder.push(table);
+        builder.push(self.parse_source()?);
         // 处理过滤
         if let Some(data) = filter {
             builder.push(" where ");
@@ -257,45 +624,146 @@ impl<'a> SqlBuilder<'a> {
     }
 
     /// 处理geometry format 格式参数，根据不同的格式参数，返回不同的空间字段
-    fn handler_geometry_format(&self, geometry_field: &str) -> String {
+    /// > geography 转换成 geometry，raster 返回外包矩形
+    fn handler_geometry_format(&self, geometry: &ColumnInfo) -> String {
         let param = &self.param;
         let geo_format = &param.geo_format;
+        // 空间字段表达式
+        let geometry_field = geometry_expr(&geometry.name, &geometry.udt_name);
+        // 指定空间字段时按字段名称返回
+        let alias = match param.geometry {
+            Some(_) => geometry.name.as_str(),
+            None => GEOMETRY,
+        };
         // 添加空间查询字段
         match geo_format {
             None => {
                 // 将空间字段转换成字符串wkt格式字符串
-                format!("st_asgeojson({geometry_field}) as {GEOMETRY} ")
+                format!("st_asgeojson({geometry_field}) as {alias} ")
             }
             Some(format) => match format {
                 GeometryFormat::GeoJson => {
-                    format!("st_asgeojson({geometry_field}) as {GEOMETRY} ")
+                    format!("st_asgeojson({geometry_field}) as {alias} ")
                 }
                 GeometryFormat::WKT => {
-                    format!("st_asewkt({geometry_field}) as {GEOMETRY} ")
+                    format!("st_asewkt({geometry_field}) as {alias} ")
                 }
                 GeometryFormat::Byte => {
-                    format!("st_asbinary({geometry_field}) as {GEOMETRY} ")
+                    format!("st_asbinary({geometry_field}) as {alias} ")
                 }
                 GeometryFormat::Text => {
-                    format!("st_astext({geometry_field}) as {GEOMETRY} ")
+                    format!("st_astext({geometry_field}) as {alias} ")
                 }
                 GeometryFormat::WKB => {
-                    format!("st_asewkb({geometry_field}) as {GEOMETRY} ")
+                    format!("st_asewkb({geometry_field}) as {alias} ")
                 }
             },
         }
     }
 
+    /// 查询结果字段描述信息，有数据时读取结果列，没有数据时通过预编译语句获取
+    /// @param query 查询语句
+    /// @param row 第一行数据
+    pub async fn query_fields(
+        &self,
+        query: &str,
+        row: Option<&PgRow>,
+    ) -> Result<Vec<FieldInfo>, CtsError> {
+        let (columns, nullable) = match row {
+            Some(row) => {
+                let columns = row
+                    .columns()
+                    .iter()
+                    .map(|column| {
+                        let name = column.name().to_string();
+                        (name, column.type_info().name().to_string())
+                    })
+                    .collect::<Vec<_>>();
+                let nullable = vec![None; columns.len()];
+                (columns, nullable)
+            }
+            None => {
+                let describe = self
+                    .describe(query)
+                    .await
+                    .map_err(|err| self.limits.query_error(err))?;
+                let columns = describe
+                    .columns()
+                    .iter()
+                    .map(|column| {
+                        let name = column.name().to_string();
+                        (name, column.type_info().name().to_string())
+                    })
+                    .collect::<Vec<_>>();
+                let nullable = (0..columns.len())
+                    .map(|index| describe.nullable(index))
+                    .collect::<Vec<_>>();
+                (columns, nullable)
+            }
+        };
+        // 输出列名与表字段的对应关系
+        let sources = FieldParse(&self.param.out_fields).sources()?;
+        // 表字段元数据
+        let meta = self.table_columns().await?;
+        Ok(build_fields(&columns, &nullable, &sources, &meta))
+    }
+
+    /// 生成查询语句以及分页统计语句，不执行查询
+    pub async fn to_sql(&mut self) -> Result<GeneratedSql, CtsError> {
+        // 格式处理，GeoJSON 格式会返回空间字段
+        self.format();
+        let select = self.parse().await?;
+        let count = match (&self.param.page, &self.param.aggregate) {
+            (Some(_), None) => Some(self.parse_page_count().await?),
+            _ => None,
+        };
+        Ok(GeneratedSql { select, count })
+    }
+
+    /// 返回查询语句的执行计划，不返回数据
+    /// @param analyze 是否实际执行查询并返回执行耗时
+    pub async fn explain(&mut self, analyze: bool) -> Result<Value, CtsError> {
+        let generated = self.to_sql().await?;
+        let options = if analyze {
+            "ANALYZE, BUFFERS, FORMAT JSON"
+        } else {
+            "FORMAT JSON"
+        };
+        let row = self
+            .fetch_one(&format!("EXPLAIN ({options}) {}", generated.select))
+            .await
+            .map_err(|err| self.limits.query_error(err))?;
+        let plan = row
+            .try_get::<Value, _>(0)
+            .map_err(|err| self.limits.query_error(err))?;
+        Ok(serde_json::json!({ "sql": generated, "plan": plan }))
+    }
+
     pub async fn query(&mut self) -> Result<Value, CtsError> {
         // 格式处理
         let format = self.format();
         // 解析查询语句
         let query = self.parse().await?;
         // 查询数据
-        let list = sqlx::query(&query)
-            .fetch_all(self.pool)
+        let list = self
+            .fetch_all(&query)
             .await
-            .map_err(|err| ParamError(err.to_string()))?;
+            .map_err(|err| self.limits.query_error(err))?;
+        // 不分页查询超过最大返回行数时返回错误
+        if self.param.page.is_none() {
+            self.limits.check_rows(list.len())?;
+        }
+        // 字段描述信息
+        let fields = match self.param.return_fields {
+            Some(true) => Some(self.query_fields(&query, list.first()).await?),
+            _ => None,
+        };
+        let value = self.query_list(format, list).await?;
+        Ok(with_fields(value, fields))
+    }
+
+    // 组装列表或者分页结果
+    async fn query_list(&self, format: CtsFormat, list: Vec<PgRow>) -> Result<Value, CtsError> {
         // 判断是否有统计条件，有统计条件不能进行分页
         if self.param.aggregate.is_none() {
             // 分页查询
@@ -303,10 +771,10 @@ impl<'a> SqlBuilder<'a> {
                 // 解析分页查询语句
                 let query = self.parse_page_count().await?;
                 // 查询分页结果
-                let result = sqlx::query(&query)
-                    .fetch_one(self.pool)
+                let result = self
+                    .fetch_one(&query)
                     .await
-                    .map_err(|err| ParamError(err.to_string()))?;
+                    .map_err(|err| self.limits.query_error(err))?;
                 let total = result.get::<i64, _>(0);
                 // 计算页数
                 let pages = (total as f64 * 1.0 / page_param.page_size as f64).ceil() as i64;
@@ -317,14 +785,26 @@ impl<'a> SqlBuilder<'a> {
                     total,
                     list,
                 };
-                Ok(CtsResult::Page(page_value).to_value(format))
+                CtsResult::Page(page_value).to_value_with_geometry(
+                    format,
+                    &self.serialize_options,
+                    &self.geometry_keys(),
+                )
             } else {
                 // 返回成功数据列表
-                Ok(CtsResult::List(list).to_value(format))
+                CtsResult::List(list).to_value_with_geometry(
+                    format,
+                    &self.serialize_options,
+                    &self.geometry_keys(),
+                )
             }
         } else {
             // 返回成功数据列表
-            Ok(CtsResult::List(list).to_value(format))
+            CtsResult::List(list).to_value_with_geometry(
+                format,
+                &self.serialize_options,
+                &self.geometry_keys(),
+            )
         }
     }
 
@@ -334,12 +814,51 @@ impl<'a> SqlBuilder<'a> {
         // 解析查询语句
         let query = self.parse().await?;
         // 查询数据
-        let row = sqlx::query(&query)
-            .fetch_one(self.pool)
+        let row = self
+            .fetch_one(&query)
             .await
-            .map_err(|err| ParamError(err.to_string()))?;
+            .map_err(|err| self.limits.query_error(err))?;
 
-        Ok(CtsResult::Single(row).to_value(format))
+        CtsResult::Single(row).to_value_with_geometry(
+            format,
+            &self.serialize_options,
+            &self.geometry_keys(),
+        )
+    }
+
+    /// 查询数据并反序列化成结构体列表，分页参数只作为limit/offset使用
+    pub async fn query_as<T: DeserializeOwned>(&mut self) -> Result<Vec<T>, CtsError> {
+        // 解析查询语句
+        let query = self.parse().await?;
+        // 查询数据
+        let list = self
+            .fetch_all(&query)
+            .await
+            .map_err(|err| self.limits.query_error(err))?;
+        // 不分页查询超过最大返回行数时返回错误
+        if self.param.page.is_none() {
+            self.limits.check_rows(list.len())?;
+        }
+        let mut result = Vec::with_capacity(list.len());
+        for row in list.iter() {
+            let data = from_row_with(row, &self.serialize_options)
+                .map_err(|err| ConvertError(format!("结果转换错误: {err}")))?;
+            result.push(data);
+        }
+        Ok(result)
+    }
+
+    /// 查询单条数据并反序列化成结构体
+    pub async fn query_one_as<T: DeserializeOwned>(&mut self) -> Result<T, CtsError> {
+        // 解析查询语句
+        let query = self.parse().await?;
+        // 查询数据
+        let row = self
+            .fetch_one(&query)
+            .await
+            .map_err(|err| self.limits.query_error(err))?;
+        from_row_with(&row, &self.serialize_options)
+            .map_err(|err| ConvertError(format!("结果转换错误: {err}")))
     }
 
     fn format(&mut self) -> CtsFormat {
@@ -362,3 +881,201 @@ impl<'a> SqlBuilder<'a> {
         }
     }
 }
+
+/// 在结果中添加字段描述信息
+/// > 分页、GeoJSON 等对象结果直接添加 fields 属性，列表结果转换成 `{"fields": [], "list": []}`
+fn with_fields(value: Value, fields: Option<Vec<FieldInfo>>) -> Value {
+    let Some(fields) = fields else {
+        return value;
+    };
+    let fields = serde_json::to_value(fields).unwrap_or_default();
+    match value {
+        Value::Object(mut map) => {
+            map.insert("fields".to_string(), fields);
+            Value::Object(map)
+        }
+        list => serde_json::json!({ "fields": fields, "list": list }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::postgres::PgPoolOptions;
+
+    #[tokio::test]
+    async fn test_to_sql() {
+        // 普通查询不需要读取表结构，不会连接数据库
+        let pool = PgPoolOptions::new()
+            .connect_lazy("postgres://localhost/test")
+            .unwrap();
+        let param: CtsParam = serde_json::from_value(serde_json::json!({
+            "filter": ["=", "name", "a"],
+            "page": {"page": 2, "pageSize": 10}
+        }))
+        .unwrap();
+        let config = ExpressionConfig::new_normal(None);
+        let generated = SqlBuilder::new(&pool, "road".to_string(), config, param)
+            .to_sql()
+            .await
+            .unwrap();
+        assert_eq!(
+            generated.select,
+            "select * from public.road where name = 'a' LIMIT 10 OFFSET 10"
+        );
+        assert_eq!(
+            generated.count.unwrap(),
+            "select count(*) as count from public.road where name = 'a'"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_date_trunc_timezone() {
+        let pool = PgPoolOptions::new()
+            .connect_lazy("postgres://localhost/test")
+            .unwrap();
+        // 表结构从缓存读取，不会连接数据库
+        let cache = Arc::new(MetadataCache::new(std::time::Duration::from_secs(60)));
+        cache.insert(
+            "public",
+            "road",
+            vec![
+                ColumnInfo::new("created_at", "timestamp"),
+                ColumnInfo::new("updated_at", "timestamptz"),
+            ],
+        );
+        let config = ExpressionConfig::new(None)
+            .with_metadata_cache(cache)
+            .with_timezone("+08:00".parse().unwrap());
+        let to_sql = |field: &str| {
+            let param: CtsParam = serde_json::from_value(serde_json::json!({
+                "dateTrunc": {"field": field, "unit": "day", "alias": "day"}
+            }))
+            .unwrap();
+            SqlBuilder::new(&pool, "road".to_string(), config.clone(), param)
+        };
+        // timestamp 字段保存的是本地时间，不转换时区
+        let generated = to_sql("created_at").to_sql().await.unwrap();
+        assert_eq!(
+            generated.select,
+            "select date_trunc('day', created_at) as day from public.road \
+             group by date_trunc('day', created_at)"
+        );
+        let generated = to_sql("road.updated_at").to_sql().await.unwrap();
+        assert_eq!(
+            generated.select,
+            "select date_trunc('day', road.updated_at AT TIME ZONE INTERVAL '+08:00') as day \
+             from public.road group by date_trunc('day', road.updated_at AT TIME ZONE INTERVAL '+08:00')"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_predicate_filter() {
+        let pool = PgPoolOptions::new()
+            .connect_lazy("postgres://localhost/test")
+            .unwrap();
+        let config = ExpressionConfig::new_normal(None)
+            .with_predicate(crate::ast::field("org_id").eq("org-1"));
+        // 请求过滤条件以及强制过滤条件分别加括号
+        let param: CtsParam = serde_json::from_value(serde_json::json!({
+            "filter": ["or", ["=", "name", "a"], ["=", "level", 1]]
+        }))
+        .unwrap();
+        let generated = SqlBuilder::new(&pool, "road".to_string(), config.clone(), param)
+            .to_sql()
+            .await
+            .unwrap();
+        assert_eq!(
+            generated.select,
+            "select * from public.road where ((name = 'a') or (level = 1)) and (org_id = 'org-1')"
+        );
+
+        // 字段名称不能写入sql片段
+        let param: CtsParam =
+            serde_json::from_value(serde_json::json!({"filter": ["=", "1=1) or (1", 1]})).unwrap();
+        let err = SqlBuilder::new(&pool, "road".to_string(), config, param)
+            .to_sql()
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), crate::error::ErrorCode::InvalidFilter);
+        assert!(
+            err.to_string()
+                .starts_with("filter[1]: 字段名称错误1=1) or (1"),
+            "{err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_join_policies() {
+        let pool = PgPoolOptions::new()
+            .connect_lazy("postgres://localhost/test")
+            .unwrap();
+        // 关联表结构写入缓存，不连接数据库
+        let cache = Arc::new(MetadataCache::new(std::time::Duration::from_secs(60)));
+        cache.insert(
+            "public",
+            "user",
+            vec![
+                ColumnInfo::new("id", "varchar"),
+                ColumnInfo::new("id_card", "varchar"),
+                ColumnInfo::new("phone", "varchar"),
+            ],
+        );
+        let join = JoinConfig {
+            name: "owner".to_string(),
+            source: "road".to_string(),
+            table: "user".to_string(),
+            schema: None,
+            local_key: "owner_id".to_string(),
+            foreign_key: "id".to_string(),
+            kind: crate::config::JoinKind::One,
+            fields: None,
+        };
+        let config = ExpressionConfig::new_normal(None)
+            .with_metadata_cache(cache)
+            .with_join(join.clone())
+            .with_field_policy(FieldPolicy::hidden("user", "id_card"))
+            .with_field_policy(FieldPolicy::masked(
+                "user",
+                "phone",
+                crate::policy::MaskRule::Function("mask".to_string()),
+            ));
+        let param: CtsParam =
+            serde_json::from_value(serde_json::json!({"join": [{"name": "owner"}]})).unwrap();
+        let generated = SqlBuilder::new(&pool, "road".to_string(), config.clone(), param)
+            .to_sql()
+            .await
+            .unwrap();
+        assert!(generated
+            .select
+            .contains("(select id, mask(phone) as phone from public.user where id = public.road.owner_id limit 1)"));
+
+        // 隐藏字段不能作为关联返回字段
+        let param: CtsParam = serde_json::from_value(
+            serde_json::json!({"join": [{"name": "owner", "outFields": ["id_card"]}]}),
+        )
+        .unwrap();
+        let result = SqlBuilder::new(&pool, "road".to_string(), config, param)
+            .to_sql()
+            .await;
+        assert!(result.is_err());
+
+        // 存在字段权限时关联字段不能使用*
+        let config = ExpressionConfig::new_normal(None)
+            .with_metadata_cache(Arc::new(MetadataCache::new(
+                std::time::Duration::from_secs(60),
+            )))
+            .with_join(JoinConfig {
+                fields: Some(vec!["*".to_string()]),
+                ..join
+            })
+            .with_field_policy(FieldPolicy::hidden("user", "id_card"));
+        let param: CtsParam =
+            serde_json::from_value(serde_json::json!({"join": [{"name": "owner"}]})).unwrap();
+        let mut builder = SqlBuilder::new(&pool, "road".to_string(), config, param);
+        builder
+            .metadata_cache
+            .insert("public", "user", vec![ColumnInfo::new("id", "varchar")]);
+        assert!(builder.to_sql().await.is_err());
+    }
+}
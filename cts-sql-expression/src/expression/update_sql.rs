@@ -1,16 +1,23 @@
 use std::collections::HashMap;
 
-use chrono::Local;
 use serde_json::Value;
 use sqlx::{Pool, Postgres};
 
 use super::{CREATED_AT, UPDATED_AT};
+use crate::ast::Predicate;
+use crate::audit::{AuditOperation, Auditor};
+use crate::config::ExpressionConfig;
+use crate::error::CtsError;
+use crate::tenant::set_search_path;
+use crate::timezone::TimeZone;
 
 /// update sql构造器
+/// > 配置中的强制过滤条件以及字段权限总是生效，不满足条件的数据不会被修改
+///
 /// @param 请求参数
 /// @param pool 数据库连接池
 /// @table 查询表名
-/// @schema 对应数据库的schema
+/// @config 查询配置，提供schema、强制过滤条件、字段权限以及时区
 /// @id 数据唯一字段
 pub struct UpdateSqlBuilder<'a> {
     data: HashMap<String, Value>,
@@ -18,6 +25,9 @@ pub struct UpdateSqlBuilder<'a> {
     table: String,
     schema: String,
     id: String,
+    predicates: Vec<Predicate>,
+    search_path: bool,
+    auditor: Option<Auditor>,
 }
 
 impl<'a> UpdateSqlBuilder<'a> {
@@ -26,21 +36,55 @@ impl<'a> UpdateSqlBuilder<'a> {
         mut data: HashMap<String, Value>,
         pool: &'a Pool<Postgres>,
         table: String,
-        schema: String,
-    ) -> Self {
-        // 插入日期字段
-        let date = Local::now().to_string();
-        data.insert(UPDATED_AT.to_string(), Value::String(date));
+        config: &ExpressionConfig,
+    ) -> Result<Self, CtsError> {
         // 判断是否有创建时间字段，如果有删除
         // 创建时间不能修改
         data.remove(CREATED_AT);
-        Self {
+        // 检查字段权限，隐藏字段以及只读字段不能修改，修改时间由构造器生成，不做检查
+        config
+            .field_policies(&table)
+            .check_write(data.keys().filter(|key| key.as_str() != UPDATED_AT))?;
+        // 插入日期字段
+        let date = config.timezone.unwrap_or_else(TimeZone::local).now();
+        data.insert(UPDATED_AT.to_string(), Value::String(date));
+        Ok(Self {
             id,
             data,
             pool,
             table,
-            schema,
-        }
+            schema: config.schema(),
+            predicates: config.predicates.clone(),
+            search_path: config.search_path,
+            auditor: config.auditor(None),
+        })
+    }
+
+    /// 添加强制过滤条件，与配置中的强制过滤条件一起生效
+    pub fn with_predicates(mut self, predicates: Vec<Predicate>) -> Self {
+        self.predicates.extend(predicates);
+        self
+    }
+
+    /// 记录审计，为空时不记录，默认使用配置中的审计
+    /// @param auditor 审计写入，通过 `ExpressionConfig::auditor` 获取
+    pub fn with_auditor(mut self, auditor: Option<Auditor>) -> Self {
+        self.auditor = auditor;
+        self
+    }
+
+    /// 审计记录的操作人，没有开启审计时忽略
+    /// @param actor 操作人
+    pub fn with_actor(mut self, actor: String) -> Self {
+        self.auditor = self.auditor.map(|auditor| auditor.with_actor(Some(actor)));
+        self
+    }
+
+    /// 按时区重新生成修改时间
+    /// @param timezone 时区
+    pub fn with_timezone(mut self, timezone: TimeZone) -> Self {
+        self.data.insert(UPDATED_AT.to_string(), Value::String(timezone.now()));
+        self
     }
 }
 
@@ -55,13 +99,35 @@ impl<'a> UpdateSqlBuilder<'a> {
         sql.pop();
         sql.pop();
         sql.push_str(&format!(" WHERE id = '{}'", self.id));
+        if let Some(predicate) = Predicate::and_all(self.predicates.iter().cloned()) {
+            sql.push_str(&format!(" AND ({predicate})"));
+        }
 
         sql
     }
 
     pub async fn execute(&self) -> Result<u64, sqlx::Error> {
-        let sql = self.build();
-        let result = sqlx::query(&sql).execute(self.pool).await?;
+        let sql = match &self.auditor {
+            None => self.build(),
+            Some(auditor) => auditor.wrap(
+                &self.build(),
+                AuditOperation::Update,
+                &self.schema,
+                &self.table,
+                Some(&self.id),
+            ),
+        };
+        // 需要设置 search_path 时在事务中修改，事务结束后设置失效
+        let result = match self.search_path {
+            true => {
+                let mut tx = self.pool.begin().await?;
+                set_search_path(&mut tx, &self.schema).await?;
+                let result = sqlx::query(&sql).execute(&mut *tx).await?;
+                tx.commit().await?;
+                result
+            }
+            false => sqlx::query(&sql).execute(self.pool).await?,
+        };
         Ok(result.rows_affected())
     }
 }
@@ -100,4 +166,27 @@ mod tests {
 
         println!("{}",sql);
     }
+
+    #[tokio::test]
+    async fn test_config() {
+        let pool = sqlx::postgres::PgPoolOptions::new()
+            .connect_lazy("postgres://localhost/test")
+            .unwrap();
+        let config = ExpressionConfig::new(None)
+            .with_predicate(crate::ast::field("org_id").eq("org-1"))
+            .with_field_policy(crate::policy::FieldPolicy::read_only("road", "level"));
+        let data = HashMap::from([("name".to_string(), json!("a"))]);
+        let builder =
+            UpdateSqlBuilder::new("1".to_string(), data, &pool, "road".to_string(), &config)
+                .unwrap();
+        assert!(builder
+            .build()
+            .ends_with("WHERE id = '1' AND (org_id = 'org-1')"));
+
+        let data = HashMap::from([("level".to_string(), json!(1))]);
+        assert!(
+            UpdateSqlBuilder::new("1".to_string(), data, &pool, "road".to_string(), &config)
+                .is_err()
+        );
+    }
 }
@@ -0,0 +1,531 @@
+use std::collections::{BTreeSet, HashMap};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use super::{CREATED_AT, ID, UPDATED_AT};
+use crate::ast::Predicate;
+use crate::audit::Auditor;
+use crate::config::ExpressionConfig;
+use crate::error::CtsError;
+use crate::error::CtsError::ParamError;
+use crate::tenant::set_search_path;
+use crate::timezone::TimeZone;
+
+/// 写入结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpsertStatus {
+    Inserted,
+    Updated,
+}
+
+/// 每行数据的写入结果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpsertResult {
+    pub id: String,
+    pub status: UpsertStatus,
+}
+
+/// # upsert sql构造器
+/// > 与 `SaveSqlBuilder` 不同，保留调用方传入的 id，没有 id 时才生成 uuid，
+/// > 冲突时按更新字段修改已有数据，创建时间不会被修改；
+/// > 强制过滤条件限制冲突时可以修改的数据，不满足条件的数据不会被修改，也不会返回
+/// ```sql
+/// INSERT INTO public.device (ID, created_at, name, updated_at) VALUES ('1', '...', 'a', '...')
+/// ON CONFLICT (ID) DO UPDATE SET name = EXCLUDED.name, updated_at = EXCLUDED.updated_at
+/// WHERE (device.org_id = 'org-1')
+/// RETURNING ID::text, (xmax = 0) AS inserted
+/// ```
+/// @param rows 写入数据，每行字段必须一致
+/// @param pool 数据库连接池
+/// @table 表名
+/// @config 查询配置，提供schema、强制过滤条件、字段权限、审计以及时区
+/// @conflict 冲突字段，默认为 id，也可以是唯一约束的字段
+/// @update 冲突时更新的字段，默认为除冲突字段、id 以及创建时间外的全部字段
+pub struct UpsertSqlBuilder<'a> {
+    rows: Vec<HashMap<String, Value>>,
+    pool: &'a Pool<Postgres>,
+    table: String,
+    schema: String,
+    conflict: Vec<String>,
+    update: Option<Vec<String>>,
+    predicates: Vec<Predicate>,
+    search_path: bool,
+    auditor: Option<Auditor>,
+}
+
+impl<'a> UpsertSqlBuilder<'a> {
+    pub fn new(
+        rows: Vec<HashMap<String, Value>>,
+        pool: &'a Pool<Postgres>,
+        table: String,
+        config: &ExpressionConfig,
+    ) -> Result<Self, CtsError> {
+        // 检查字段权限，id以及日期字段由构造器处理，不做检查
+        let policies = config.field_policies(&table);
+        for data in &rows {
+            policies.check_write(data.keys().filter(|key| {
+                !key.eq_ignore_ascii_case(ID) && ![CREATED_AT, UPDATED_AT].contains(&key.as_str())
+            }))?;
+        }
+        let date = config.timezone.unwrap_or_else(TimeZone::local).now();
+        let rows = rows
+            .into_iter()
+            .map(|mut data| {
+                // 保留调用方的 id，统一使用 ID 作为字段名
+                let key = data
+                    .keys()
+                    .find(|key| key.eq_ignore_ascii_case(ID))
+                    .cloned();
+                let id = key
+                    .and_then(|key| data.remove(&key))
+                    .filter(|id| !id.is_null())
+                    .unwrap_or_else(|| Value::String(Uuid::new_v4().to_string()));
+                data.insert(ID.to_string(), id);
+                data.insert(CREATED_AT.to_string(), Value::String(date.to_string()));
+                data.insert(UPDATED_AT.to_string(), Value::String(date.to_string()));
+                data
+            })
+            .collect();
+        Ok(Self {
+            rows,
+            pool,
+            table,
+            schema: config.schema(),
+            conflict: vec![ID.to_string()],
+            update: None,
+            predicates: config.predicates.clone(),
+            search_path: config.search_path,
+            auditor: config.auditor(None),
+        })
+    }
+
+    /// 添加强制过滤条件，与配置中的强制过滤条件一起生效
+    /// > 冲突时排除数据表中的数据，字段加上表名，`Raw` 条件需要自己带上表名
+    pub fn with_predicates(mut self, predicates: Vec<Predicate>) -> Self {
+        self.predicates.extend(predicates);
+        self
+    }
+
+    /// 冲突字段，必须是主键或者唯一约束的字段
+    /// @param columns 冲突字段
+    pub fn with_conflict(mut self, columns: Vec<String>) -> Self {
+        self.conflict = columns;
+        self
+    }
+
+    /// 冲突时更新的字段，修改时间总是会被更新
+    /// @param columns 更新字段
+    pub fn with_update(mut self, columns: Vec<String>) -> Self {
+        self.update = Some(columns);
+        self
+    }
+
+    /// 记录审计，为空时不记录，默认使用配置中的审计
+    /// @param auditor 审计写入，通过 `ExpressionConfig::auditor` 获取
+    pub fn with_auditor(mut self, auditor: Option<Auditor>) -> Self {
+        self.auditor = auditor;
+        self
+    }
+
+    /// 审计记录的操作人，没有开启审计时忽略
+    /// @param actor 操作人
+    pub fn with_actor(mut self, actor: String) -> Self {
+        self.auditor = self.auditor.map(|auditor| auditor.with_actor(Some(actor)));
+        self
+    }
+
+    /// 按时区重新生成创建时间以及修改时间
+    /// @param timezone 时区
+    pub fn with_timezone(mut self, timezone: TimeZone) -> Self {
+        let date = timezone.now();
+        for data in &mut self.rows {
+            data.insert(CREATED_AT.to_string(), Value::String(date.to_string()));
+            data.insert(UPDATED_AT.to_string(), Value::String(date.to_string()));
+        }
+        self
+    }
+
+    pub fn build(&self) -> Result<String, CtsError> {
+        // 新插入的数据 xmax 为 0
+        Ok(format!(
+            "{} RETURNING {ID}::text, (xmax = 0) AS inserted",
+            self.statement()?
+        ))
+    }
+
+    // 不带 returning 的写入语句
+    fn statement(&self) -> Result<String, CtsError> {
+        let Some(first) = self.rows.first() else {
+            return Err(ParamError("没有需要写入的数据".to_string()));
+        };
+        // 字段排序，保证每行数据的顺序一致
+        let columns: BTreeSet<&String> = first.keys().collect();
+        for (index, data) in self.rows.iter().enumerate() {
+            if data.keys().collect::<BTreeSet<_>>() != columns {
+                return Err(ParamError("每行数据的字段必须一致".to_string())
+                    .with_path(&format!("[{index}]")));
+            }
+        }
+        for column in &columns {
+            check_name(column)?;
+        }
+        let contains = |name: &str| columns.iter().any(|item| item.eq_ignore_ascii_case(name));
+
+        if self.conflict.is_empty() {
+            return Err(ParamError("冲突字段不能为空".to_string()));
+        }
+        if let Some(column) = self.conflict.iter().find(|item| !contains(item)) {
+            return Err(ParamError(format!("冲突字段{column}不在写入数据中")));
+        }
+        let is_conflict = |name: &str| {
+            self.conflict
+                .iter()
+                .any(|item| item.eq_ignore_ascii_case(name))
+        };
+        let update: Vec<&str> = match &self.update {
+            Some(update) => {
+                if let Some(column) = update.iter().find(|item| !contains(item)) {
+                    return Err(ParamError(format!("更新字段{column}不在写入数据中")));
+                }
+                if let Some(column) = update
+                    .iter()
+                    .find(|item| is_conflict(item) || item.eq_ignore_ascii_case(CREATED_AT))
+                {
+                    return Err(ParamError(format!("字段{column}不能更新")));
+                }
+                update.iter().map(String::as_str).collect()
+            }
+            None => columns
+                .iter()
+                .map(|item| item.as_str())
+                .filter(|item| {
+                    !is_conflict(item)
+                        && !item.eq_ignore_ascii_case(ID)
+                        && !item.eq_ignore_ascii_case(CREATED_AT)
+                })
+                .collect(),
+        };
+
+        let mut sql = format!(
+            "INSERT INTO {}.{} ({}) VALUES ",
+            self.schema,
+            self.table,
+            columns
+                .iter()
+                .map(|item| item.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        let values: Vec<String> = self
+            .rows
+            .iter()
+            .map(|data| {
+                let values: Vec<String> = columns
+                    .iter()
+                    .map(|column| handler_value(&data[*column]))
+                    .collect();
+                format!("({})", values.join(", "))
+            })
+            .collect();
+        sql.push_str(&values.join(", "));
+
+        // 修改时间总是更新
+        let mut set: Vec<String> = update
+            .iter()
+            .filter(|item| !item.eq_ignore_ascii_case(UPDATED_AT))
+            .map(|item| format!("{item} = EXCLUDED.{item}"))
+            .collect();
+        set.push(format!("{UPDATED_AT} = EXCLUDED.{UPDATED_AT}"));
+        sql.push_str(&format!(
+            " ON CONFLICT ({}) DO UPDATE SET {}",
+            self.conflict.join(", "),
+            set.join(", ")
+        ));
+        // 冲突数据同时存在于数据表以及 EXCLUDED 中，字段需要带上表名
+        if let Some(predicate) = Predicate::and_all(self.predicates.iter().cloned()) {
+            sql.push_str(&format!(" WHERE ({})", predicate.qualify(&self.table)));
+        }
+        Ok(sql)
+    }
+
+    /// 执行写入，按数据库返回顺序给出每行数据的 id 以及插入或者更新
+    pub async fn execute(&self) -> Result<Vec<UpsertResult>, CtsError> {
+        if self.rows.is_empty() {
+            return Ok(Vec::new());
+        }
+        let sql = match &self.auditor {
+            None => self.build()?,
+            Some(auditor) => auditor.wrap_upsert(&self.statement()?, &self.schema, &self.table),
+        };
+        // 需要设置 search_path 时在事务中写入，事务结束后设置失效
+        let query = sqlx::query_as::<_, (String, bool)>(&sql);
+        let rows = match self.search_path {
+            true => {
+                let mut tx = self.pool.begin().await?;
+                set_search_path(&mut tx, &self.schema).await?;
+                let rows = query.fetch_all(&mut *tx).await?;
+                tx.commit().await?;
+                rows
+            }
+            false => query.fetch_all(self.pool).await?,
+        };
+        Ok(rows
+            .into_iter()
+            .map(|(id, inserted)| UpsertResult {
+                id,
+                status: match inserted {
+                    true => UpsertStatus::Inserted,
+                    false => UpsertStatus::Updated,
+                },
+            })
+            .collect())
+    }
+}
+
+/// 处理数据，判断值的类型，返回不同的字符串
+fn handler_value(data: &Value) -> String {
+    match data {
+        Value::String(s) => format!("'{}'", s.replace('\'', "''")),
+        Value::Number(n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+        _ => "NULL".to_string(),
+    }
+}
+
+// 字段名称只能包含字母、数字以及下划线
+fn check_name(name: &str) -> Result<(), CtsError> {
+    match !name.is_empty()
+        && name
+            .chars()
+            .all(|item| item.is_alphanumeric() || item == '_')
+    {
+        true => Ok(()),
+        false => Err(ParamError(format!("字段名称错误{name}"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use sqlx::postgres::PgPoolOptions;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_build() {
+        let pool = PgPoolOptions::new()
+            .connect_lazy("postgres://localhost/test")
+            .unwrap();
+        let config = ExpressionConfig::new(None);
+        let rows = vec![
+            HashMap::from([
+                ("id".to_string(), json!("a")),
+                ("name".to_string(), json!("O'Neil")),
+            ]),
+            HashMap::from([("name".to_string(), json!("b"))]),
+        ];
+        let builder = UpsertSqlBuilder::new(rows, &pool, "device".to_string(), &config)
+            .unwrap()
+            .with_timezone("+08:00".parse().unwrap());
+        let sql = builder.build().unwrap();
+        assert!(sql.starts_with(
+            "INSERT INTO public.device (ID, created_at, name, updated_at) VALUES ('a', "
+        ));
+        assert!(sql.contains("'O''Neil'"));
+        assert!(sql.ends_with(
+            "ON CONFLICT (ID) DO UPDATE SET name = EXCLUDED.name, updated_at = EXCLUDED.updated_at \
+             RETURNING ID::text, (xmax = 0) AS inserted"
+        ));
+
+        let rows = vec![HashMap::from([
+            ("code".to_string(), json!(1)),
+            ("name".to_string(), json!("a")),
+        ])];
+        let builder = UpsertSqlBuilder::new(rows, &pool, "device".to_string(), &config)
+            .unwrap()
+            .with_conflict(vec!["code".to_string()])
+            .with_update(vec!["created_at".to_string()]);
+        assert!(builder.build().is_err());
+
+        let rows = vec![
+            HashMap::from([("name".to_string(), json!("a"))]),
+            HashMap::from([("code".to_string(), json!(1))]),
+        ];
+        let builder = UpsertSqlBuilder::new(rows, &pool, "device".to_string(), &config).unwrap();
+        assert_eq!(builder.build().unwrap_err().path(), Some("[1]"));
+
+        // 只读字段不能写入
+        let config = ExpressionConfig::new(None)
+            .with_field_policy(crate::policy::FieldPolicy::read_only("device", "code"));
+        let rows = vec![HashMap::from([("code".to_string(), json!(1))])];
+        assert!(UpsertSqlBuilder::new(rows, &pool, "device".to_string(), &config).is_err());
+
+        let config =
+            ExpressionConfig::new(None).with_predicate(crate::ast::field("org_id").eq("org-1"));
+        let rows = vec![HashMap::from([("name".to_string(), json!("a"))])];
+        let builder = UpsertSqlBuilder::new(rows, &pool, "device".to_string(), &config).unwrap();
+        assert!(builder.build().unwrap().ends_with(
+            "DO UPDATE SET name = EXCLUDED.name, updated_at = EXCLUDED.updated_at \
+             WHERE (device.org_id = 'org-1') RETURNING ID::text, (xmax = 0) AS inserted"
+        ));
+    }
+
+    // 设置 DATABASE_URL 时才连接数据库测试，没有设置时跳过
+    #[tokio::test]
+    async fn test_execute_predicate() {
+        let Ok(url) = std::env::var("DATABASE_URL") else {
+            return;
+        };
+        // 临时表只在当前连接中存在
+        let pool = PgPoolOptions::new()
+            .max_connections(1)
+            .connect(&url)
+            .await
+            .unwrap();
+        sqlx::query(
+            "create temp table device (id text primary key, org_id text, name text, \
+             created_at text, updated_at text)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query("insert into pg_temp.device (id, org_id, name) values ('a', 'org-2', 'b')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let config = ExpressionConfig::new(Some("pg_temp".to_string()))
+            .with_predicate(crate::ast::field("org_id").eq("org-1"));
+        let rows = vec![
+            HashMap::from([
+                ("id".to_string(), json!("a")),
+                ("name".to_string(), json!("c")),
+            ]),
+            HashMap::from([
+                ("id".to_string(), json!("d")),
+                ("name".to_string(), json!("e")),
+            ]),
+        ];
+        let builder = UpsertSqlBuilder::new(rows, &pool, "device".to_string(), &config).unwrap();
+        // 其它组织的数据不会被修改，也不会返回
+        let result = builder.execute().await.unwrap();
+        assert_eq!(
+            result,
+            vec![UpsertResult {
+                id: "d".to_string(),
+                status: UpsertStatus::Inserted,
+            }]
+        );
+        let name: String = sqlx::query_scalar("select name from pg_temp.device where id = 'a'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(name, "b");
+    }
+
+    #[tokio::test]
+    async fn test_execute_audit() {
+        let Ok(url) = std::env::var("DATABASE_URL") else {
+            return;
+        };
+        let pool = PgPoolOptions::new()
+            .max_connections(1)
+            .connect(&url)
+            .await
+            .unwrap();
+        sqlx::query(
+            "create temp table device (id text primary key, name text, \
+             created_at text, updated_at text)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query("insert into pg_temp.device (id, name) values ('a', 'b')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let config = ExpressionConfig::new(Some("pg_temp".to_string()))
+            .with_audit(crate::audit::AuditConfig::default());
+        let auditor = config.auditor(None).unwrap();
+        auditor.create_table(&pool).await.unwrap();
+
+        let rows = vec![
+            HashMap::from([
+                ("id".to_string(), json!("a")),
+                ("name".to_string(), json!("c")),
+            ]),
+            HashMap::from([
+                ("id".to_string(), json!("d")),
+                ("name".to_string(), json!("e")),
+            ]),
+        ];
+        let builder = UpsertSqlBuilder::new(rows, &pool, "device".to_string(), &config)
+            .unwrap()
+            .with_actor("admin".to_string());
+        let mut result = builder.execute().await.unwrap();
+        result.sort_by(|a, b| a.id.cmp(&b.id));
+        assert_eq!(result[0].status, UpsertStatus::Updated);
+        assert_eq!(result[1].status, UpsertStatus::Inserted);
+
+        let history = auditor
+            .history(&pool, "pg_temp", "device", "a")
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].operation, "update");
+        assert_eq!(history[0].actor.as_deref(), Some("admin"));
+        assert_eq!(history[0].before.as_ref().unwrap()["name"], json!("b"));
+        assert_eq!(history[0].after.as_ref().unwrap()["name"], json!("c"));
+        assert!(history[0]
+            .after
+            .as_ref()
+            .unwrap()
+            .get("cts_inserted")
+            .is_none());
+        let history = auditor
+            .history(&pool, "pg_temp", "device", "d")
+            .await
+            .unwrap();
+        assert_eq!(history[0].operation, "insert");
+        assert!(history[0].before.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_execute_search_path() {
+        let Ok(url) = std::env::var("DATABASE_URL") else {
+            return;
+        };
+        let pool = PgPoolOptions::new()
+            .max_connections(1)
+            .connect(&url)
+            .await
+            .unwrap();
+        // 默认值记录写入时的 search_path
+        sqlx::query(
+            "create temp table device (id text primary key, name text, created_at text, \
+             updated_at text, path text default current_setting('search_path'))",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let config = ExpressionConfig::new(Some("pg_temp".to_string())).with_search_path(true);
+        let rows = vec![HashMap::from([("name".to_string(), json!("a"))])];
+        let builder = UpsertSqlBuilder::new(rows, &pool, "device".to_string(), &config).unwrap();
+        builder.execute().await.unwrap();
+        let path: String = sqlx::query_scalar("select path from pg_temp.device")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(path, "pg_temp");
+        // 事务结束后设置失效
+        let path: String = sqlx::query_scalar("select current_setting('search_path')")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_ne!(path, "pg_temp");
+    }
+}
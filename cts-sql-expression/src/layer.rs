@@ -0,0 +1,194 @@
+use crate::error::CtsError;
+use crate::error::CtsError::ParamError;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// # 虚拟图层
+/// > 通过视图或者sql模板定义的查询对象，查询时作为子查询使用，过滤、排序、分页、统计以及输出格式和普通表一致，
+/// > 客户端只能传递声明过的参数，不能提交sql
+/// ```json
+/// {
+///     "name": "road_stat",
+///     "source": {"sql": "select * from public.road where region = ${region} and level >= ${level}"},
+///     "params": [
+///         {"name": "region", "type": "string"},
+///         {"name": "level", "type": "integer", "default": 1}
+///     ]
+/// }
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VirtualLayer {
+    /// 图层名称，查询时作为表名使用
+    pub name: String,
+    pub source: LayerSource,
+    /// 模板参数声明
+    #[serde(default)]
+    pub params: Vec<LayerParam>,
+}
+
+/// 图层数据来源
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "lowercase")]
+pub enum LayerSource {
+    /// 视图名称，例如 public.v_road
+    View(String),
+    /// sql模板，参数格式 `${name}`
+    Sql(String),
+}
+
+/// 模板参数声明
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LayerParam {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub param_type: LayerParamType,
+    /// 默认值，没有默认值时为必填参数
+    pub default: Option<Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LayerParamType {
+    String,
+    Integer,
+    Double,
+    Bool,
+}
+
+impl VirtualLayer {
+    pub fn new_view(name: &str, view: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            source: LayerSource::View(view.to_string()),
+            params: Vec::new(),
+        }
+    }
+
+    pub fn new_sql(name: &str, sql: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            source: LayerSource::Sql(sql.to_string()),
+            params: Vec::new(),
+        }
+    }
+
+    /// 声明模板参数
+    /// @param name 参数名称
+    /// @param param_type 参数类型
+    /// @param default 默认值
+    pub fn with_param(
+        mut self,
+        name: &str,
+        param_type: LayerParamType,
+        default: Option<Value>,
+    ) -> Self {
+        self.params.push(LayerParam {
+            name: name.to_string(),
+            param_type,
+            default,
+        });
+        self
+    }
+
+    /// 生成子查询sql，模板参数按声明的类型转换成sql值
+    /// @param values 请求参数
+    pub fn render(&self, values: Option<&Map<String, Value>>) -> Result<String, CtsError> {
+        let template = match &self.source {
+            LayerSource::View(view) => return Ok(format!("select * from {view}")),
+            LayerSource::Sql(sql) => sql,
+        };
+        // 不允许传递未声明的参数
+        if let Some(values) = values {
+            if let Some(name) = values
+                .keys()
+                .find(|key| !self.params.iter().any(|param| &param.name == *key))
+            {
+                return Err(ParamError(format!(
+                    "参数错误，图层{}不支持参数{name}",
+                    self.name
+                )));
+            }
+        }
+        let mut sql = String::new();
+        let mut rest = template.as_str();
+        while let Some(start) = rest.find("${") {
+            sql.push_str(&rest[..start]);
+            let end = rest[start..]
+                .find('}')
+                .ok_or(ParamError(format!("图层{}模板格式错误", self.name)))?;
+            let name = &rest[start + 2..start + end];
+            let param = self
+                .params
+                .iter()
+                .find(|param| param.name == name)
+                .ok_or(ParamError(format!("图层{}模板参数{name}未声明", self.name)))?;
+            let value = values
+                .and_then(|values| values.get(name))
+                .or(param.default.as_ref())
+                .ok_or(ParamError(format!(
+                    "参数错误，图层{}缺少参数{name}",
+                    self.name
+                )))?;
+            sql.push_str(&param.to_sql(value)?);
+            rest = &rest[start + end + 1..];
+        }
+        sql.push_str(rest);
+        Ok(sql)
+    }
+}
+
+impl LayerParam {
+    /// 按声明的类型转换成sql值，类型不一致时返回错误
+    fn to_sql(&self, value: &Value) -> Result<String, CtsError> {
+        let sql = match (self.param_type, value) {
+            (_, Value::Null) => Some("NULL".to_string()),
+            (LayerParamType::String, Value::String(data)) => {
+                Some(format!("'{}'", data.replace('\'', "''")))
+            }
+            (LayerParamType::Integer, Value::Number(data)) => data.as_i64().map(|v| v.to_string()),
+            (LayerParamType::Double, Value::Number(data)) => data
+                .as_f64()
+                .filter(|v| v.is_finite())
+                .map(|v| v.to_string()),
+            (LayerParamType::Bool, Value::Bool(data)) => Some(data.to_string()),
+            _ => None,
+        };
+        sql.ok_or(ParamError(format!(
+            "参数错误，参数{}类型应该是{:?}",
+            self.name, self.param_type
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_render() {
+        let layer = VirtualLayer::new_sql(
+            "road_stat",
+            "select * from public.road where region = ${region} and level >= ${level}",
+        )
+        .with_param("region", LayerParamType::String, None)
+        .with_param("level", LayerParamType::Integer, Some(json!(1)));
+
+        let values = json!({"region": "east'"});
+        let sql = layer.render(values.as_object()).unwrap();
+        assert_eq!(
+            sql,
+            "select * from public.road where region = 'east''' and level >= 1"
+        );
+        // 缺少必填参数
+        assert!(layer.render(None).is_err());
+        // 类型错误
+        let values = json!({"region": "east", "level": "1 or 1=1"});
+        assert!(layer.render(values.as_object()).is_err());
+        // 未声明参数
+        let values = json!({"region": "east", "other": 1});
+        assert!(layer.render(values.as_object()).is_err());
+    }
+}
@@ -4,3 +4,18 @@ pub mod error;
 pub mod expression;
 pub mod convert;
 pub mod config;
+pub mod metadata;
+pub mod layer;
+pub mod ast;
+pub mod policy;
+pub mod tenant;
+pub mod dialect;
+pub mod limits;
+pub mod validate;
+pub mod cql;
+pub mod timezone;
+pub mod audit;
+#[cfg(feature = "response")]
+pub mod ogc;
+#[cfg(feature = "schema")]
+pub mod schema;
@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+use sqlx::PgConnection;
+
+use crate::error::CtsError;
+use crate::error::CtsError::{DatabaseError, ParamError, TimeoutError};
+use crate::request::PageParam;
+
+/// 没有配置时的默认分页大小
+pub const DEFAULT_PAGE_SIZE: i32 = 10;
+
+/// # 查询限制
+/// > 限制单次查询的代价，避免一个请求拖垮数据库
+/// ```json
+/// {"defaultPageSize": 20, "maxPageSize": 500, "maxRows": 10000, "statementTimeout": 30000}
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryLimits {
+    /// 请求没有设置 pageSize 时使用的分页大小，默认 10
+    pub default_page_size: Option<i32>,
+    /// 最大分页大小
+    pub max_page_size: Option<i32>,
+    /// 不分页查询最多返回的行数，超过时返回错误
+    pub max_rows: Option<i64>,
+    /// 单条语句超时时间，单位毫秒
+    pub statement_timeout: Option<u64>,
+}
+
+impl QueryLimits {
+    /// 分页大小为0时使用默认分页大小
+    pub fn normalize_page(&self, page: &mut PageParam) {
+        if page.page_size == 0 {
+            page.page_size = self.default_page_size.unwrap_or(DEFAULT_PAGE_SIZE);
+        }
+    }
+
+    /// 检查分页参数
+    pub fn check_page(&self, page: &PageParam) -> Result<(), CtsError> {
+        if page.page < 1 {
+            return Err(ParamError("参数错误，page必须大于0".to_string()));
+        }
+        if page.page_size < 1 {
+            return Err(ParamError("参数错误，pageSize必须大于0".to_string()));
+        }
+        if page.offset.is_some_and(|offset| offset < 0) {
+            return Err(ParamError("参数错误，offset不能小于0".to_string()));
+        }
+        match self.max_page_size {
+            Some(max) if page.page_size > max => {
+                Err(ParamError(format!("参数错误，pageSize不能超过{max}")))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// 不分页查询的 limit 语句，多查询一行用于判断是否超过限制
+    pub fn unpaged_limit(&self) -> Option<String> {
+        self.max_rows.map(|max| format!(" LIMIT {}", max + 1))
+    }
+
+    /// 检查不分页查询的结果行数
+    pub fn check_rows(&self, rows: usize) -> Result<(), CtsError> {
+        match self.max_rows {
+            Some(max) if rows as i64 > max => {
+                Err(ParamError(format!("查询结果超过{max}条，请使用分页查询")))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// 转换查询错误，语句超时时返回明确的提示，其他数据库错误不返回详细信息
+    pub fn query_error(&self, err: sqlx::Error) -> CtsError {
+        let timeout = err
+            .as_database_error()
+            .and_then(|data| data.code())
+            .is_some_and(|code| code == "57014");
+        match (timeout, self.statement_timeout) {
+            (true, Some(ms)) => TimeoutError(format!("查询超时，执行时间超过{ms}毫秒")),
+            _ => DatabaseError(err),
+        }
+    }
+}
+
+/// 设置当前事务的 statement_timeout，事务结束后恢复
+/// @param conn 已经开启事务的连接
+/// @param timeout 超时时间，单位毫秒
+pub async fn set_statement_timeout(
+    conn: &mut PgConnection,
+    timeout: u64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("select set_config('statement_timeout', $1, true)")
+        .bind(timeout.to_string())
+        .execute(conn)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page() {
+        let limits = QueryLimits {
+            default_page_size: Some(20),
+            max_page_size: Some(100),
+            max_rows: Some(1000),
+            statement_timeout: None,
+        };
+        let mut page = PageParam {
+            page: 1,
+            page_size: 0,
+            offset: None,
+        };
+        limits.normalize_page(&mut page);
+        assert_eq!(page.page_size, 20);
+        assert!(limits.check_page(&page).is_ok());
+        page.page_size = 101;
+        assert!(limits.check_page(&page).is_err());
+        page.page_size = 10;
+        page.page = 0;
+        assert!(limits.check_page(&page).is_err());
+        assert_eq!(limits.unpaged_limit().unwrap(), " LIMIT 1001");
+        assert!(limits.check_rows(1001).is_err());
+    }
+}
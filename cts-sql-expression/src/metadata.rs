@@ -0,0 +1,338 @@
+pub mod cache;
+pub mod field;
+
+use crate::config::ExpressionConfig;
+use crate::error::CtsError;
+use crate::error::CtsError::{DatabaseError, ParamError};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Pool, Postgres};
+
+/// 数据表信息
+#[derive(Debug, Serialize, Deserialize, Clone, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct TableInfo {
+    /// 表名称
+    pub name: String,
+    /// 表类型，BASE TABLE、VIEW 等
+    pub table_type: String,
+    /// 表注释
+    pub description: Option<String>,
+}
+
+/// 表字段信息，来源 information_schema.columns 以及 geometry_columns
+#[derive(Debug, Serialize, Deserialize, Clone, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnInfo {
+    /// 字段名称
+    pub name: String,
+    /// 数据类型，例如 character varying、USER-DEFINED
+    pub data_type: String,
+    /// 数据库类型名称，例如 varchar、geometry、_int4
+    pub udt_name: String,
+    /// 是否可以为空
+    pub nullable: bool,
+    /// 默认值表达式
+    pub default_value: Option<String>,
+    /// 字符最大长度
+    pub max_length: Option<i32>,
+    /// 字段注释
+    pub description: Option<String>,
+    /// 是否是主键
+    #[sqlx(default)]
+    pub primary_key: bool,
+    /// 空间类型，例如 POINT、MULTIPOLYGON
+    #[sqlx(default)]
+    pub geometry_type: Option<String>,
+    /// 空间参考
+    #[sqlx(default)]
+    pub srid: Option<i32>,
+}
+
+impl ColumnInfo {
+    /// 创建字段信息，其他属性为默认值
+    pub fn new(name: &str, udt_name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            data_type: udt_name.to_string(),
+            udt_name: udt_name.to_string(),
+            nullable: true,
+            default_value: None,
+            max_length: None,
+            description: None,
+            primary_key: false,
+            geometry_type: None,
+            srid: None,
+        }
+    }
+
+    /// 是否是空间字段
+    pub fn is_spatial(&self) -> bool {
+        is_spatial(&self.udt_name)
+    }
+}
+
+/// 是否是空间类型，支持 geometry、geography、raster
+pub fn is_spatial(udt_name: &str) -> bool {
+    matches!(udt_name, "geometry" | "geography" | "raster")
+}
+
+/// 将空间字段转换成 geometry 表达式，geography 强制转换，raster 取外包矩形
+/// @param column 字段表达式
+/// @param udt_name 数据库类型名称
+pub fn geometry_expr(column: &str, udt_name: &str) -> String {
+    match udt_name {
+        "geography" => format!("{column}::geometry"),
+        "raster" => format!("st_envelope({column})"),
+        _ => column.to_string(),
+    }
+}
+
+/// 空间字段信息，来源 geometry_columns、geography_columns 以及 raster_columns
+#[derive(Debug, Serialize, Deserialize, Clone, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct GeometryInfo {
+    /// 字段名称
+    pub column: String,
+    /// 数据库类型名称，geometry、geography 或者 raster
+    pub udt_name: String,
+    /// 空间类型，栅格为 RASTER
+    pub geometry_type: String,
+    /// 空间参考
+    pub srid: i32,
+    /// 坐标维度
+    pub dimension: i32,
+}
+
+/// 空间范围
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Extent {
+    pub xmin: f64,
+    pub ymin: f64,
+    pub xmax: f64,
+    pub ymax: f64,
+}
+
+/// # 表元数据
+/// > 管理端根据表元数据生成表单以及图层配置
+/// ```json
+/// {"schema": "public", "name": "road", "description": "道路", "columns": [],
+///  "primaryKeys": ["id"], "geometries": [], "estimatedRows": 1024, "extent": null}
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TableMeta {
+    pub schema: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub columns: Vec<ColumnInfo>,
+    pub primary_keys: Vec<String>,
+    pub geometries: Vec<GeometryInfo>,
+    /// 估算行数，表没有统计信息时为空
+    pub estimated_rows: Option<i64>,
+    /// 第一个空间字段的空间范围
+    pub extent: Option<Extent>,
+}
+
+/// # 元数据查询
+/// > 查询数据库模式下的表、字段、主键、空间字段等信息
+/// ```rust,ignore
+/// let metadata = MetadataQuery::new(&pool, &ExpressionConfig::new(None));
+/// let tables = metadata.tables().await?;
+/// let road = metadata.table("road").await?;
+/// ```
+pub struct MetadataQuery<'a> {
+    pool: &'a Pool<Postgres>,
+    schema: String,
+}
+
+impl<'a> MetadataQuery<'a> {
+    pub fn new(pool: &'a Pool<Postgres>, config: &ExpressionConfig) -> Self {
+        Self {
+            pool,
+            schema: config.schema(),
+        }
+    }
+
+    /// 按模式名称创建
+    pub(crate) fn with_schema(pool: &'a Pool<Postgres>, schema: &str) -> Self {
+        Self {
+            pool,
+            schema: schema.to_string(),
+        }
+    }
+
+    /// 查询模式下的表和视图
+    pub async fn tables(&self) -> Result<Vec<TableInfo>, CtsError> {
+        let query = "SELECT t.table_name::text AS name, t.table_type::text AS table_type, \
+            obj_description(format('%I.%I', t.table_schema, t.table_name)::regclass, 'pg_class') AS description \
+            FROM information_schema.tables t WHERE t.table_schema = $1 ORDER BY t.table_name";
+        sqlx::query_as::<_, TableInfo>(query)
+            .bind(&self.schema)
+            .fetch_all(self.pool)
+            .await
+            .map_err(DatabaseError)
+    }
+
+    /// 查询表字段，包含主键以及空间字段信息
+    /// @param table 表名称
+    pub async fn columns(&self, table: &str) -> Result<Vec<ColumnInfo>, CtsError> {
+        let query = "SELECT c.column_name::text AS name, c.data_type::text AS data_type, \
+            c.udt_name::text AS udt_name, c.is_nullable = 'YES' AS nullable, \
+            c.column_default::text AS default_value, c.character_maximum_length::int AS max_length, \
+            col_description(format('%I.%I', c.table_schema, c.table_name)::regclass, c.ordinal_position::int) AS description \
+            FROM information_schema.columns c WHERE c.table_schema = $1 AND c.table_name = $2 \
+            ORDER BY c.ordinal_position";
+        let mut columns = sqlx::query_as::<_, ColumnInfo>(query)
+            .bind(&self.schema)
+            .bind(table)
+            .fetch_all(self.pool)
+            .await
+            .map_err(DatabaseError)?;
+        if columns.is_empty() {
+            return Err(ParamError(format!("数据表{}.{table}不存在", self.schema)));
+        }
+        // 主键
+        let primary_keys = self.primary_keys(table).await?;
+        // 有空间字段时才查询 geometry_columns，普通库没有安装 PostGIS
+        let geometries = if columns.iter().any(ColumnInfo::is_spatial) {
+            self.geometries(table).await?
+        } else {
+            Vec::new()
+        };
+        for column in columns.iter_mut() {
+            column.primary_key = primary_keys.contains(&column.name);
+            if let Some(geometry) = geometries.iter().find(|item| item.column == column.name) {
+                column.geometry_type = Some(geometry.geometry_type.clone());
+                column.srid = Some(geometry.srid);
+            }
+        }
+        Ok(columns)
+    }
+
+    /// 查询表主键，按主键顺序返回
+    /// @param table 表名称
+    pub async fn primary_keys(&self, table: &str) -> Result<Vec<String>, CtsError> {
+        let query = "SELECT a.attname::text FROM pg_index i \
+            JOIN pg_attribute a ON a.attrelid = i.indrelid AND a.attnum = ANY(i.indkey) \
+            WHERE i.indrelid = format('%I.%I', $1::text, $2::text)::regclass AND i.indisprimary \
+            ORDER BY array_position(i.indkey::int2[], a.attnum)";
+        sqlx::query_scalar::<_, String>(query)
+            .bind(&self.schema)
+            .bind(table)
+            .fetch_all(self.pool)
+            .await
+            .map_err(DatabaseError)
+    }
+
+    /// 查询表空间字段，包含 geometry、geography 以及 raster 字段
+    /// @param table 表名称
+    pub async fn geometries(&self, table: &str) -> Result<Vec<GeometryInfo>, CtsError> {
+        let query = "SELECT f_geometry_column::text AS \"column\", 'geometry' AS udt_name, \
+            type::text AS geometry_type, srid, coord_dimension AS dimension FROM geometry_columns \
+            WHERE f_table_schema = $1 AND f_table_name = $2 \
+            UNION ALL SELECT f_geography_column::text, 'geography', type::text, srid, coord_dimension \
+            FROM geography_columns WHERE f_table_schema = $1 AND f_table_name = $2";
+        let mut geometries = sqlx::query_as::<_, GeometryInfo>(query)
+            .bind(&self.schema)
+            .bind(table)
+            .fetch_all(self.pool)
+            .await
+            .map_err(DatabaseError)?;
+        // 栅格扩展单独安装，存在 raster_columns 时才查询
+        let has_raster =
+            sqlx::query_scalar::<_, bool>("SELECT to_regclass('raster_columns') IS NOT NULL")
+                .fetch_one(self.pool)
+                .await
+                .map_err(DatabaseError)?;
+        if has_raster {
+            let query = "SELECT r_raster_column::text AS \"column\", 'raster' AS udt_name, \
+                'RASTER' AS geometry_type, COALESCE(srid, 0) AS srid, 2 AS dimension FROM raster_columns \
+                WHERE r_table_schema = $1 AND r_table_name = $2";
+            let rasters = sqlx::query_as::<_, GeometryInfo>(query)
+                .bind(&self.schema)
+                .bind(table)
+                .fetch_all(self.pool)
+                .await
+                .map_err(DatabaseError)?;
+            geometries.extend(rasters);
+        }
+        Ok(geometries)
+    }
+
+    /// 估算表行数，读取 pg_class 统计信息，没有统计信息时返回空
+    /// @param table 表名称
+    pub async fn estimated_rows(&self, table: &str) -> Result<Option<i64>, CtsError> {
+        let query = "SELECT c.reltuples::bigint FROM pg_class c \
+            JOIN pg_namespace n ON n.oid = c.relnamespace \
+            WHERE n.nspname = $1 AND c.relname = $2";
+        let rows = sqlx::query_scalar::<_, i64>(query)
+            .bind(&self.schema)
+            .bind(table)
+            .fetch_optional(self.pool)
+            .await
+            .map_err(DatabaseError)?;
+        Ok(rows.filter(|rows| *rows >= 0))
+    }
+
+    /// 计算空间字段范围，表为空时返回空，geography 以及 raster 字段按外包矩形计算
+    /// @param table 表名称
+    /// @param geometry 空间字段
+    pub async fn extent(
+        &self,
+        table: &str,
+        geometry: &GeometryInfo,
+    ) -> Result<Option<Extent>, CtsError> {
+        let query = format!(
+            "SELECT ST_XMin(e)::float8 AS xmin, ST_YMin(e)::float8 AS ymin, \
+            ST_XMax(e)::float8 AS xmax, ST_YMax(e)::float8 AS ymax \
+            FROM (SELECT ST_Extent({}) AS e FROM {}.{}) t WHERE e IS NOT NULL",
+            geometry_expr(&quote_ident(&geometry.column), &geometry.udt_name),
+            quote_ident(&self.schema),
+            quote_ident(table)
+        );
+        sqlx::query_as::<_, Extent>(&query)
+            .fetch_optional(self.pool)
+            .await
+            .map_err(DatabaseError)
+    }
+
+    /// 查询表完整元数据
+    /// @param table 表名称
+    pub async fn table(&self, table: &str) -> Result<TableMeta, CtsError> {
+        let columns = self.columns(table).await?;
+        let description = sqlx::query_scalar::<_, Option<String>>(
+            "SELECT obj_description(format('%I.%I', $1::text, $2::text)::regclass, 'pg_class')",
+        )
+        .bind(&self.schema)
+        .bind(table)
+        .fetch_one(self.pool)
+        .await
+        .map_err(DatabaseError)?;
+        let primary_keys = self.primary_keys(table).await?;
+        let geometries = if columns.iter().any(ColumnInfo::is_spatial) {
+            self.geometries(table).await?
+        } else {
+            Vec::new()
+        };
+        let extent = match geometries.first() {
+            Some(geometry) => self.extent(table, geometry).await?,
+            None => None,
+        };
+        Ok(TableMeta {
+            schema: self.schema.clone(),
+            name: table.to_string(),
+            description,
+            columns,
+            primary_keys,
+            geometries,
+            estimated_rows: self.estimated_rows(table).await?,
+            extent,
+        })
+    }
+}
+
+/// 转义标识符
+pub(crate) fn quote_ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
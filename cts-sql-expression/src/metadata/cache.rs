@@ -0,0 +1,254 @@
+use crate::error::CtsError;
+use crate::error::CtsError::DatabaseError;
+use crate::metadata::{ColumnInfo, MetadataQuery};
+use sqlx::postgres::PgListener;
+use sqlx::{Pool, Postgres};
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::sync::{Arc, LazyLock, RwLock};
+use std::time::{Duration, Instant};
+
+/// 默认缓存时间
+pub const DEFAULT_TTL: Duration = Duration::from_secs(300);
+
+/// 默认通知通道
+pub const NOTIFY_CHANNEL: &str = "cts_metadata";
+
+/// # 表结构变更通知
+/// > 创建事件触发器后，DDL 执行完成以及删除对象时向 [NOTIFY_CHANNEL] 发送变更对象所在的schema；
+/// > 重命名、修改schema后对象名称是新名称，索引、触发器等对象名称与表名无关，无法按表删除缓存，
+/// > 因此 ALTER 语句发送空内容清空缓存，其他语句按schema删除缓存
+pub const NOTIFY_TRIGGER_SQL: &str = r#"
+CREATE OR REPLACE FUNCTION cts_metadata_notify() RETURNS event_trigger AS $$
+DECLARE r record;
+BEGIN
+    IF TG_EVENT = 'sql_drop' THEN
+        FOR r IN SELECT DISTINCT schema_name FROM pg_event_trigger_dropped_objects() LOOP
+            PERFORM pg_notify('cts_metadata', coalesce(r.schema_name, ''));
+        END LOOP;
+    ELSE
+        FOR r IN SELECT DISTINCT schema_name, command_tag LIKE 'ALTER %' AS altered
+                 FROM pg_event_trigger_ddl_commands() LOOP
+            PERFORM pg_notify('cts_metadata', CASE WHEN r.altered THEN '' ELSE coalesce(r.schema_name, '') END);
+        END LOOP;
+    END IF;
+END $$ LANGUAGE plpgsql;
+CREATE EVENT TRIGGER cts_metadata_notify ON ddl_command_end EXECUTE FUNCTION cts_metadata_notify();
+CREATE EVENT TRIGGER cts_metadata_drop ON sql_drop EXECUTE FUNCTION cts_metadata_notify();
+"#;
+
+/// 全局共享缓存，配置中没有指定缓存时使用
+static GLOBAL: LazyLock<Arc<MetadataCache>> =
+    LazyLock::new(|| Arc::new(MetadataCache::new(DEFAULT_TTL)));
+
+struct CacheEntry {
+    columns: Arc<Vec<ColumnInfo>>,
+    loaded_at: Instant,
+}
+
+/// # 表结构缓存
+/// > 按 schema + table 缓存表字段信息，线程安全，可以在多个构造器之间共享
+/// ```rust,ignore
+/// let cache = Arc::new(MetadataCache::new(Duration::from_secs(60)));
+/// let config = ExpressionConfig::new(None).with_metadata_cache(cache.clone());
+/// // 监听表结构变更，需要先执行 NOTIFY_TRIGGER_SQL 创建事件触发器
+/// tokio::spawn(async move { cache.listen(&pool, NOTIFY_CHANNEL).await });
+/// ```
+pub struct MetadataCache {
+    ttl: Duration,
+    entries: RwLock<HashMap<(String, String), CacheEntry>>,
+}
+
+impl Debug for MetadataCache {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let entries = self.entries.read().unwrap_or_else(|err| err.into_inner());
+        f.debug_struct("MetadataCache")
+            .field("ttl", &self.ttl)
+            .field("entries", &entries.len())
+            .finish()
+    }
+}
+
+impl Default for MetadataCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_TTL)
+    }
+}
+
+impl MetadataCache {
+    /// 创建缓存
+    /// @param ttl 缓存时间，为0时不缓存
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// 全局共享缓存
+    pub fn global() -> Arc<MetadataCache> {
+        GLOBAL.clone()
+    }
+
+    /// 读取未过期的缓存
+    pub fn get(&self, schema: &str, table: &str) -> Option<Arc<Vec<ColumnInfo>>> {
+        let entries = self.entries.read().unwrap_or_else(|err| err.into_inner());
+        entries
+            .get(&(schema.to_string(), table.to_string()))
+            .filter(|entry| entry.loaded_at.elapsed() < self.ttl)
+            .map(|entry| entry.columns.clone())
+    }
+
+    /// 写入缓存
+    pub fn insert(
+        &self,
+        schema: &str,
+        table: &str,
+        columns: Vec<ColumnInfo>,
+    ) -> Arc<Vec<ColumnInfo>> {
+        let columns = Arc::new(columns);
+        let mut entries = self.entries.write().unwrap_or_else(|err| err.into_inner());
+        entries.insert(
+            (schema.to_string(), table.to_string()),
+            CacheEntry {
+                columns: columns.clone(),
+                loaded_at: Instant::now(),
+            },
+        );
+        columns
+    }
+
+    /// 读取表字段，缓存不存在或者过期时查询数据库
+    /// @param pool 数据库连接池
+    /// @param schema 数据库模式
+    /// @param table 表名称
+    pub async fn columns(
+        &self,
+        pool: &Pool<Postgres>,
+        schema: &str,
+        table: &str,
+    ) -> Result<Arc<Vec<ColumnInfo>>, CtsError> {
+        if let Some(columns) = self.get(schema, table) {
+            return Ok(columns);
+        }
+        let columns = MetadataQuery::with_schema(pool, schema)
+            .columns(table)
+            .await?;
+        Ok(self.insert(schema, table, columns))
+    }
+
+    /// 删除表缓存
+    pub fn invalidate(&self, schema: &str, table: &str) {
+        let mut entries = self.entries.write().unwrap_or_else(|err| err.into_inner());
+        entries.remove(&(schema.to_string(), table.to_string()));
+    }
+
+    /// 删除模式下所有表缓存
+    pub fn invalidate_schema(&self, schema: &str) {
+        let mut entries = self.entries.write().unwrap_or_else(|err| err.into_inner());
+        entries.retain(|(key, _), _| key != schema);
+    }
+
+    /// 清空缓存
+    pub fn clear(&self) {
+        let mut entries = self.entries.write().unwrap_or_else(|err| err.into_inner());
+        entries.clear();
+    }
+
+    /// 按通知内容删除缓存
+    /// > 内容为 `schema.table` 时删除表缓存，`schema` 或者 `schema.table.column` 等其他对象名称删除模式缓存，
+    /// > 空内容或者无法识别schema时清空缓存
+    pub fn invalidate_notify(&self, payload: &str) {
+        let names: Vec<&str> = payload
+            .split('.')
+            .map(|name| name.trim_matches('"'))
+            .collect();
+        // 触发器、策略等对象名称带有 on 子句，无法识别schema
+        if names
+            .iter()
+            .any(|name| name.is_empty() || name.contains(char::is_whitespace))
+        {
+            return self.clear();
+        }
+        match names.as_slice() {
+            [schema, table] => self.invalidate(schema, table),
+            [schema, ..] => self.invalidate_schema(schema),
+            _ => self.clear(),
+        }
+    }
+
+    /// 监听表结构变更通知，收到通知后删除对应缓存，该方法不会返回，需要单独启动任务
+    /// @param pool 数据库连接池
+    /// @param channel 通知通道名称
+    pub async fn listen(&self, pool: &Pool<Postgres>, channel: &str) -> Result<(), CtsError> {
+        let mut listener = PgListener::connect_with(pool)
+            .await
+            .map_err(DatabaseError)?;
+        listener.listen(channel).await.map_err(DatabaseError)?;
+        loop {
+            match listener.try_recv().await {
+                Ok(Some(notification)) => self.invalidate_notify(notification.payload()),
+                // 连接断开后重连，期间可能丢失通知，清空缓存
+                Ok(None) => self.clear(),
+                Err(err) => return Err(DatabaseError(err)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(name: &str) -> ColumnInfo {
+        ColumnInfo {
+            name: name.to_string(),
+            data_type: "text".to_string(),
+            udt_name: "text".to_string(),
+            nullable: true,
+            default_value: None,
+            max_length: None,
+            description: None,
+            primary_key: false,
+            geometry_type: None,
+            srid: None,
+        }
+    }
+
+    #[test]
+    fn test_invalidate() {
+        let cache = MetadataCache::default();
+        cache.insert("public", "road", vec![column("name")]);
+        cache.insert("public", "river", vec![column("name")]);
+        cache.insert("gis", "road", vec![column("name")]);
+        assert!(cache.get("public", "road").is_some());
+
+        cache.invalidate_notify("public.road");
+        assert!(cache.get("public", "road").is_none());
+        assert!(cache.get("public", "river").is_some());
+
+        // 字段、索引等对象删除模式缓存
+        cache.invalidate_notify("public.road_name_idx.name");
+        assert!(cache.get("public", "river").is_none());
+        assert!(cache.get("gis", "road").is_some());
+
+        cache.insert("public", "road", vec![column("name")]);
+        cache.invalidate_notify("gis");
+        assert!(cache.get("gis", "road").is_none());
+        assert!(cache.get("public", "road").is_some());
+
+        // 无法识别schema时清空缓存
+        cache.invalidate_notify("road_trigger on public.road");
+        assert!(cache.get("public", "road").is_none());
+        cache.insert("public", "road", vec![column("name")]);
+        cache.invalidate_notify("");
+        assert!(cache.get("public", "road").is_none());
+    }
+
+    #[test]
+    fn test_ttl() {
+        let cache = MetadataCache::new(Duration::ZERO);
+        cache.insert("public", "road", vec![column("name")]);
+        assert!(cache.get("public", "road").is_none());
+    }
+}
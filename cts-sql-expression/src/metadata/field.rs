@@ -0,0 +1,182 @@
+use crate::expression::GEOMETRY;
+use crate::metadata::{is_spatial, ColumnInfo};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// # 字段描述信息
+/// > 返回结果中每一列的名称、数据库类型、是否可为空、别名(字段注释)以及空间类型信息，
+/// > 前端可以据此渲染表格列或者地图图层
+/// ```json
+/// {"name": "geom", "type": "geometry", "category": "geometry", "nullable": true,
+///  "column": "shape", "geometryType": "MULTIPOLYGON", "srid": 4326}
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldInfo {
+    /// 输出列名称
+    pub name: String,
+    /// 数据库类型名称，小写
+    #[serde(rename = "type")]
+    pub type_name: String,
+    /// 类型分类
+    pub category: FieldCategory,
+    /// 是否可以为空，计算列为空
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nullable: Option<bool>,
+    /// 对应的表字段名称，计算列为空
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<String>,
+    /// 字段别名，读取字段注释
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+    /// 空间类型，例如 POINT、MULTIPOLYGON
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub geometry_type: Option<String>,
+    /// 空间参考
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub srid: Option<i32>,
+}
+
+/// 字段类型分类
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldCategory {
+    String,
+    Integer,
+    Double,
+    Boolean,
+    Date,
+    Time,
+    DateTime,
+    Json,
+    Binary,
+    Geometry,
+    Array,
+    Other,
+}
+
+impl FieldCategory {
+    /// 根据数据库类型名称获取分类
+    /// @param type_name 类型名称，不区分大小写，例如 INT4、timestamptz、_text
+    pub fn from_type(type_name: &str) -> Self {
+        let name = type_name.to_lowercase();
+        if name.starts_with('_') || name.ends_with("[]") {
+            return FieldCategory::Array;
+        }
+        match name.as_str() {
+            "text" | "varchar" | "bpchar" | "char" | "name" | "citext" | "uuid" | "inet"
+            | "cidr" | "macaddr" | "interval" => FieldCategory::String,
+            "int2" | "int4" | "int8" | "oid" => FieldCategory::Integer,
+            "float4" | "float8" | "numeric" | "money" => FieldCategory::Double,
+            "bool" => FieldCategory::Boolean,
+            "date" => FieldCategory::Date,
+            "time" | "timetz" => FieldCategory::Time,
+            "timestamp" | "timestamptz" => FieldCategory::DateTime,
+            "json" | "jsonb" => FieldCategory::Json,
+            "bytea" => FieldCategory::Binary,
+            "geometry" | "geography" | "raster" => FieldCategory::Geometry,
+            _ => FieldCategory::Other,
+        }
+    }
+}
+
+/// 组装返回字段描述信息
+/// @param columns 结果列名称以及类型名称
+/// @param nullable 结果列是否可为空，按列顺序，无法判断时为空
+/// @param sources 输出列名与表字段的对应关系
+/// @param meta 表字段元数据
+pub fn build_fields(
+    columns: &[(String, String)],
+    nullable: &[Option<bool>],
+    sources: &HashMap<String, String>,
+    meta: &[ColumnInfo],
+) -> Vec<FieldInfo> {
+    columns
+        .iter()
+        .enumerate()
+        .map(|(index, (name, type_name))| {
+            let source = sources.get(name).unwrap_or(name);
+            let column = meta.iter().find(|item| &item.name == source).or_else(|| {
+                // 空间字段统一输出为 GEOMETRY 别名
+                (name == GEOMETRY)
+                    .then(|| meta.iter().find(|item| is_spatial(&item.udt_name)))
+                    .flatten()
+            });
+            match column {
+                Some(column) => {
+                    // 空间字段输出时经过了格式转换，类型以表字段为准
+                    let type_name = if is_spatial(&column.udt_name) {
+                        column.udt_name.clone()
+                    } else {
+                        type_name.to_lowercase()
+                    };
+                    FieldInfo {
+                        name: name.clone(),
+                        category: FieldCategory::from_type(&type_name),
+                        type_name,
+                        nullable: Some(column.nullable),
+                        column: Some(column.name.clone()),
+                        alias: column.description.clone(),
+                        geometry_type: column.geometry_type.clone(),
+                        srid: column.srid,
+                    }
+                }
+                None => FieldInfo {
+                    name: name.clone(),
+                    type_name: type_name.to_lowercase(),
+                    category: FieldCategory::from_type(type_name),
+                    nullable: nullable.get(index).copied().flatten(),
+                    column: None,
+                    alias: None,
+                    geometry_type: None,
+                    srid: None,
+                },
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meta(name: &str, udt_name: &str) -> ColumnInfo {
+        ColumnInfo {
+            name: name.to_string(),
+            data_type: udt_name.to_string(),
+            udt_name: udt_name.to_string(),
+            nullable: false,
+            default_value: None,
+            max_length: None,
+            description: None,
+            primary_key: false,
+            geometry_type: None,
+            srid: None,
+        }
+    }
+
+    #[test]
+    fn test_build_fields() {
+        let mut shape = meta("shape", "geometry");
+        shape.geometry_type = Some("POINT".to_string());
+        shape.srid = Some(4326);
+        let mut name = meta("name", "varchar");
+        name.description = Some("名称".to_string());
+        let meta = vec![meta("id", "int4"), name, shape];
+        let columns = vec![
+            ("id".to_string(), "INT4".to_string()),
+            ("title".to_string(), "VARCHAR".to_string()),
+            ("total".to_string(), "INT8".to_string()),
+            (GEOMETRY.to_string(), "TEXT".to_string()),
+        ];
+        let sources = HashMap::from([("title".to_string(), "name".to_string())]);
+        let fields = build_fields(&columns, &[None, None, Some(true), None], &sources, &meta);
+        assert_eq!(fields[0].category, FieldCategory::Integer);
+        assert_eq!(fields[1].column.as_deref(), Some("name"));
+        assert_eq!(fields[1].alias.as_deref(), Some("名称"));
+        assert_eq!(fields[2].nullable, Some(true));
+        assert_eq!(fields[2].column, None);
+        assert_eq!(fields[3].type_name, "geometry");
+        assert_eq!(fields[3].srid, Some(4326));
+    }
+}
@@ -0,0 +1,705 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use http::{header, HeaderMap, StatusCode};
+use response_utils::res::ResResult;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sqlx::{Pool, Postgres};
+
+use crate::config::{ExpressionConfig, QueryMode};
+use crate::cql::json::{is_temporal, temporal};
+use crate::cql::{parse_json, parse_text};
+use crate::error::CtsError::ParamError;
+use crate::error::{CtsError, Locale};
+use crate::expression::sql::SqlBuilder;
+use crate::expression::{CtsValue, Single};
+use crate::limits::QueryLimits;
+use crate::metadata::MetadataQuery;
+use crate::request::{CtsFormat, CtsParam, PageParam};
+
+/// 条目查询中有固定含义的参数，其他参数必须为集合的字段，作为属性等值过滤
+static RESERVED: [&str; 8] = [
+    "f",
+    "limit",
+    "offset",
+    "bbox",
+    "bbox-crs",
+    "datetime",
+    "filter",
+    "filter-lang",
+];
+
+/// # OGC API - Features 服务
+/// > 按 OGC API - Features 提供 `/collections`、`/collections/{id}`、`/collections/{id}/items`
+/// > 以及 `/collections/{id}/items/{fid}` 接口，查询通过 SqlBuilder 执行，
+/// > 配置的强制过滤条件、字段权限以及查询限制同样生效
+/// ```rust,ignore
+/// let router = OgcFeatures::new(pool, ExpressionConfig::new(None))
+///     .with_base_url("https://example.com/ogc")
+///     .with_collection(OgcCollection::new("roads", "road").with_datetime("updated_at"))
+///     .router();
+/// let app = Router::new().nest("/ogc", router);
+/// ```
+/// 条目查询参数
+/// ```txt
+/// f=geojson|json|csv      返回格式，默认 geojson，json 按 ResResult 返回分页结果
+/// limit=10&offset=25      分页，offset 为跳过的条目数
+/// bbox=120,30,121,31      范围过滤，bbox-crs 指定范围坐标系
+/// datetime=2024-01-01/..  时间过滤，需要图层配置时间字段
+/// filter=...              文本查询语言或者 CQL2-JSON，filter-lang=cql2-text|cql2-json
+/// name=xxx                其他参数必须为集合的非空间字段，作为属性等值过滤
+/// ```
+#[derive(Clone)]
+pub struct OgcFeatures {
+    pool: Pool<Postgres>,
+    config: ExpressionConfig,
+    collections: Vec<OgcCollection>,
+    base_url: String,
+}
+
+/// # 要素集合
+/// ```json
+/// {"id": "roads", "table": "road", "title": "道路", "datetime": "updated_at"}
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OgcCollection {
+    /// 集合标识，用于访问路径
+    pub id: String,
+    /// 表名称或者虚拟图层名称
+    pub table: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    /// datetime 参数过滤的时间字段
+    pub datetime: Option<String>,
+}
+
+impl OgcFeatures {
+    /// 创建服务，查询模式固定为空间查询
+    /// @param pool 数据库连接池
+    /// @param config 表达式配置
+    pub fn new(pool: Pool<Postgres>, config: ExpressionConfig) -> Self {
+        let mut config = config;
+        config.query_mode = QueryMode::Spatial;
+        Self {
+            pool,
+            config,
+            collections: vec![],
+            base_url: String::new(),
+        }
+    }
+
+    /// 添加要素集合
+    pub fn with_collection(mut self, collection: OgcCollection) -> Self {
+        self.collections.push(collection);
+        self
+    }
+
+    /// 设置链接地址前缀，没有设置时返回相对路径
+    /// @param base_url 服务地址，例如 `https://example.com/ogc`
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// 生成路由
+    pub fn router(self) -> Router {
+        Router::new()
+            .route("/collections", get(collections))
+            .route("/collections/{id}", get(collection))
+            .route("/collections/{id}/items", get(items))
+            .route("/collections/{id}/items/{fid}", get(item))
+            .with_state(Arc::new(self))
+    }
+
+    fn find(&self, id: &str) -> Option<&OgcCollection> {
+        self.collections.iter().find(|item| item.id == id)
+    }
+
+    fn collection_value(&self, collection: &OgcCollection) -> Value {
+        let href = format!("{}/collections/{}", self.base_url, collection.id);
+        json!({
+            "id": collection.id,
+            "title": collection.title,
+            "description": collection.description,
+            "itemType": "feature",
+            "links": [
+                {"href": href, "rel": "self", "type": "application/json"},
+                {"href": format!("{href}/items"), "rel": "items", "type": "application/geo+json"},
+            ]
+        })
+    }
+}
+
+impl OgcCollection {
+    /// @param id 集合标识
+    /// @param table 表名称或者虚拟图层名称
+    pub fn new(id: &str, table: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            table: table.to_string(),
+            title: None,
+            description: None,
+            datetime: None,
+        }
+    }
+
+    pub fn with_title(mut self, title: &str) -> Self {
+        self.title = Some(title.to_string());
+        self
+    }
+
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    /// 设置 datetime 参数过滤的时间字段
+    pub fn with_datetime(mut self, field: &str) -> Self {
+        self.datetime = Some(field.to_string());
+        self
+    }
+
+    /// 条目查询参数转换成查询参数
+    /// @param query url 查询参数
+    /// @param limits 查询限制，没有 limit 参数时使用默认分页大小
+    /// @param queryables 可以作为属性过滤的字段，其他参数返回错误
+    pub fn items_param(
+        &self,
+        query: &BTreeMap<String, String>,
+        limits: &QueryLimits,
+        queryables: &[String],
+    ) -> Result<CtsParam, CtsError> {
+        let mut param = CtsParam {
+            format: Some(handler_format(query)?),
+            ..Default::default()
+        };
+        // 分页
+        let mut page = PageParam {
+            page: 1,
+            page_size: number(query, "limit")?.unwrap_or(0),
+            offset: number(query, "offset")?,
+        };
+        limits.normalize_page(&mut page);
+        // 页码只用于返回结果，查询按 offset 跳过数据
+        if let Some(offset) = page.offset {
+            page.page = offset / page.page_size + 1;
+        }
+        param.page = Some(page);
+        // 范围
+        if let Some(bbox) = query.get("bbox") {
+            let list = bbox
+                .split(',')
+                .map(|item| item.trim().parse::<f64>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| ParamError("bbox参数错误".to_string()).with_path("bbox"))?;
+            param.bbox = Some(list);
+        }
+        if let Some(crs) = query.get("bbox-crs") {
+            param.bbox_crs = Some(handler_crs(crs).map_err(|err| err.with_path("bbox-crs"))?);
+        }
+        // 过滤条件
+        let mut conditions = Vec::new();
+        if let Some(datetime) = query.get("datetime") {
+            let filter = self
+                .datetime_filter(datetime)
+                .map_err(|err| err.with_path("datetime"))?;
+            conditions.push(CtsValue::Array(filter));
+        }
+        if let Some(filter) = query.get("filter") {
+            let filter = handler_filter(filter, query.get("filter-lang"))
+                .map_err(|err| err.with_path("filter"))?;
+            conditions.push(CtsValue::Array(filter));
+        }
+        for (key, value) in query.iter() {
+            if RESERVED.contains(&key.as_str()) {
+                continue;
+            }
+            if !queryables.contains(key) {
+                return Err(ParamError(format!("不支持的查询参数{key}")).with_path(key));
+            }
+            conditions.push(CtsValue::Array(vec![name("="), name(key), name(value)]));
+        }
+        param.filter = match conditions.len() {
+            0 => None,
+            1 => match conditions.remove(0) {
+                CtsValue::Array(list) => Some(list),
+                data => Some(vec![data]),
+            },
+            _ => {
+                conditions.insert(0, name("and"));
+                Some(conditions)
+            }
+        };
+        Ok(param)
+    }
+
+    // datetime 为时间点或者时间区间，区间两端为空或者 ".." 时表示不限
+    fn datetime_filter(&self, datetime: &str) -> Result<Vec<CtsValue>, CtsError> {
+        let field = self
+            .datetime
+            .as_deref()
+            .ok_or(ParamError("集合没有配置时间字段".to_string()))?;
+        let data = match datetime.split_once('/') {
+            Some((start, end)) => {
+                json!({ "interval": [datetime_bound(start)?, datetime_bound(end)?] })
+            }
+            None if is_temporal(datetime.trim()) => json!({ "timestamp": datetime.trim() }),
+            None => return Err(ParamError("datetime参数错误".to_string())),
+        };
+        temporal("t_intersects", field, &data)
+    }
+}
+
+async fn collections(State(state): State<Arc<OgcFeatures>>) -> Response {
+    let list = state
+        .collections
+        .iter()
+        .map(|item| state.collection_value(item))
+        .collect::<Vec<_>>();
+    ResResult::with_success(json!({
+        "collections": list,
+        "links": [{
+            "href": format!("{}/collections", state.base_url),
+            "rel": "self",
+            "type": "application/json"
+        }]
+    }))
+}
+
+async fn collection(
+    State(state): State<Arc<OgcFeatures>>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    let locale = locale(&headers);
+    let Some(collection) = state.find(&id) else {
+        return not_found("集合不存在");
+    };
+    let mut value = state.collection_value(collection);
+    // 第一个空间字段的范围
+    let metadata = MetadataQuery::new(&state.pool, &state.config);
+    let geometries = match metadata.geometries(&collection.table).await {
+        Ok(data) => data,
+        Err(err) => return err.to_response(locale),
+    };
+    if let Some(geometry) = geometries.first() {
+        match metadata.extent(&collection.table, geometry).await {
+            Ok(Some(extent)) => {
+                value["extent"] = json!({
+                    "spatial": {
+                        "bbox": [[extent.xmin, extent.ymin, extent.xmax, extent.ymax]],
+                        "crs": crs_uri(geometry.srid)
+                    }
+                });
+            }
+            Ok(None) => {}
+            Err(err) => return err.to_response(locale),
+        }
+        value["storageCrs"] = json!(crs_uri(geometry.srid));
+    }
+    ResResult::with_success(value)
+}
+
+async fn items(
+    State(state): State<Arc<OgcFeatures>>,
+    Path(id): Path<String>,
+    Query(query): Query<BTreeMap<String, String>>,
+    headers: HeaderMap,
+) -> Response {
+    let locale = locale(&headers);
+    let Some(collection) = state.find(&id) else {
+        return not_found("集合不存在");
+    };
+    // 有属性过滤参数时才查询表字段
+    let mut queryables = Vec::new();
+    if query.keys().any(|key| !RESERVED.contains(&key.as_str())) {
+        let metadata = MetadataQuery::new(&state.pool, &state.config);
+        match metadata.columns(&collection.table).await {
+            Ok(columns) => {
+                queryables = columns
+                    .into_iter()
+                    .filter(|item| !item.is_spatial())
+                    .map(|item| item.name)
+                    .collect();
+            }
+            Err(err) => return err.to_response(locale),
+        }
+    }
+    let mut param = match collection.items_param(&query, &state.config.limits, &queryables) {
+        Ok(data) => data,
+        Err(err) => return err.to_response(locale),
+    };
+    let format = param.format.unwrap_or(CtsFormat::GeoJson);
+    let limit = param.page.as_ref().map(|page| page.page_size).unwrap_or(0);
+    let offset = param
+        .page
+        .as_ref()
+        .map(|page| page.skip() as i32)
+        .unwrap_or(0);
+    // csv 需要字段名称作为表头
+    if let CtsFormat::CSV = format {
+        param.return_fields = Some(true);
+    }
+    let mut builder = SqlBuilder::new(
+        &state.pool,
+        collection.table.clone(),
+        state.config.clone(),
+        param,
+    );
+    let value = match builder.query().await {
+        Ok(data) => data,
+        Err(err) => return err.to_response(locale),
+    };
+    match format {
+        CtsFormat::GeoJson => {
+            let mut value = with_feature_id(value);
+            let returned = value["features"].as_array().map(Vec::len).unwrap_or(0);
+            let href = format!("{}/collections/{}/items", state.base_url, collection.id);
+            let mut links = vec![
+                json!({"href": link(&href, &query, None), "rel": "self", "type": "application/geo+json"}),
+                json!({"href": format!("{}/collections/{}", state.base_url, collection.id), "rel": "collection", "type": "application/json"}),
+            ];
+            // 返回数量等于分页大小时可能还有下一页
+            if limit > 0 && returned as i32 == limit {
+                let next = link(&href, &query, Some((limit, offset + limit)));
+                links.push(json!({"href": next, "rel": "next", "type": "application/geo+json"}));
+            }
+            value["numberReturned"] = json!(returned);
+            value["links"] = Value::Array(links);
+            geojson_response(value)
+        }
+        CtsFormat::CSV => csv_response(&value),
+        CtsFormat::Json => ResResult::with_success(value),
+    }
+}
+
+async fn item(
+    State(state): State<Arc<OgcFeatures>>,
+    Path((id, fid)): Path<(String, String)>,
+    Query(query): Query<BTreeMap<String, String>>,
+    headers: HeaderMap,
+) -> Response {
+    let locale = locale(&headers);
+    let Some(collection) = state.find(&id) else {
+        return not_found("集合不存在");
+    };
+    let format = match handler_format(&query) {
+        Ok(data) => data,
+        Err(err) => return err.to_response(locale),
+    };
+    let param = CtsParam {
+        format: Some(format),
+        return_fields: matches!(format, CtsFormat::CSV).then_some(true),
+        ..Default::default()
+    };
+    let mut builder = SqlBuilder::new_simplify(
+        &state.pool,
+        collection.table.clone(),
+        state.config.clone(),
+        param,
+        fid,
+    );
+    // 按列表查询，csv 可以返回字段名称
+    let value = match builder.query().await {
+        Ok(data) => data,
+        Err(err) => return err.to_response(locale),
+    };
+    let list = match format {
+        CtsFormat::GeoJson => &value["features"],
+        CtsFormat::CSV => &value["list"],
+        CtsFormat::Json => &value,
+    };
+    if list.as_array().is_none_or(Vec::is_empty) {
+        return not_found("要素不存在");
+    }
+    match format {
+        CtsFormat::GeoJson => {
+            let mut value = with_feature_id(value);
+            let mut feature = value["features"][0].take();
+            let href = format!("{}/collections/{}", state.base_url, collection.id);
+            feature["links"] = json!([
+                {"href": format!("{href}/items/{}", feature["id"].as_str().unwrap_or_default()), "rel": "self", "type": "application/geo+json"},
+                {"href": href, "rel": "collection", "type": "application/json"},
+            ]);
+            geojson_response(feature)
+        }
+        CtsFormat::CSV => csv_response(&value),
+        CtsFormat::Json => ResResult::with_success(value[0].clone()),
+    }
+}
+
+fn datetime_bound(data: &str) -> Result<&str, CtsError> {
+    match data.trim() {
+        "" | ".." => Ok(".."),
+        data if is_temporal(data) => Ok(data),
+        _ => Err(ParamError("datetime参数错误".to_string())),
+    }
+}
+
+// f 参数，默认 geojson
+fn handler_format(query: &BTreeMap<String, String>) -> Result<CtsFormat, CtsError> {
+    match query.get("f").map(|data| data.to_lowercase()).as_deref() {
+        None | Some("geojson") => Ok(CtsFormat::GeoJson),
+        Some("json") => Ok(CtsFormat::Json),
+        Some("csv") => Ok(CtsFormat::CSV),
+        Some(data) => Err(ParamError(format!("不支持的返回格式{data}")).with_path("f")),
+    }
+}
+
+// 大于等于0的整数参数
+fn number(query: &BTreeMap<String, String>, key: &str) -> Result<Option<i32>, CtsError> {
+    match query.get(key) {
+        None => Ok(None),
+        Some(data) => match data.parse::<i32>() {
+            Ok(data) if data >= 0 => Ok(Some(data)),
+            _ => Err(ParamError(format!("{key}参数错误")).with_path(key)),
+        },
+    }
+}
+
+// 坐标系参数，支持 CRS84、EPSG:4326 以及 http://www.opengis.net/def/crs/EPSG/0/4326
+fn handler_crs(crs: &str) -> Result<i32, CtsError> {
+    if crs.ends_with("CRS84") {
+        return Ok(4326);
+    }
+    crs.rsplit(['/', ':'])
+        .next()
+        .and_then(|data| data.parse::<i32>().ok())
+        .ok_or(ParamError("坐标系参数错误".to_string()))
+}
+
+fn handler_filter(filter: &str, lang: Option<&String>) -> Result<Vec<CtsValue>, CtsError> {
+    match lang.map(String::as_str) {
+        None | Some("cql2-text") => parse_text(filter),
+        Some("cql2-json") => {
+            let data: Value = serde_json::from_str(filter)
+                .map_err(|_| ParamError("filter参数必须为JSON格式".to_string()))?;
+            parse_json(&data)
+        }
+        Some(lang) => Err(ParamError(format!("不支持的查询语言{lang}"))),
+    }
+}
+
+fn crs_uri(srid: i32) -> String {
+    match srid {
+        4326 => "http://www.opengis.net/def/crs/OGC/1.3/CRS84".to_string(),
+        _ => format!("http://www.opengis.net/def/crs/EPSG/0/{srid}"),
+    }
+}
+
+// 要素 id 使用 id 字段，GeoJsonConvert 默认使用行号
+fn with_feature_id(mut value: Value) -> Value {
+    if let Some(features) = value["features"].as_array_mut() {
+        for feature in features.iter_mut() {
+            let id = match &feature["properties"]["id"] {
+                Value::Null => continue,
+                Value::String(data) => data.clone(),
+                data => data.to_string(),
+            };
+            feature["id"] = Value::String(id);
+        }
+    }
+    value
+}
+
+// 生成链接，page 为下一页的 (limit, offset)
+fn link(href: &str, query: &BTreeMap<String, String>, page: Option<(i32, i32)>) -> String {
+    let mut query = query.clone();
+    if let Some((limit, offset)) = page {
+        query.insert("limit".to_string(), limit.to_string());
+        query.insert("offset".to_string(), offset.to_string());
+    }
+    if query.is_empty() {
+        return href.to_string();
+    }
+    let text = query
+        .iter()
+        .map(|(key, value)| format!("{}={}", encode(key), encode(value)))
+        .collect::<Vec<_>>()
+        .join("&");
+    format!("{href}?{text}")
+}
+
+// url 参数编码
+fn encode(data: &str) -> String {
+    let mut result = String::new();
+    for byte in data.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                result.push(byte as char)
+            }
+            _ => result.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    result
+}
+
+// 查询结果转换成 csv，第一行为字段名称
+fn to_csv(value: &Value) -> String {
+    let fields = value["fields"]
+        .as_array()
+        .map(|list| {
+            list.iter()
+                .map(|item| item["name"].as_str().unwrap_or_default().to_string())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    let rows = value["list"].as_array().cloned().unwrap_or_default();
+    let mut lines = vec![fields
+        .iter()
+        .map(|item| csv_cell(item))
+        .collect::<Vec<_>>()
+        .join(",")];
+    for row in rows.iter() {
+        let line = row
+            .as_array()
+            .map(|list| {
+                list.iter()
+                    .map(|item| match item {
+                        Value::Null => String::new(),
+                        Value::String(data) => csv_cell(data),
+                        data => csv_cell(&data.to_string()),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(",")
+            })
+            .unwrap_or_default();
+        lines.push(line);
+    }
+    lines.join("\r\n")
+}
+
+fn csv_cell(data: &str) -> String {
+    if data.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", data.replace('"', "\"\""))
+    } else {
+        data.to_string()
+    }
+}
+
+fn name(data: &str) -> CtsValue {
+    CtsValue::Single(Single::String(data.to_string()))
+}
+
+fn locale(headers: &HeaderMap) -> Locale {
+    headers
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|data| data.to_str().ok())
+        .map(Locale::from_accept_language)
+        .unwrap_or_default()
+}
+
+fn not_found(message: &str) -> Response {
+    ResResult::<Value>::with_error_code(message, 40400, StatusCode::NOT_FOUND)
+}
+
+fn geojson_response(value: Value) -> Response {
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "application/geo+json; charset=utf-8")],
+        value.to_string(),
+    )
+        .into_response()
+}
+
+fn csv_response(value: &Value) -> Response {
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "text/csv; charset=utf-8")],
+        to_csv(value),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expression::parse::filter::filter_parse;
+
+    fn query(list: &[(&str, &str)]) -> BTreeMap<String, String> {
+        list.iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_items_param() {
+        let collection = OgcCollection::new("roads", "road").with_datetime("updated_at");
+        let limits = QueryLimits::default();
+        let queryables = vec!["status".to_string(), "level".to_string()];
+        let param = collection
+            .items_param(
+                &query(&[
+                    ("limit", "20"),
+                    ("offset", "40"),
+                    ("bbox", "120,30,121,31"),
+                    ("datetime", "2024-01-01/.."),
+                    ("status", "open"),
+                ]),
+                &limits,
+                &queryables,
+            )
+            .unwrap();
+        let page = param.page.unwrap();
+        assert_eq!((page.page, page.page_size, page.skip()), (3, 20, 40));
+        assert_eq!(param.bbox, Some(vec![120.0, 30.0, 121.0, 31.0]));
+        assert!(matches!(param.format, Some(CtsFormat::GeoJson)));
+        assert_eq!(
+            filter_parse(&param.filter.unwrap()).unwrap().unwrap(),
+            "(updated_at >= '2024-01-01') and (status = 'open')"
+        );
+
+        // offset 不需要是 limit 的整数倍
+        let param = collection
+            .items_param(
+                &query(&[("limit", "20"), ("offset", "5")]),
+                &limits,
+                &queryables,
+            )
+            .unwrap();
+        let page = param.page.unwrap();
+        assert_eq!((page.page, page.skip()), (1, 5));
+        let err = collection
+            .items_param(&query(&[("offset", "-1")]), &limits, &queryables)
+            .unwrap_err();
+        assert_eq!(err.path(), Some("offset"));
+        // 不是字段的参数返回错误
+        let err = collection
+            .items_param(&query(&[("name", "a")]), &limits, &queryables)
+            .unwrap_err();
+        assert_eq!(err.path(), Some("name"));
+        assert_eq!(err.code().http_status(), 400);
+        let err = collection
+            .items_param(&query(&[("datetime", "yesterday")]), &limits, &queryables)
+            .unwrap_err();
+        assert_eq!(err.path(), Some("datetime"));
+        let param = collection
+            .items_param(
+                &query(&[("filter", "level > 2"), ("f", "csv")]),
+                &limits,
+                &queryables,
+            )
+            .unwrap();
+        assert!(matches!(param.format, Some(CtsFormat::CSV)));
+        assert_eq!(param.page.unwrap().page_size, 10);
+    }
+
+    #[test]
+    fn test_csv() {
+        let value = json!({
+            "fields": [{"name": "id"}, {"name": "name"}],
+            "list": [[1, "a,b"], [2, null]]
+        });
+        assert_eq!(to_csv(&value), "id,name\r\n1,\"a,b\"\r\n2,");
+        assert_eq!(
+            link("/items", &query(&[("f", "geojson")]), Some((10, 20))),
+            "/items?f=geojson&limit=10&offset=20"
+        );
+    }
+}
@@ -0,0 +1,397 @@
+use crate::ast::is_field_name;
+use crate::error::CtsError;
+use crate::error::CtsError::{FieldError, ParamError, PermissionError};
+use crate::expression::parse::handler_name;
+use crate::expression::{CtsValue, Single};
+use crate::request::CtsParam;
+use serde::{Deserialize, Serialize};
+
+/// # 字段权限
+/// > 按表声明字段的访问策略，不同角色使用不同的配置：
+/// > hidden 字段不能查询、过滤、排序、分组以及写入；readOnly 字段不能写入；
+/// > masked 字段查询时返回脱敏后的值，不能在查询字段中参与表达式计算，
+/// > 也不能用于过滤、排序、分组以及统计，避免逐位推测出原始值
+/// ```json
+/// [
+///     {"table": "user", "column": "id_card", "access": "hidden"},
+///     {"table": "user", "column": "level", "access": "readOnly"},
+///     {"table": "user", "column": "phone", "access": {"masked": {"keep": {"first": 3, "last": 4}}}}
+/// ]
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldPolicy {
+    /// 表名称，虚拟图层使用图层名称
+    pub table: String,
+    pub column: String,
+    pub access: FieldAccess,
+}
+
+/// 字段访问方式
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum FieldAccess {
+    Hidden,
+    ReadOnly,
+    Masked(MaskRule),
+}
+
+/// 脱敏规则
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum MaskRule {
+    /// 保留前 first 位以及后 last 位，其余字符替换成 *
+    Keep { first: usize, last: usize },
+    /// 数据库脱敏函数名称，例如 public.mask_phone，函数参数为字段值
+    Function(String),
+}
+
+impl FieldPolicy {
+    pub fn hidden(table: &str, column: &str) -> Self {
+        Self::new(table, column, FieldAccess::Hidden)
+    }
+
+    pub fn read_only(table: &str, column: &str) -> Self {
+        Self::new(table, column, FieldAccess::ReadOnly)
+    }
+
+    /// 脱敏字段
+    /// @param rule 脱敏规则
+    pub fn masked(table: &str, column: &str, rule: MaskRule) -> Self {
+        Self::new(table, column, FieldAccess::Masked(rule))
+    }
+
+    fn new(table: &str, column: &str, access: FieldAccess) -> Self {
+        Self {
+            table: table.to_string(),
+            column: column.to_string(),
+            access,
+        }
+    }
+}
+
+impl MaskRule {
+    /// 保留后 last 位
+    pub fn keep_last(last: usize) -> Self {
+        MaskRule::Keep { first: 0, last }
+    }
+
+    /// 生成脱敏表达式，null 值保持为 null
+    /// @param column 字段名称
+    pub fn to_sql(&self, column: &str) -> Result<String, CtsError> {
+        match self {
+            MaskRule::Keep { first, last } => {
+                let text = format!("{column}::text");
+                Ok(format!(
+                    "left({text}, {first}) || repeat('*', greatest(char_length({text}) - {}, 0)) || right({text}, {last})",
+                    first + last
+                ))
+            }
+            MaskRule::Function(name) => {
+                let valid = !name.is_empty()
+                    && name
+                        .split('.')
+                        .all(|item| !item.is_empty() && identifiers(item).eq([item]));
+                if !valid {
+                    return Err(ParamError(format!("脱敏函数名称{name}错误")));
+                }
+                Ok(format!("{name}({column})"))
+            }
+        }
+    }
+}
+
+/// 单个表的字段权限
+#[derive(Debug, Clone, Default)]
+pub struct FieldPolicies(Vec<FieldPolicy>);
+
+impl FieldPolicies {
+    /// 筛选表对应的字段权限
+    /// @param list 所有字段权限
+    /// @param table 表名称
+    pub fn for_table(list: &[FieldPolicy], table: &str) -> Self {
+        Self(
+            list.iter()
+                .filter(|item| item.table == table)
+                .cloned()
+                .collect(),
+        )
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// 字段访问方式，没有声明时返回空
+    pub fn access(&self, column: &str) -> Option<&FieldAccess> {
+        self.0
+            .iter()
+            .find(|item| item.column.eq_ignore_ascii_case(column))
+            .map(|item| &item.access)
+    }
+
+    pub fn is_hidden(&self, column: &str) -> bool {
+        matches!(self.access(column), Some(FieldAccess::Hidden))
+    }
+
+    /// 查询字段表达式，脱敏字段替换成脱敏表达式
+    /// > 存在字段权限时只能查询字段名称，可以带表名，别名只能包含字母、数字以及下划线；
+    /// > 表达式可以通过整行引用、函数或者子查询读取隐藏字段，不能使用
+    ///
+    /// @param field 查询字段
+    /// @param alias 别名
+    pub fn select(&self, field: &str, alias: Option<&str>) -> Result<String, CtsError> {
+        if self.is_empty() {
+            return match alias {
+                Some(alias) => Ok(format!("{field} as {alias}")),
+                None => Ok(field.to_string()),
+            };
+        }
+        let name = field.trim();
+        if name == "*" || name.ends_with(".*") {
+            return Err(FieldError(
+                "存在字段权限限制，查询字段不能使用*".to_string(),
+            ));
+        }
+        // 字段名称不能是表名，表名作为值时返回整行数据
+        let column = name.rsplit('.').next().unwrap_or(name);
+        if !is_field_name(name)
+            || self
+                .0
+                .iter()
+                .any(|item| item.table.eq_ignore_ascii_case(column))
+        {
+            return Err(FieldError(format!(
+                "存在字段权限限制，查询字段只能是字段名称{name}"
+            )));
+        }
+        if let Some(alias) = alias.filter(|data| !is_field_name(data) || data.contains('.')) {
+            return Err(FieldError(format!("别名错误{alias}")));
+        }
+        match self.access(column) {
+            Some(FieldAccess::Hidden) => Err(PermissionError(format!("无权查询字段{column}"))),
+            Some(FieldAccess::Masked(rule)) => Ok(format!(
+                "{} as {}",
+                rule.to_sql(name)?,
+                alias.unwrap_or(column)
+            )),
+            _ => match alias {
+                Some(alias) => Ok(format!("{name} as {alias}")),
+                None => Ok(name.to_string()),
+            },
+        }
+    }
+
+    /// 检查过滤、排序、分组以及统计中使用的字段，隐藏字段以及脱敏字段返回错误
+    /// @param expression 字段名称或者表达式
+    /// @param path 字段在请求参数中的路径
+    fn check_visible(&self, expression: &str, path: &str) -> Result<(), CtsError> {
+        for ident in identifiers(expression) {
+            match self.access(ident) {
+                Some(FieldAccess::Hidden) => {
+                    return Err(PermissionError(format!("无权使用字段{ident}")).with_path(path));
+                }
+                Some(FieldAccess::Masked(_)) => {
+                    return Err(PermissionError(format!(
+                        "脱敏字段{ident}不能用于过滤、排序、分组以及统计"
+                    ))
+                    .with_path(path));
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// 检查过滤条件，错误路径相对于过滤参数
+    pub fn check_filter(&self, filter: &[CtsValue]) -> Result<(), CtsError> {
+        if self.is_empty() || filter.is_empty() {
+            return Ok(());
+        }
+        let ope = handler_name(&filter[0])?.to_lowercase();
+        match ope.as_str() {
+            "or" | "and" | "not" => {
+                for (index, item) in filter.iter().enumerate().skip(1) {
+                    if let CtsValue::Array(list) = item {
+                        self.check_filter(list)
+                            .map_err(|err| err.with_path(&format!("[{index}]")))?;
+                    }
+                }
+                Ok(())
+            }
+            _ => match filter.get(1) {
+                Some(CtsValue::Single(Single::String(field))) => self.check_visible(field, "[1]"),
+                _ => Ok(()),
+            },
+        }
+    }
+
+    /// 检查排序字段，错误路径相对于排序参数
+    pub fn check_order(&self, order: &[CtsValue]) -> Result<(), CtsError> {
+        for (index, item) in order.iter().enumerate() {
+            let (field, path) = match item {
+                CtsValue::Array(list) if !list.is_empty() => (&list[0], format!("[{index}][0]")),
+                data => (data, format!("[{index}]")),
+            };
+            if let CtsValue::Single(Single::String(field)) = field {
+                self.check_visible(field, &path)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 检查分组字段，错误路径相对于分组参数
+    pub fn check_group(&self, fields: &[String]) -> Result<(), CtsError> {
+        fields
+            .iter()
+            .enumerate()
+            .try_for_each(|(index, item)| self.check_visible(item, &format!("[{index}]")))
+    }
+
+    /// 检查统计字段，单个统计函数以及统计函数数组都检查，错误路径相对于统计参数
+    pub fn check_aggregate(&self, aggregate: &[CtsValue]) -> Result<(), CtsError> {
+        match aggregate.first() {
+            // 单个统计函数 [操作符, 字段, 别名]
+            Some(CtsValue::Single(_)) => match aggregate.get(1) {
+                Some(CtsValue::Single(Single::String(field))) => self.check_visible(field, "[1]"),
+                _ => Ok(()),
+            },
+            _ => {
+                for (index, item) in aggregate.iter().enumerate() {
+                    if let CtsValue::Array(list) = item {
+                        self.check_aggregate(list)
+                            .map_err(|err| err.with_path(&format!("[{index}]")))?;
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// 检查请求参数中过滤、排序、分组以及统计使用的字段
+    pub fn check_param(&self, param: &CtsParam) -> Result<(), CtsError> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        if let Some(filter) = &param.filter {
+            self.check_filter(filter)
+                .map_err(|err| err.with_path("filter"))?;
+        }
+        if let Some(order) = &param.order_by {
+            self.check_order(order)
+                .map_err(|err| err.with_path("orderBy"))?;
+        }
+        if let Some(group) = &param.group_by {
+            self.check_group(group)
+                .map_err(|err| err.with_path("groupBy"))?;
+        }
+        if let Some(aggregate) = &param.aggregate {
+            self.check_aggregate(aggregate)
+                .map_err(|err| err.with_path("aggregate"))?;
+        }
+        if let Some(date_trunc) = &param.date_trunc {
+            self.check_visible(&date_trunc.field, ".field")
+                .map_err(|err| err.with_path("dateTrunc"))?;
+        }
+        Ok(())
+    }
+
+    /// 检查写入字段，隐藏字段以及只读字段不能写入
+    /// @param columns 写入的字段名称
+    pub fn check_write<'a>(
+        &self,
+        mut columns: impl Iterator<Item = &'a String>,
+    ) -> Result<(), CtsError> {
+        match columns.find(|item| {
+            matches!(
+                self.access(item),
+                Some(FieldAccess::Hidden | FieldAccess::ReadOnly)
+            )
+        }) {
+            None => Ok(()),
+            Some(column) => Err(PermissionError(format!("无权修改字段{column}"))),
+        }
+    }
+}
+
+// 表达式中的标识符
+fn identifiers(expression: &str) -> impl Iterator<Item = &str> {
+    expression
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .filter(|item| {
+            item.chars()
+                .next()
+                .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policies() {
+        let list = vec![
+            FieldPolicy::hidden("user", "id_card"),
+            FieldPolicy::read_only("user", "level"),
+            FieldPolicy::masked("user", "phone", MaskRule::keep_last(4)),
+            FieldPolicy::hidden("road", "name"),
+        ];
+        let policies = FieldPolicies::for_table(&list, "user");
+        assert_eq!(policies.select("name", None).unwrap(), "name");
+        assert_eq!(
+            policies.select("phone", Some("tel")).unwrap(),
+            "left(phone::text, 0) || repeat('*', greatest(char_length(phone::text) - 4, 0)) \
+             || right(phone::text, 4) as tel"
+        );
+        assert!(policies.select("id_card", None).is_err());
+        assert!(policies.select("upper(phone)", None).is_err());
+        assert!(policies.select("*", None).is_err());
+        assert!(policies
+            .select("user.phone", None)
+            .unwrap()
+            .ends_with(" as phone"));
+        // 别名以及整行引用不能读取隐藏字段
+        assert!(policies.select("name", Some("x, id_card")).is_err());
+        assert!(policies.select("to_jsonb(user)", None).is_err());
+        assert!(policies.select("user", None).is_err());
+        assert!(policies.select("public.user", None).is_err());
+        assert!(policies.select("(select id_card)", None).is_err());
+
+        let filter: Vec<CtsValue> =
+            serde_json::from_str(r#"["or", ["=", "name", "id_card"], ["like", "ID_CARD", "1%"]]"#)
+                .unwrap();
+        let err = policies.check_filter(&filter).unwrap_err();
+        assert_eq!(err.path(), Some("[2][1]"));
+        let filter: Vec<CtsValue> = serde_json::from_str(r#"["=", "name", "id_card"]"#).unwrap();
+        assert!(policies.check_filter(&filter).is_ok());
+
+        let filter: Vec<CtsValue> = serde_json::from_str(r#"["like", "phone", "138%"]"#).unwrap();
+        assert_eq!(
+            policies.check_filter(&filter).unwrap_err().path(),
+            Some("[1]")
+        );
+        let order: Vec<CtsValue> = serde_json::from_str(r#"[["phone", "desc"]]"#).unwrap();
+        assert_eq!(
+            policies.check_order(&order).unwrap_err().path(),
+            Some("[0][0]")
+        );
+        assert!(policies.check_group(&["phone".to_string()]).is_err());
+
+        let aggregate: Vec<CtsValue> =
+            serde_json::from_str(r#"[["count", "name", "n"], ["max", "id_card", "x"]]"#).unwrap();
+        assert_eq!(
+            policies.check_aggregate(&aggregate).unwrap_err().path(),
+            Some("[1][1]")
+        );
+        let aggregate: Vec<CtsValue> = serde_json::from_str(r#"["max", "phone"]"#).unwrap();
+        assert!(policies.check_aggregate(&aggregate).is_err());
+        let aggregate: Vec<CtsValue> =
+            serde_json::from_str(r#"["count", "name", "phone"]"#).unwrap();
+        assert!(policies.check_aggregate(&aggregate).is_ok());
+
+        let data = ["name".to_string(), "level".to_string()];
+        assert!(policies.check_write(data.iter()).is_err());
+        assert!(policies.check_write(data[..1].iter()).is_ok());
+    }
+}
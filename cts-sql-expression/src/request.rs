@@ -1,18 +1,48 @@
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use crate::expression::{CtsValue, Single};
 
 #[derive(Debug, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct CtsParam {
+    /// 过滤条件
+    #[cfg_attr(feature = "schema", schemars(with = "Option<crate::schema::CtsFilter>"))]
     pub filter: Option<Vec<CtsValue>>,
+    /// 分组字段
     pub group_by: Option<Vec<String>>,
+    /// 查询字段
+    #[cfg_attr(feature = "schema", schemars(with = "Option<Vec<crate::schema::CtsOutField>>"))]
     pub out_fields: Option<Vec<CtsValue>>,
+    /// 统计函数
+    #[cfg_attr(feature = "schema", schemars(with = "Option<crate::schema::CtsAggregateParam>"))]
     pub aggregate: Option<Vec<CtsValue>>,
+    /// 是否返回空间字段
     pub return_geometry: Option<bool>,
+    /// 排序字段
+    #[cfg_attr(feature = "schema", schemars(with = "Option<Vec<crate::schema::CtsOrder>>"))]
     pub order_by: Option<Vec<CtsValue>>,
+    /// 分页参数，没有时不分页
     pub page: Option<PageParam>,
+    /// 空间字段格式
     pub geo_format: Option<GeometryFormat>,
+    /// 返回格式
     pub format: Option<CtsFormat>,
+    /// 是否返回字段描述信息
+    pub return_fields: Option<bool>,
+    /// 返回的空间字段，支持 geometry、geography、raster，
+    /// 指定后每个空间字段按字段名称返回，没有指定时返回第一个空间字段，名称为 geom
+    pub geometry: Option<Vec<String>>,
+    /// 范围过滤 [minx, miny, maxx, maxy]，作用于第一个返回的空间字段
+    pub bbox: Option<Vec<f64>>,
+    /// 范围坐标系，默认 4326
+    pub bbox_crs: Option<i32>,
+    /// 关联表查询，关联名称需要在配置中声明
+    pub join: Option<Vec<JoinParam>>,
+    /// 虚拟图层参数
+    pub params: Option<Map<String, Value>>,
+    /// 按时间截断分组，截断后的时间同时作为查询字段以及分组字段
+    pub date_trunc: Option<DateTruncParam>,
 }
 
 
@@ -20,8 +50,12 @@ impl CtsParam {
     pub fn search_param(mut self) -> Self {
         self.group_by = None;
         self.aggregate = None;
+        self.date_trunc = None;
         self.return_geometry = None;
         self.geo_format = None;
+        self.geometry = None;
+        self.bbox = None;
+        self.bbox_crs = None;
         self
     }
 
@@ -30,7 +64,9 @@ impl CtsParam {
         self.filter = None;
         self.group_by = None;
         self.aggregate = None;
+        self.date_trunc = None;
         self.page = None;
+        self.bbox = None;
         //重新设置条件
         self.filter = Some(vec![
             CtsValue::Single(Single::String("=".to_string())),
@@ -42,16 +78,68 @@ impl CtsParam {
 }
 
 
+/// 关联表查询参数
+/// ```json
+/// {"name": "attachments", "outFields": ["name", "url"]}
+/// ```
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct JoinParam {
+    pub name: String,
+    /// 返回的关联表字段，默认返回配置允许的全部字段
+    pub out_fields: Option<Vec<String>>,
+}
+
+/// 时间截断分组参数
+/// ```json
+/// {"field": "created_at", "unit": "day", "alias": "created_day"}
+/// ```
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct DateTruncParam {
+    /// 时间字段
+    pub field: String,
+    /// 截断单位 second、minute、hour、day、week、month、quarter、year
+    pub unit: String,
+    /// 别名，默认与字段名称相同
+    pub alias: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct PageParam {
+    /// 页码，从1开始
+    #[serde(default = "default_page")]
     pub page: i32,
+    /// 分页大小，没有设置时使用配置的默认分页大小
+    #[serde(default)]
     pub page_size: i32,
+    /// 跳过的行数，设置后不再按页码计算偏移量
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offset: Option<i32>,
 }
 
+fn default_page() -> i32 {
+    1
+}
 
+impl PageParam {
+    /// 查询的偏移量，没有设置 offset 时按页码计算
+    pub fn skip(&self) -> i64 {
+        match self.offset {
+            Some(offset) => offset as i64,
+            None => (self.page as i64 - 1) * self.page_size as i64,
+        }
+    }
+}
 
-#[derive(Debug, Serialize, Deserialize)]
+
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum CtsFormat {
     Json,
@@ -60,6 +148,7 @@ pub enum CtsFormat {
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum GeometryFormat {
     GeoJson,
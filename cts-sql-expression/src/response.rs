@@ -2,9 +2,13 @@ use crate::convert::csv::CsvConvert;
 use crate::convert::geojson::GeoJsonConvert;
 use crate::convert::json::JsonConvert;
 use crate::convert::PgRowConvert;
+use crate::error::CtsError;
+use crate::expression::GEOMETRY;
 use crate::request::CtsFormat;
+use cts_pgrow::SerializeOptions;
 use serde_json::Value;
 use sqlx::postgres::PgRow;
+use std::sync::Arc;
 
 #[derive(Debug)]
 pub enum CtsResult {
@@ -23,18 +27,44 @@ pub struct PageValue {
 }
 
 impl CtsResult {
-    pub fn to_value(self, format: CtsFormat) -> Value {
+    pub fn to_value(self, format: CtsFormat) -> Result<Value, CtsError> {
+        self.to_value_with_options(format, &Arc::new(SerializeOptions::default()))
+    }
+
+    /// 按序列化配置转换查询结果
+    /// @param format 结果格式
+    /// @param options 序列化配置
+    pub fn to_value_with_options(
+        self,
+        format: CtsFormat,
+        options: &Arc<SerializeOptions>,
+    ) -> Result<Value, CtsError> {
+        self.to_value_with_geometry(format, options, &[GEOMETRY.to_string()])
+    }
+
+    /// 按序列化配置以及空间字段名称转换查询结果
+    /// @param format 结果格式
+    /// @param options 序列化配置
+    /// @param geometry 空间字段名称，GeoJson 格式第一个字段作为要素空间数据
+    pub fn to_value_with_geometry(
+        self,
+        format: CtsFormat,
+        options: &Arc<SerializeOptions>,
+        geometry: &[String],
+    ) -> Result<Value, CtsError> {
         // 配置转换器
         let row_convert: Box<dyn PgRowConvert> = match format {
             // 匹配 类型是GeoJson 并且空间字段不为空
-            CtsFormat::GeoJson => Box::new(GeoJsonConvert),
+            CtsFormat::GeoJson => Box::new(GeoJsonConvert {
+                geometry: geometry.to_vec(),
+            }),
             CtsFormat::CSV => Box::new(CsvConvert),
             _ => Box::new(JsonConvert),
         };
-        row_convert.convert(self)
+        row_convert.convert(self, options)
     }
 
-    pub fn to_json(self) -> Value {
+    pub fn to_json(self) -> Result<Value, CtsError> {
         self.to_value(CtsFormat::Json)
     }
 }
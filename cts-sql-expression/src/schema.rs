@@ -0,0 +1,271 @@
+use schemars::gen::{SchemaGenerator, SchemaSettings};
+use schemars::schema::{RootSchema, Schema};
+use schemars::JsonSchema;
+use serde_json::{json, Value};
+
+use crate::expression::parse::aggregate::OPERATORS;
+use crate::expression::parse::filter::temporal::UNITS;
+use crate::expression::parse::order::ORDERS;
+use crate::request::CtsParam;
+
+/// 比较操作符
+static COMPARE_OPERATORS: [&str; 6] = [">", "<", ">=", "<=", "=", "!="];
+/// 逻辑操作符
+static LOGIC_OPERATORS: [&str; 3] = ["and", "or", "not"];
+
+/// # 查询参数 JSON Schema
+/// > 根据 CtsParam 生成 JSON Schema (draft-07)，过滤、字段、统计、排序这些数组语法由下面的类型描述，
+/// > 元组数组使用 draft-07 的 `items` 数组写法，嵌入 OpenAPI 3.1 时需要把 jsonSchemaDialect 设置为 draft-07
+/// ```rust
+/// use cts_sql_expression::schema::{cts_param_schema, openapi_components};
+///
+/// let schema = serde_json::to_value(cts_param_schema()).unwrap();
+/// assert!(schema["definitions"]["CtsFilter"].is_object());
+/// let components = openapi_components();
+/// assert_eq!(
+///     components["schemas"]["CtsParam"]["properties"]["filter"]["anyOf"][0]["$ref"],
+///     "#/components/schemas/CtsFilter"
+/// );
+/// ```
+pub fn cts_param_schema() -> RootSchema {
+    SchemaSettings::draft07()
+        .into_generator()
+        .into_root_schema_for::<CtsParam>()
+}
+
+/// OpenAPI components，包含 CtsParam 以及引用的全部类型，可以直接合并到服务的 OpenAPI 文档
+/// ```json
+/// {"schemas": {"CtsParam": {...}, "CtsFilter": {...}}}
+/// ```
+pub fn openapi_components() -> Value {
+    let mut generator = SchemaSettings::draft07()
+        .with(|settings| settings.definitions_path = "#/components/schemas/".to_string())
+        .into_generator();
+    generator.subschema_for::<CtsParam>();
+    json!({ "schemas": generator.take_definitions() })
+}
+
+/// 过滤条件
+/// ```txt
+/// [express,field,value]
+/// ["or",["=",field,value],["=",field2,value]]
+/// ["in",field,[value1,value2]]
+/// ["between",field,value1,value2]
+/// ["like",field,"%aaaa"]
+/// ["is null",field]
+/// ["during",field,"2024-01-01","2024-02-01"]
+/// ```
+pub struct CtsFilter;
+
+impl JsonSchema for CtsFilter {
+    fn schema_name() -> String {
+        "CtsFilter".to_string()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        let filter = reference::<CtsFilter>(generator);
+        let field = json!({ "type": "string", "minLength": 1, "description": "字段名称" });
+        let scalar = json!({ "type": ["string", "number", "boolean"] });
+        let not_empty = json!({
+            "anyOf": [{ "type": "string", "minLength": 1 }, { "type": ["number", "boolean"] }]
+        });
+        let range = json!({ "type": ["string", "number"] });
+        let datetime = json!({ "type": "string", "description": "ISO 8601 格式的日期或者时间" });
+        schema(json!({
+            "description": "过滤条件，第一项为操作符，操作符不区分大小写",
+            "oneOf": [
+                tuple(json!([{ "enum": COMPARE_OPERATORS }, field, scalar]), 3, Some(3)),
+                {
+                    "type": "array",
+                    "items": [{ "enum": LOGIC_OPERATORS }],
+                    "additionalItems": filter,
+                    "minItems": 2
+                },
+                tuple(
+                    json!([
+                        { "enum": ["in", "not in"] },
+                        field,
+                        { "anyOf": [not_empty, { "type": "array", "items": not_empty }] }
+                    ]),
+                    3,
+                    Some(3)
+                ),
+                tuple(
+                    json!([{ "enum": ["between", "not between"] }, field, range, range]),
+                    4,
+                    Some(4)
+                ),
+                tuple(
+                    json!([{ "const": "like" }, field, { "type": "string", "minLength": 1 }]),
+                    3,
+                    Some(3)
+                ),
+                tuple(json!([{ "enum": ["is null", "is not null"] }, field]), 2, Some(2)),
+                tuple(
+                    json!([
+                        { "const": "intersects" },
+                        field,
+                        { "type": "string", "description": "WKT格式的空间对象" },
+                        { "type": "integer", "minimum": 1, "description": "坐标系，默认4326" }
+                    ]),
+                    3,
+                    Some(4)
+                ),
+                tuple(json!([{ "enum": ["before", "after"] }, field, datetime]), 3, Some(3)),
+                tuple(
+                    json!([{ "const": "during" }, field, datetime, datetime]),
+                    4,
+                    Some(4)
+                ),
+                tuple(
+                    json!([
+                        { "const": "last" },
+                        field,
+                        { "type": "integer", "minimum": 1 },
+                        { "enum": UNITS }
+                    ]),
+                    4,
+                    Some(4)
+                ),
+            ]
+        }))
+    }
+}
+
+/// 查询字段，字段名称或者 [字段, 别名]
+pub struct CtsOutField;
+
+impl JsonSchema for CtsOutField {
+    fn schema_name() -> String {
+        "CtsOutField".to_string()
+    }
+
+    fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+        let name = json!({ "type": "string" });
+        schema(json!({
+            "description": "查询字段，字段名称或者 [字段, 别名]",
+            "oneOf": [name, tuple(json!([name, name]), 1, Some(2))]
+        }))
+    }
+}
+
+/// 统计函数 [操作符, 字段] 或者 [操作符, 字段, 别名]
+pub struct CtsAggregate;
+
+impl JsonSchema for CtsAggregate {
+    fn schema_name() -> String {
+        "CtsAggregate".to_string()
+    }
+
+    fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+        let name = json!({ "type": "string" });
+        schema(tuple(
+            json!([{ "enum": OPERATORS }, name, name]),
+            2,
+            Some(3),
+        ))
+    }
+}
+
+/// 统计参数，单个统计函数或者统计函数数组
+pub struct CtsAggregateParam;
+
+impl JsonSchema for CtsAggregateParam {
+    fn schema_name() -> String {
+        "CtsAggregateParam".to_string()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        let aggregate = reference::<CtsAggregate>(generator);
+        schema(json!({
+            "description": "统计参数，单个统计函数或者统计函数数组",
+            "oneOf": [aggregate, { "type": "array", "items": aggregate, "minItems": 1 }]
+        }))
+    }
+}
+
+/// 排序字段，字段名称或者 [字段, asc|desc]
+pub struct CtsOrder;
+
+impl JsonSchema for CtsOrder {
+    fn schema_name() -> String {
+        "CtsOrder".to_string()
+    }
+
+    fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+        let name = json!({ "type": "string" });
+        let orders: Vec<String> = ORDERS
+            .iter()
+            .flat_map(|item| [item.to_string(), item.to_uppercase()])
+            .collect();
+        schema(json!({
+            "description": "排序字段，字段名称或者 [字段, asc|desc]",
+            "oneOf": [name, tuple(json!([name, { "enum": orders }]), 2, Some(2))]
+        }))
+    }
+}
+
+// 元组数组
+fn tuple(items: Value, min: usize, max: Option<usize>) -> Value {
+    let mut data = json!({ "type": "array", "items": items, "minItems": min });
+    if let Some(max) = max {
+        data["maxItems"] = json!(max);
+        data["additionalItems"] = json!(false);
+    }
+    data
+}
+
+// 生成类型引用，递归类型需要通过引用描述
+fn reference<T: JsonSchema>(generator: &mut SchemaGenerator) -> Value {
+    serde_json::to_value(generator.subschema_for::<T>()).unwrap_or_default()
+}
+
+fn schema(data: Value) -> Schema {
+    serde_json::from_value(data).unwrap_or(Schema::Bool(true))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expression::parse::filter::filter_parse;
+    use crate::expression::CtsValue;
+
+    #[test]
+    fn test_operators() {
+        // schema 中的操作符必须都能被解析器识别
+        let schema = serde_json::to_value(cts_param_schema()).unwrap();
+        let filter = &schema["definitions"]["CtsFilter"]["oneOf"];
+        for item in filter.as_array().unwrap() {
+            let first = &item["items"][0];
+            let operators = match first.get("const") {
+                Some(data) => vec![data.clone()],
+                None => first["enum"].as_array().unwrap().clone(),
+            };
+            let len = item["minItems"].as_u64().unwrap() as usize;
+            for ope in operators {
+                let mut data = match ope.as_str().unwrap() {
+                    "before" | "after" | "during" => {
+                        vec![
+                            json!(ope),
+                            json!("field"),
+                            json!("2024-01-01"),
+                            json!("2024-02-01"),
+                        ]
+                    }
+                    "last" => vec![json!(ope), json!("field"), json!(7), json!("day")],
+                    _ => vec![json!(ope), json!("field"), json!("POINT(1 2)"), json!("b")],
+                };
+                if LOGIC_OPERATORS.contains(&ope.as_str().unwrap()) {
+                    data = vec![json!(ope), json!(["is null", "field"])];
+                }
+                data.truncate(len);
+                let filter: Vec<CtsValue> = serde_json::from_value(json!(data)).unwrap();
+                assert!(filter_parse(&filter).is_ok(), "{ope}");
+            }
+        }
+        assert_eq!(
+            schema["properties"]["orderBy"]["items"]["$ref"],
+            "#/definitions/CtsOrder"
+        );
+    }
+}
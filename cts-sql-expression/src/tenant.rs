@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+use serde_json::{Map, Value};
+use sqlx::PgConnection;
+
+/// # 多租户
+/// > 每个租户对应一个数据库schema，通过请求上下文(请求头、JWT声明)识别租户，
+/// > 识别出的schema需要在 ExpressionConfig 的租户白名单中
+/// ```rust
+/// use std::sync::Arc;
+/// use cts_sql_expression::config::ExpressionConfig;
+/// use cts_sql_expression::tenant::{HeaderTenantResolver, TenantContext};
+///
+/// let config = ExpressionConfig::new_normal(None)
+///     .with_tenant_resolver(Arc::new(HeaderTenantResolver::new("x-tenant")))
+///     .with_tenant_schemas(vec!["tenant_a".to_string()]);
+/// let context = TenantContext::default().with_header("X-Tenant", "tenant_a");
+/// assert_eq!(config.resolve_tenant(&context).unwrap().schema(), "tenant_a");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TenantContext {
+    /// 请求头，名称统一转换成小写
+    pub headers: HashMap<String, String>,
+    /// JWT声明
+    pub claims: Map<String, Value>,
+}
+
+impl TenantContext {
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.insert(name.to_lowercase(), value.to_string());
+        self
+    }
+
+    pub fn with_claims(mut self, claims: Map<String, Value>) -> Self {
+        self.claims = claims;
+        self
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .get(&name.to_lowercase())
+            .map(|item| item.as_str())
+    }
+}
+
+/// 租户识别，返回租户对应的schema，无法识别时返回空
+pub trait TenantResolver: Debug + Send + Sync {
+    fn resolve(&self, context: &TenantContext) -> Option<String>;
+}
+
+/// 从请求头中读取租户schema
+#[derive(Debug, Clone)]
+pub struct HeaderTenantResolver {
+    pub header: String,
+}
+
+impl HeaderTenantResolver {
+    pub fn new(header: &str) -> Self {
+        Self {
+            header: header.to_string(),
+        }
+    }
+}
+
+impl TenantResolver for HeaderTenantResolver {
+    fn resolve(&self, context: &TenantContext) -> Option<String> {
+        context
+            .header(&self.header)
+            .map(|item| item.trim().to_string())
+            .filter(|item| !item.is_empty())
+    }
+}
+
+/// 从JWT声明中读取租户schema
+#[derive(Debug, Clone)]
+pub struct ClaimTenantResolver {
+    pub claim: String,
+}
+
+impl ClaimTenantResolver {
+    pub fn new(claim: &str) -> Self {
+        Self {
+            claim: claim.to_string(),
+        }
+    }
+}
+
+impl TenantResolver for ClaimTenantResolver {
+    fn resolve(&self, context: &TenantContext) -> Option<String> {
+        match context.claims.get(&self.claim) {
+            Some(Value::String(data)) if !data.is_empty() => Some(data.to_string()),
+            _ => None,
+        }
+    }
+}
+
+/// 设置当前事务的 search_path，事务结束后恢复，连接归还连接池后不会影响其他租户
+/// @param conn 已经开启事务的连接
+/// @param schema 租户schema
+pub async fn set_search_path(conn: &mut PgConnection, schema: &str) -> Result<(), sqlx::Error> {
+    sqlx::query("select set_config('search_path', $1, true)")
+        .bind(schema)
+        .execute(conn)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_resolve() {
+        let claims = json!({"tenant": "tenant_b"});
+        let context = TenantContext::default()
+            .with_header("X-Tenant", " tenant_a ")
+            .with_claims(claims.as_object().unwrap().clone());
+        assert_eq!(
+            HeaderTenantResolver::new("x-tenant").resolve(&context),
+            Some("tenant_a".to_string())
+        );
+        assert_eq!(
+            ClaimTenantResolver::new("tenant").resolve(&context),
+            Some("tenant_b".to_string())
+        );
+        assert_eq!(ClaimTenantResolver::new("org").resolve(&context), None);
+    }
+}
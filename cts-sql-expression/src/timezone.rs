@@ -0,0 +1,114 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use chrono::{FixedOffset, Local, Offset, SecondsFormat, Utc};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::CtsError;
+use crate::error::CtsError::ParamError;
+
+/// # 时区
+/// > 固定偏移时区，用于生成审计字段以及 date_trunc 分组，配置格式为 `+08:00`、`-0530`、`UTC`
+/// ```rust
+/// use cts_sql_expression::timezone::TimeZone;
+///
+/// let timezone: TimeZone = "+08:00".parse().unwrap();
+/// assert_eq!(timezone.to_sql(), "INTERVAL '+08:00'");
+/// assert!(timezone.now().ends_with("+08:00"));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeZone(FixedOffset);
+
+impl TimeZone {
+    pub fn utc() -> Self {
+        Self(Utc.fix())
+    }
+
+    /// 服务器本地时区
+    pub fn local() -> Self {
+        Self(Local::now().offset().fix())
+    }
+
+    pub fn offset(&self) -> FixedOffset {
+        self.0
+    }
+
+    /// 当前时间，ISO 8601 格式，例如 `2024-01-01T08:00:00.000+08:00`
+    pub fn now(&self) -> String {
+        Utc::now()
+            .with_timezone(&self.0)
+            .to_rfc3339_opts(SecondsFormat::Millis, false)
+    }
+
+    /// sql 中的时区，使用 interval 避免 postgres 按 POSIX 规则反转偏移方向
+    pub fn to_sql(&self) -> String {
+        format!("INTERVAL '{self}'")
+    }
+}
+
+impl FromStr for TimeZone {
+    type Err = CtsError;
+
+    fn from_str(data: &str) -> Result<Self, Self::Err> {
+        let error = || ParamError(format!("时区格式错误{data}"));
+        let data = data.trim();
+        if data.eq_ignore_ascii_case("utc") || data == "Z" {
+            return Ok(Self::utc());
+        }
+        let (sign, rest) = match data.split_at_checked(1) {
+            Some(("+", rest)) => (1, rest),
+            Some(("-", rest)) => (-1, rest),
+            _ => return Err(error()),
+        };
+        let digits = rest.replace(':', "");
+        if !digits.chars().all(|item| item.is_ascii_digit()) {
+            return Err(error());
+        }
+        let (hour, minute) = match digits.len() {
+            2 => (&digits[..], "0"),
+            4 => (&digits[..2], &digits[2..]),
+            _ => return Err(error()),
+        };
+        let hour = hour.parse::<i32>().map_err(|_| error())?;
+        let minute = minute.parse::<i32>().map_err(|_| error())?;
+        if hour > 14 || minute > 59 {
+            return Err(error());
+        }
+        FixedOffset::east_opt(sign * (hour * 3600 + minute * 60))
+            .map(Self)
+            .ok_or_else(error)
+    }
+}
+
+impl Display for TimeZone {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Serialize for TimeZone {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for TimeZone {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = String::deserialize(deserializer)?;
+        data.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timezone() {
+        assert_eq!("+0800".parse::<TimeZone>().unwrap().to_string(), "+08:00");
+        assert_eq!("-05:30".parse::<TimeZone>().unwrap().to_string(), "-05:30");
+        assert_eq!("UTC".parse::<TimeZone>().unwrap().to_string(), "+00:00");
+        assert!("Asia/Shanghai".parse::<TimeZone>().is_err());
+        assert!("+25:00".parse::<TimeZone>().is_err());
+    }
+}
@@ -0,0 +1,217 @@
+use crate::error::CtsError;
+use crate::error::CtsError::{FilterError, GroupError, ParamError, ValidationError};
+use crate::expression::parse::aggregate::handler_item;
+use crate::expression::parse::date_trunc::DateTruncParse;
+use crate::expression::parse::field::handler_cts_value;
+use crate::expression::parse::filter::filter_parse;
+use crate::expression::parse::handler_name;
+use crate::expression::parse::order::handler_order;
+use crate::expression::{CtsValue, SqlParse};
+use crate::request::CtsParam;
+
+/// # 请求参数校验
+/// > 在生成sql之前检查整个 CtsParam，一次返回全部问题，每个问题都带有参数路径，例如 `filter[2][1]`。
+/// > 校验不需要数据库连接，也可以单独用于检查保存的过滤条件。字段权限以及查询限制依赖配置，不在这里检查
+/// ```rust
+/// use cts_sql_expression::request::CtsParam;
+/// use cts_sql_expression::validate::ParamValidator;
+///
+/// let param: CtsParam = serde_json::from_str(
+///     r#"{"filter": ["and", ["is null", "a", "b"], ["between", 1, 2]], "orderBy": [["id", "up"]]}"#,
+/// )
+/// .unwrap();
+/// let errors = ParamValidator(&param).errors();
+/// let paths: Vec<_> = errors.iter().filter_map(|item| item.path()).collect();
+/// assert_eq!(paths, vec!["filter[1][2]", "filter[2]", "orderBy[0][1]"]);
+/// ```
+pub struct ParamValidator<'a>(pub &'a CtsParam);
+
+impl ParamValidator<'_> {
+    /// 校验参数，有问题时返回包含全部问题的 ValidationError
+    pub fn validate(&self) -> Result<(), CtsError> {
+        let errors = self.errors();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationError(errors))
+        }
+    }
+
+    /// 返回全部问题，没有问题时返回空数组
+    pub fn errors(&self) -> Vec<CtsError> {
+        let param = self.0;
+        let mut errors = Vec::new();
+        if let Some(filter) = &param.filter {
+            check_filter(filter, "filter", &mut errors);
+        }
+        if let Some(fields) = &param.out_fields {
+            for (index, item) in fields.iter().enumerate() {
+                if let Err(err) = handler_cts_value(item) {
+                    errors.push(err.with_path(&format!("outFields[{index}]")));
+                }
+            }
+        }
+        if let Some(groups) = &param.group_by {
+            for (index, item) in groups.iter().enumerate() {
+                if item.trim().is_empty() {
+                    let err = GroupError("分组字段不能为空".to_string());
+                    errors.push(err.with_path(&format!("groupBy[{index}]")));
+                }
+            }
+        }
+        if let Some(aggregate) = &param.aggregate {
+            check_aggregate(aggregate, &mut errors);
+        }
+        let date_trunc = DateTruncParse {
+            param: &param.date_trunc,
+            timezone: None,
+        };
+        if let Err(err) = date_trunc.parse() {
+            errors.push(err.with_path("dateTrunc"));
+        }
+        if let Some(orders) = &param.order_by {
+            for (index, item) in orders.iter().enumerate() {
+                if let Err(err) = handler_order(item) {
+                    errors.push(err.with_path(&format!("orderBy[{index}]")));
+                }
+            }
+        }
+        if let Some(page) = &param.page {
+            if page.page < 1 {
+                let err = ParamError("参数错误，page必须大于0".to_string());
+                errors.push(err.with_path("page.page"));
+            }
+            // 0 表示使用配置的默认分页大小
+            if page.page_size < 0 {
+                let err = ParamError("参数错误，pageSize不能小于0".to_string());
+                errors.push(err.with_path("page.pageSize"));
+            }
+            if page.offset.is_some_and(|offset| offset < 0) {
+                let err = ParamError("参数错误，offset不能小于0".to_string());
+                errors.push(err.with_path("page.offset"));
+            }
+        }
+        if let Some(bbox) = &param.bbox {
+            if bbox.len() != 4 {
+                let err = ParamError("参数错误，bbox必须为4个数字".to_string());
+                errors.push(err.with_path("bbox"));
+            } else if bbox[0] > bbox[2] || bbox[1] > bbox[3] {
+                let err = ParamError("参数错误，bbox范围错误".to_string());
+                errors.push(err.with_path("bbox"));
+            }
+        }
+        errors
+    }
+}
+
+/// 单独校验过滤条件，错误路径相对于过滤参数，例如 `[2][1]`
+pub fn validate_filter(filter: &[CtsValue]) -> Result<(), CtsError> {
+    let mut errors = Vec::new();
+    check_filter(filter, "", &mut errors);
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(ValidationError(errors))
+    }
+}
+
+// 逻辑条件逐个检查子条件，其他条件按 FilterParse 解析，收集全部错误
+fn check_filter(filter: &[CtsValue], path: &str, errors: &mut Vec<CtsError>) {
+    let ope = match filter.first().map(handler_name) {
+        Some(Ok(ope)) => ope.to_lowercase(),
+        // 长度以及操作符错误交给解析器返回
+        _ => {
+            if let Err(err) = filter_parse(filter) {
+                errors.push(at_path(err, path));
+            }
+            return;
+        }
+    };
+    match ope.as_str() {
+        "or" | "and" | "not" => {
+            if filter.len() < 2 {
+                let err = FilterError("参数错误，参数长度不够".to_string());
+                errors.push(at_path(err, path));
+            }
+            for (index, item) in filter.iter().enumerate().skip(1) {
+                let path = format!("{path}[{index}]");
+                match item {
+                    CtsValue::Array(list) => check_filter(list, &path, errors),
+                    CtsValue::Single(_) => {
+                        errors.push(FilterError("参数错误".to_string()).with_path(&path))
+                    }
+                }
+            }
+        }
+        _ => {
+            if let Err(err) = filter_parse(filter) {
+                errors.push(at_path(err, path));
+            }
+        }
+    }
+}
+
+// 空路径表示相对于过滤参数本身
+fn at_path(err: CtsError, path: &str) -> CtsError {
+    if path.is_empty() {
+        err
+    } else {
+        err.with_path(path)
+    }
+}
+
+fn check_aggregate(aggregate: &[CtsValue], errors: &mut Vec<CtsError>) {
+    match aggregate.first() {
+        None => {
+            errors.push(CtsError::AggregateError("统计参数错误".to_string()).with_path("aggregate"))
+        }
+        // 单个统计函数
+        Some(CtsValue::Single(_)) => {
+            if let Err(err) = handler_item(aggregate) {
+                errors.push(err.with_path("aggregate"));
+            }
+        }
+        Some(CtsValue::Array(_)) => {
+            for (index, item) in aggregate.iter().enumerate() {
+                let result = match item {
+                    CtsValue::Array(list) => handler_item(list).map(|_| ()),
+                    CtsValue::Single(_) => {
+                        Err(CtsError::AggregateError("统计参数错误".to_string()))
+                    }
+                };
+                if let Err(err) = result {
+                    errors.push(err.with_path(&format!("aggregate[{index}]")));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Locale;
+
+    #[test]
+    fn test_validate_filter() {
+        let filter: Vec<CtsValue> = serde_json::from_str(
+            r#"["or", ["=", "a", 1, 2], "b", ["", ["=", "c", 1]], ["in", "d", [1, [2]]]]"#,
+        )
+        .unwrap();
+        let err = validate_filter(&filter).unwrap_err();
+        match &err {
+            ValidationError(list) => {
+                let paths: Vec<_> = list.iter().map(|item| item.path().unwrap()).collect();
+                assert_eq!(paths, vec!["[1][3]", "[2]", "[3][0]", "[4][2][1]"]);
+            }
+            _ => panic!("{err}"),
+        }
+        assert!(err
+            .message(Locale::En)
+            .starts_with("[1][3]: invalid parameter, too many arguments; "));
+
+        let filter: Vec<CtsValue> =
+            serde_json::from_str(r#"["and", ["is null", "a"], ["like", "b", "%c"]]"#).unwrap();
+        assert!(validate_filter(&filter).is_ok());
+    }
+}
@@ -98,6 +98,24 @@ where
             .into_response()
     }
 
+    /// 创建带错误详情的错误对象
+    /// @param err 错误信息
+    /// @param code 错误编码
+    /// @param status_code 错误状态
+    /// @param data 错误详情
+    pub fn with_error_detail(err: &str, code: i32, status_code: StatusCode, data: T) -> Response {
+        (
+            status_code,
+            [(header::CONTENT_TYPE, "application/json; charset=utf-8")],
+            Self {
+                code,
+                data: Some(data),
+                msg: Some(err.to_string()),
+            },
+        )
+            .into_response()
+    }
+
     /// 创建成功对象
     /// @param data 数据参数
     /// @param msg 成功信息参数